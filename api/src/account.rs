@@ -1,17 +1,30 @@
 use crate::{
-    util::{AppState, USERNAME_RE},
+    activities::move_account::{move_follows, Move},
+    domain_policy::resolve_actor,
+    enums::{InstanceRole, RegistrationMode},
+    instance, invite,
+    objects::person::User,
+    throttle::{self, Throttle},
+    util::{AppState, RedisHandle, USERNAME_RE},
     DbHandle,
 };
-use activitypub_federation::{config::Data, http_signatures::generate_actor_keypair};
+use activitypub_federation::{
+    config::Data, http_signatures::generate_actor_keypair, traits::Object,
+};
 use actix_session::Session;
-use actix_web::web;
+use actix_web::{http::header::USER_AGENT, web, HttpRequest};
 use argon2::{
     password_hash::{rand_core::OsRng, SaltString},
-    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    Algorithm, Params, PasswordHash, PasswordHasher, PasswordVerifier,
 };
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use sqlx::{query, PgPool};
+use std::{net::IpAddr, str::FromStr};
 use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
 use validator::Validate;
 
 #[derive(Debug, Error)]
@@ -20,24 +33,114 @@ pub enum UserValidateError {
     Unauthorized(String),
     #[error("User NotFound: {0}")]
     NotFound(String),
+    #[error("User Forbidden: {0}")]
+    Forbidden(String),
     #[error("User InternalServerError: {0}")]
     InternalServerError(String),
 }
 
-pub async fn validate(
-    conn: &PgPool,
-    session: Session,
-) -> Result<(String, String), UserValidateError> {
+/// Days a session lasts since it was last used. Its index row is left out of
+/// the session list and pruned after that too.
+pub const SESSION_TTL_DAYS: i32 = 7;
+
+/// Client details recorded for each signed in session.
+pub struct ClientInfo {
+    pub app: String,
+    pub website: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl ClientInfo {
+    /// Proxy headers are only used for the IP address when the request comes
+    /// from one of `trusted_proxies`. Anyone can send them otherwise.
+    pub fn new(
+        request: &HttpRequest,
+        app: String,
+        website: Option<String>,
+        trusted_proxies: &[IpAddr],
+    ) -> Self {
+        let ip = match request.peer_addr().map(|addr| addr.ip()) {
+            Some(peer) if trusted_proxies.contains(&peer) => request
+                .connection_info()
+                .realip_remote_addr()
+                .map(|ip| ip.to_string()),
+            peer => peer.map(|ip| ip.to_string()),
+        };
+        ClientInfo {
+            app,
+            website,
+            user_agent: request
+                .headers()
+                .get(USER_AGENT)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.to_string()),
+            ip,
+        }
+    }
+}
+
+/// Returns the apub_id of the signed in user. Sessions that have been revoked
+/// or predate the session index are purged.
+pub async fn current_user(conn: &PgPool, session: &Session) -> Result<String, UserValidateError> {
     let id = match session.get::<String>("id") {
         Err(e) => return Err(UserValidateError::InternalServerError(e.to_string())),
         Ok(Some(u)) => u,
         Ok(None) => return Err(UserValidateError::Unauthorized("Not signed in".to_string())),
     };
+    let sid = session
+        .get::<Uuid>("sid")
+        .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?;
+
+    let active = match sid {
+        None => false,
+        Some(sid) => {
+            query!(
+                r#"UPDATE sessions SET last_seen=now()
+               WHERE id=$1 AND lower(apub_id)=$2"#,
+                sid,
+                id.to_lowercase()
+            )
+            .execute(conn)
+            .await
+            .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?
+            .rows_affected()
+                > 0
+        }
+    };
+
+    if !active {
+        session.purge();
+        return Err(UserValidateError::Unauthorized(
+            "Expired session".to_string(),
+        ));
+    }
+
     session.renew();
-    let name = match query!("SELECT apub_id, name FROM users WHERE apub_id=$1", id)
-        .fetch_optional(conn)
-        .await
+    Ok(id)
+}
+
+/// Returns the apub_id and name of the signed in user. Sessions of suspended
+/// users are purged.
+pub async fn validate(
+    conn: &PgPool,
+    session: Session,
+) -> Result<(String, String), UserValidateError> {
+    let id = current_user(conn, &session).await?;
+    let name = match query!(
+        r#"SELECT apub_id, name, suspended_at IS NOT NULL AS "suspended!"
+           FROM users WHERE apub_id=$1"#,
+        id
+    )
+    .fetch_optional(conn)
+    .await
     {
+        Ok(Some(v)) if v.suspended => {
+            session.purge();
+            return Err(UserValidateError::Unauthorized(
+                "Account suspended".to_string(),
+            ));
+        }
         Ok(Some(v)) => (v.apub_id, v.name),
         Ok(None) => {
             return Err(UserValidateError::Unauthorized(
@@ -49,12 +152,48 @@ pub async fn validate(
     Ok(name)
 }
 
+/// Returns the instance role of `apub_id`. Remote and unknown users have no
+/// privileges.
+pub async fn user_role(conn: &PgPool, apub_id: &str) -> Result<InstanceRole, UserValidateError> {
+    let role = query!(
+        "SELECT role FROM users WHERE lower(apub_id)=$1 AND private_key IS NOT NULL",
+        apub_id.to_lowercase()
+    )
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?;
+
+    match role {
+        Some(r) => InstanceRole::from_str(&r.role)
+            .map_err(|e| UserValidateError::InternalServerError(e.to_string())),
+        None => Ok(InstanceRole::User),
+    }
+}
+
+/// Returns the signed in user if their instance role is at least `role`.
+pub async fn require_role(
+    conn: &PgPool,
+    session: &Session,
+    role: InstanceRole,
+) -> Result<(String, InstanceRole), UserValidateError> {
+    let id = current_user(conn, session).await?;
+    let current = user_role(conn, &id).await?;
+    if current < role {
+        return Err(UserValidateError::Forbidden(format!(
+            "Requires the {role} role"
+        )));
+    }
+    Ok((id, current))
+}
+
 #[derive(Debug, Error)]
 pub enum LoginError {
     #[error("LoginError: BadRequest: {0}")]
     BadRequest(String),
     #[error("LoginError: Unauthorized")]
     Unauthorized(FormAuthError),
+    #[error("LoginError: TooManyRequests: retry after {0} seconds")]
+    TooManyRequests(i64),
     #[error("LoginError: InternalServerError: {0}")]
     InternalServerError(String),
 }
@@ -67,15 +206,46 @@ pub enum FormAuthError {
     Password,
     #[error("Username not found")]
     Username,
+    #[error("Invalid email or password")]
+    Credentials,
+    #[error("Account awaiting approval")]
+    Pending,
+    #[error("Account suspended")]
+    Suspended,
+}
+
+/// Hashes `password` with the instance's parameters.
+fn hash_password(state: &AppState, password: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    state
+        .argon2()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|h| h.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether `hash` was made with weaker parameters than the instance's.
+fn needs_rehash(state: &AppState, hash: &PasswordHash) -> bool {
+    let current = &state.argon2_params;
+    match Params::try_from(hash) {
+        Ok(p) => {
+            hash.algorithm != Algorithm::Argon2id.ident()
+                || p.m_cost() < current.m_cost()
+                || p.t_cost() < current.t_cost()
+                || p.p_cost() < current.p_cost()
+        }
+        Err(_) => true,
+    }
 }
 
 pub async fn login(
     pool: &PgPool,
+    redis: &RedisHandle,
+    state: &AppState,
     session: Session,
     email: String,
     password: String,
-    client_app: String,
-    client_website: Option<String>,
+    client: ClientInfo,
 ) -> Result<String, LoginError> {
     #[derive(Deserialize, Validate)]
     struct LoginData {
@@ -91,85 +261,466 @@ pub async fn login(
     let info = LoginData {
         email,
         password,
-        client_app,
-        client_website,
+        client_app: client.app,
+        client_website: client.website,
     };
 
     info.validate()
         .map_err(|e| LoginError::BadRequest(e.to_string()))?;
 
+    let ip = client.ip.as_deref();
+    match throttle::check(redis, &info.email, ip)
+        .await
+        .map_err(LoginError::InternalServerError)?
+    {
+        Throttle::Locked(retry_after) => return Err(LoginError::TooManyRequests(retry_after)),
+        Throttle::Allowed(delay) if !delay.is_zero() => actix_web::rt::time::sleep(delay).await,
+        Throttle::Allowed(_) => (),
+    }
+
     let res = sqlx::query!(
-        "SELECT apub_id, password FROM users WHERE lower(email)=$1",
+        r#"SELECT apub_id, password AS "password!", approved,
+             suspended_at IS NOT NULL AS "suspended!"
+           FROM users WHERE lower(email)=$1"#,
         info.email.to_lowercase()
     )
-    .fetch_one(pool)
+    .fetch_optional(pool)
     .await
-    .map_err(|_| LoginError::Unauthorized(FormAuthError::Email))?;
+    .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
 
-    let password_hash = PasswordHash::new(&res.password)
-        .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
-
-    match PasswordVerifier::verify_password(
-        &Argon2::default(),
-        info.password.as_bytes(),
-        &password_hash,
-    ) {
-        Ok(_) => {
-            session
-                .insert("id", &res.apub_id)
+    let verified = match &res {
+        Some(res) => {
+            let password_hash = PasswordHash::new(&res.password)
                 .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
-            session
-                .insert("client_app", &info.client_app)
+            let verified = PasswordVerifier::verify_password(
+                &state.argon2(),
+                info.password.as_bytes(),
+                &password_hash,
+            )
+            .is_ok();
+            if verified && needs_rehash(state, &password_hash) {
+                let password = hash_password(state, &info.password)
+                    .map_err(LoginError::InternalServerError)?;
+                query!(
+                    "UPDATE users SET password=$2 WHERE apub_id=$1",
+                    res.apub_id,
+                    password
+                )
+                .execute(pool)
+                .await
                 .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
-            session
-                .insert("client_website", &info.client_website)
+            }
+            verified
+        }
+        None => {
+            // Spend the same time as a real verification so that unknown
+            // emails can't be told apart by timing.
+            hash_password(state, &info.password).map_err(LoginError::InternalServerError)?;
+            false
+        }
+    };
+
+    let apub_id = match res.as_ref().map(|r| &r.apub_id) {
+        Some(apub_id) if verified => apub_id,
+        apub_id => {
+            let locked = throttle::failure(redis, &info.email)
+                .await
+                .map_err(LoginError::InternalServerError)?;
+            if let (true, Some(apub_id)) = (locked, apub_id) {
+                log::warn!("Locked {apub_id} after repeated failed logins");
+                add_notice(
+                    pool,
+                    apub_id,
+                    &format!(
+                        "Signing in was locked for {} minutes after repeated failed attempts{}. \
+                         If this wasn't you, consider changing your password.",
+                        throttle::LOCKOUT / 60,
+                        ip.map(|ip| format!(" from {ip}")).unwrap_or_default()
+                    ),
+                )
+                .await
                 .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
-            Ok(res.apub_id)
+            }
+            return Err(LoginError::Unauthorized(if state.uniform_login_errors {
+                FormAuthError::Credentials
+            } else if apub_id.is_none() {
+                FormAuthError::Email
+            } else {
+                FormAuthError::Password
+            }));
         }
-        Err(_) => Err(LoginError::Unauthorized(FormAuthError::Password)),
+    };
+
+    throttle::success(redis, &info.email, ip)
+        .await
+        .map_err(LoginError::InternalServerError)?;
+
+    if !res.as_ref().map_or(false, |r| r.approved) {
+        return Err(LoginError::Unauthorized(FormAuthError::Pending));
+    }
+    if res.as_ref().map_or(false, |r| r.suspended) {
+        return Err(LoginError::Unauthorized(FormAuthError::Suspended));
     }
+
+    query!(
+        r#"DELETE FROM sessions
+           WHERE lower(apub_id)=$1 AND last_seen < now() - make_interval(days => $2)"#,
+        apub_id.to_lowercase(),
+        SESSION_TTL_DAYS
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
+
+    let sid = query!(
+        r#"INSERT INTO sessions
+           (apub_id, client_app, client_website, user_agent, ip)
+           VALUES ($1, $2, $3, $4, $5)
+           RETURNING id"#,
+        apub_id,
+        info.client_app,
+        info.client_website,
+        client.user_agent,
+        client.ip
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| LoginError::InternalServerError(e.to_string()))?
+    .id;
+
+    session
+        .insert("id", apub_id)
+        .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
+    session
+        .insert("sid", sid)
+        .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
+    session
+        .insert("client_app", &info.client_app)
+        .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
+    session
+        .insert("client_website", &info.client_website)
+        .map_err(|e| LoginError::InternalServerError(e.to_string()))?;
+    Ok(apub_id.to_string())
 }
 
-pub enum RegistrationError {
-    Conflict(FormAuthError),
+/// Ends the current session and removes it from the session index.
+pub async fn logout(pool: &PgPool, session: Session) -> Result<(), UserValidateError> {
+    if let Some(sid) = session
+        .get::<Uuid>("sid")
+        .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?
+    {
+        query!("DELETE FROM sessions WHERE id=$1", sid)
+            .execute(pool)
+            .await
+            .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?;
+    }
+    session.purge();
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    pub id: Uuid,
+    pub client_app: String,
+    pub client_website: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub published: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub current: bool,
+}
+
+/// Lists the active sessions of the signed in user, most recently seen first.
+/// Sessions unused for [`SESSION_TTL_DAYS`] have expired and are left out.
+pub async fn list_sessions(
+    pool: &PgPool,
+    session: &Session,
+) -> Result<Vec<SessionInfo>, UserValidateError> {
+    let apub_id = current_user(pool, session).await?;
+    let sid = session
+        .get::<Uuid>("sid")
+        .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?;
+
+    Ok(query!(
+        r#"SELECT id, client_app, client_website, user_agent, ip, published, last_seen
+           FROM sessions
+           WHERE lower(apub_id)=$1 AND last_seen > now() - make_interval(days => $2)
+           ORDER BY last_seen DESC"#,
+        apub_id.to_lowercase(),
+        SESSION_TTL_DAYS
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| SessionInfo {
+        id: row.id,
+        client_app: row.client_app,
+        client_website: row.client_website,
+        user_agent: row.user_agent,
+        ip: row.ip,
+        published: row.published,
+        last_seen: row.last_seen,
+        current: Some(row.id) == sid,
+    })
+    .collect())
+}
+
+/// Revokes one of the signed in user's sessions. The revoked session is
+/// signed out on its next request.
+pub async fn revoke_session(
+    pool: &PgPool,
+    session: &Session,
+    id: Uuid,
+) -> Result<(), UserValidateError> {
+    let apub_id = current_user(pool, session).await?;
+    let res = query!(
+        "DELETE FROM sessions WHERE id=$1 AND lower(apub_id)=$2",
+        id,
+        apub_id.to_lowercase()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?;
+
+    if res.rows_affected() == 0 {
+        return Err(UserValidateError::NotFound("Session not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Revokes every session of `apub_id` except `keep`.
+pub async fn revoke_sessions(
+    pool: &PgPool,
+    apub_id: &str,
+    keep: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    query!(
+        "DELETE FROM sessions WHERE lower(apub_id)=$1 AND id IS DISTINCT FROM $2",
+        apub_id.to_lowercase(),
+        keep
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Signs out all of the signed in user's sessions except the current one.
+pub async fn revoke_other_sessions(
+    pool: &PgPool,
+    session: &Session,
+) -> Result<(), UserValidateError> {
+    let apub_id = current_user(pool, session).await?;
+    let sid = session
+        .get::<Uuid>("sid")
+        .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?;
+    revoke_sessions(pool, &apub_id, sid)
+        .await
+        .map_err(|e| UserValidateError::InternalServerError(e.to_string()))
+}
+
+/// Something that happened to an account that its owner should know about.
+#[derive(Debug, Serialize)]
+pub struct SecurityNotice {
+    pub id: Uuid,
+    pub message: String,
+    pub published: DateTime<Utc>,
+}
+
+/// Leaves a notice for `apub_id`, shown with their sessions until dismissed.
+pub async fn add_notice(pool: &PgPool, apub_id: &str, message: &str) -> Result<(), sqlx::Error> {
+    query!(
+        "INSERT INTO security_notices (apub_id, message) VALUES ($1, $2)",
+        apub_id,
+        message
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Lists the signed in user's notices, newest first.
+pub async fn list_notices(
+    pool: &PgPool,
+    session: &Session,
+) -> Result<Vec<SecurityNotice>, UserValidateError> {
+    let apub_id = current_user(pool, session).await?;
+    query!(
+        r#"SELECT id, message, published FROM security_notices
+           WHERE lower(apub_id)=$1
+           ORDER BY published DESC"#,
+        apub_id.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map(|rows| {
+        rows.into_iter()
+            .map(|row| SecurityNotice {
+                id: row.id,
+                message: row.message,
+                published: row.published,
+            })
+            .collect()
+    })
+    .map_err(|e| UserValidateError::InternalServerError(e.to_string()))
+}
+
+pub async fn dismiss_notice(
+    pool: &PgPool,
+    session: &Session,
+    id: Uuid,
+) -> Result<(), UserValidateError> {
+    let apub_id = current_user(pool, session).await?;
+    let res = query!(
+        "DELETE FROM security_notices WHERE id=$1 AND lower(apub_id)=$2",
+        id,
+        apub_id.to_lowercase()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| UserValidateError::InternalServerError(e.to_string()))?;
+
+    if res.rows_affected() == 0 {
+        return Err(UserValidateError::NotFound("Notice not found".to_string()));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum PasswordChangeError {
+    #[error("PasswordChange: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Wrong password")]
+    WrongPassword,
+    #[error("PasswordChange: BadRequest: {0}")]
     BadRequest(String),
+    #[error("PasswordChange: InternalServerError: {0}")]
     InternalServerError(String),
 }
 
-pub async fn register(
-    state: web::Data<AppState>,
-    pool: Data<DbHandle>,
-    display_name: String,
-    username: String,
-    email: String,
-    password: String,
-) -> Result<(), RegistrationError> {
-    #[derive(Debug, Deserialize, Serialize, Validate)]
-    struct NewUser {
-        display_name: String,
-        #[validate(regex(path = "USERNAME_RE", message = "Invalid username"))]
-        username: String,
-        #[validate(email)]
-        email: String,
+/// Changes the signed in user's password and signs out all of their other
+/// sessions.
+pub async fn change_password(
+    pool: &PgPool,
+    state: &AppState,
+    session: &Session,
+    current: String,
+    new: String,
+) -> Result<(), PasswordChangeError> {
+    #[derive(Validate)]
+    struct NewPassword {
         #[validate(length(min = 8))]
         password: String,
     }
 
-    let info = NewUser {
-        display_name,
-        username,
-        email,
-        password,
-    };
+    let apub_id = current_user(pool, session).await.map_err(|e| match e {
+        UserValidateError::Unauthorized(e) => PasswordChangeError::Unauthorized(e),
+        e => PasswordChangeError::InternalServerError(e.to_string()),
+    })?;
 
+    let info = NewPassword { password: new };
+    info.validate()
+        .map_err(|e| PasswordChangeError::BadRequest(e.to_string()))?;
+
+    let stored = query!(
+        r#"SELECT password AS "password!" FROM users WHERE apub_id=$1"#,
+        apub_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| PasswordChangeError::InternalServerError(e.to_string()))?
+    .password;
+    let password_hash = PasswordHash::new(&stored)
+        .map_err(|e| PasswordChangeError::InternalServerError(e.to_string()))?;
+    PasswordVerifier::verify_password(&state.argon2(), current.as_bytes(), &password_hash)
+        .map_err(|_| PasswordChangeError::WrongPassword)?;
+
+    let password =
+        hash_password(state, &info.password).map_err(PasswordChangeError::InternalServerError)?;
+    query!(
+        "UPDATE users SET password=$2 WHERE apub_id=$1",
+        apub_id,
+        password
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| PasswordChangeError::InternalServerError(e.to_string()))?;
+
+    let sid = session
+        .get::<Uuid>("sid")
+        .map_err(|e| PasswordChangeError::InternalServerError(e.to_string()))?;
+    revoke_sessions(pool, &apub_id, sid)
+        .await
+        .map_err(|e| PasswordChangeError::InternalServerError(e.to_string()))
+}
+
+pub enum RegistrationError {
+    Conflict(FormAuthError),
+    BadRequest(String),
+    Forbidden(String),
+    InternalServerError(String),
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct NewUser {
+    pub display_name: String,
+    #[validate(regex(path = "USERNAME_RE", message = "Invalid username"))]
+    pub username: String,
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(min = 8))]
+    pub password: String,
+    /// Why the user wants to join, for instances that approve registrations.
+    pub reason: Option<String>,
+    pub invite: Option<String>,
+}
+
+/// Creates a local account according to the instance's registration mode.
+/// Returns whether the account can be used right away or is waiting for
+/// approval.
+pub async fn register(
+    state: web::Data<AppState>,
+    pool: Data<DbHandle>,
+    info: NewUser,
+) -> Result<bool, RegistrationError> {
     info.validate()
         .map_err(|e| RegistrationError::BadRequest(e.to_string()))?;
 
     let scheme = &state.scheme;
+    let mode = instance::settings(pool.app_data().as_ref())
+        .await
+        .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?
+        .registration_mode;
+    let invite = info.invite.as_deref().filter(|i| !i.is_empty());
+    let reason = info
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    match (&mode, invite, reason) {
+        (RegistrationMode::Closed, _, _) => {
+            return Err(RegistrationError::Forbidden(
+                "Registrations are closed".to_string(),
+            ))
+        }
+        (RegistrationMode::Invite, None, _) => {
+            return Err(RegistrationError::Forbidden(
+                "An invite is required to register".to_string(),
+            ))
+        }
+        (RegistrationMode::Approval, None, None) => {
+            return Err(RegistrationError::BadRequest(
+                "Please tell us why you want to join".to_string(),
+            ))
+        }
+        _ => (),
+    }
 
     match query!(
         r#"SELECT
-           EXISTS(SELECT 1 FROM users WHERE preferred_username = $1) AS username,
+           EXISTS(SELECT 1 FROM users
+                  WHERE preferred_username = $1 AND private_key IS NOT NULL) AS username,
            EXISTS(SELECT 1 FROM users WHERE email = $2) AS email"#,
         info.username.to_lowercase(),
         info.email.to_lowercase()
@@ -198,18 +749,54 @@ pub async fn register(
         }
     }
 
-    let salt = SaltString::generate(&mut OsRng);
-    let password = Argon2::default()
-        .hash_password(info.password.into_bytes().as_slice(), &salt)
-        .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?
-        .to_string();
+    let password =
+        hash_password(&state, &info.password).map_err(RegistrationError::InternalServerError)?;
     let keypair = generate_actor_keypair()
         .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?;
 
+    let mut tx = pool
+        .app_data()
+        .begin()
+        .await
+        .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?;
+
+    // The first local account owns the instance. Locking the single settings
+    // row makes concurrent registrations wait, so only one of them can see
+    // an empty instance.
+    query!("SELECT 1 AS one FROM instance_settings FOR UPDATE")
+        .fetch_all(&mut tx)
+        .await
+        .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?;
+    let first =
+        !query!(r#"SELECT EXISTS(SELECT 1 FROM users WHERE private_key IS NOT NULL) AS "exists!""#)
+            .fetch_one(&mut tx)
+            .await
+            .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?
+            .exists;
+    let role = if first {
+        InstanceRole::Owner
+    } else {
+        InstanceRole::User
+    };
+
+    let invited_by = match invite {
+        Some(code) => Some(
+            invite::redeem(&mut tx, code)
+                .await
+                .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?
+                .ok_or_else(|| {
+                    RegistrationError::BadRequest("Invalid or expired invite".to_string())
+                })?,
+        ),
+        None => None,
+    };
+    let approved = first || mode != RegistrationMode::Approval || invited_by.is_some();
+
     query!(
         r#"INSERT INTO users
-           (apub_id, preferred_username, name, inbox, outbox, public_key, private_key, email, password)
-           VALUES (lower($1), $2, $3, $4, $5, $6, $7, $8, $9)"#,
+           (apub_id, preferred_username, name, inbox, outbox, public_key, private_key, email, password,
+            approved, reason, invited_by, role)
+           VALUES (lower($1), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)"#,
         format!("{}://{}/user/{}", scheme, pool.domain(), info.username.to_lowercase()),
         info.username,
         info.display_name,
@@ -219,10 +806,134 @@ pub async fn register(
         keypair.private_key,
         info.email,
         password,
+        approved,
+        reason,
+        invited_by,
+        role.to_string(),
     )
-    .execute(pool.app_data().as_ref())
+    .execute(&mut tx)
     .await
     .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?;
 
+    tx.commit()
+        .await
+        .map_err(|e| RegistrationError::InternalServerError(e.to_string()))?;
+
+    Ok(approved)
+}
+
+#[derive(Debug, Error)]
+pub enum AccountMoveError {
+    #[error("AccountMove: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("AccountMove: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("AccountMove: NotFound")]
+    NotFound,
+    #[error("AccountMove: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+async fn session_user(data: &Data<DbHandle>, session: &Session) -> Result<User, AccountMoveError> {
+    let apub_id: Url = current_user(data.app_data().as_ref(), session)
+        .await
+        .map_err(|e| match e {
+            UserValidateError::Unauthorized(e) => AccountMoveError::Unauthorized(e),
+            e => AccountMoveError::InternalServerError(e.to_string()),
+        })?
+        .parse()
+        .map_err(|e: url::ParseError| AccountMoveError::InternalServerError(e.to_string()))?;
+
+    User::read_from_id(apub_id, data)
+        .await
+        .map_err(|e| AccountMoveError::InternalServerError(e.to_string()))?
+        .ok_or_else(|| AccountMoveError::Unauthorized("Expired session".to_string()))
+}
+
+/// Lists another account as an alias of the signed in user. The old account
+/// must be aliased before it can `Move` to this one.
+pub async fn add_alias(
+    data: &Data<DbHandle>,
+    session: Session,
+    alias: String,
+) -> Result<(), AccountMoveError> {
+    let user = session_user(data, &session).await?;
+    let alias: User = resolve_actor(alias.trim_start_matches('@'), data)
+        .await
+        .map_err(|_| AccountMoveError::NotFound)?;
+
+    if alias.apub_id.eq_ignore_ascii_case(&user.apub_id) {
+        return Err(AccountMoveError::BadRequest(
+            "Cannot alias an account to itself".to_string(),
+        ));
+    }
+
+    query!(
+        r#"UPDATE users
+           SET also_known_as = array_append(also_known_as, $2)
+           WHERE apub_id=$1 AND NOT ($2 = ANY(also_known_as))"#,
+        user.apub_id,
+        alias.apub_id
+    )
+    .execute(data.app_data().as_ref())
+    .await
+    .map_err(|e| AccountMoveError::InternalServerError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Moves the signed in user to `target`, which must already list this account
+/// in its `alsoKnownAs`. Remote followers and co-authored novels are notified
+/// with a `Move` activity, local followers follow `target` right away.
+pub async fn move_account(
+    data: &Data<DbHandle>,
+    session: Session,
+    target: String,
+) -> Result<(), AccountMoveError> {
+    let user = session_user(data, &session).await?;
+    let target: User = resolve_actor(target.trim_start_matches('@'), data)
+        .await
+        .map_err(|_| AccountMoveError::NotFound)?;
+
+    if !target
+        .also_known_as
+        .iter()
+        .any(|a| a.eq_ignore_ascii_case(&user.apub_id))
+    {
+        return Err(AccountMoveError::BadRequest(
+            "The new account must list this account as an alias".to_string(),
+        ));
+    }
+
+    let inboxes: Vec<Url> = query!(
+        r#"SELECT users.inbox AS "inbox!"
+           FROM follows JOIN users ON lower(follows.follower)=lower(users.apub_id)
+           WHERE lower(follows.following)=$1 AND users.private_key IS NULL
+           UNION
+           SELECT novels.inbox AS "inbox!"
+           FROM author_roles JOIN novels ON lower(author_roles.id)=lower(novels.apub_id)
+           WHERE lower(author_roles.author)=$1 AND novels.private_key IS NULL"#,
+        user.apub_id.to_lowercase()
+    )
+    .fetch_all(data.app_data().as_ref())
+    .await
+    .map_err(|e| AccountMoveError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .filter_map(|row| row.inbox.parse().ok())
+    .unique()
+    .collect();
+
+    move_follows(&user.apub_id, &target, data)
+        .await
+        .map_err(|e| AccountMoveError::InternalServerError(e.to_string()))?;
+
+    let target_id = target
+        .apub_id
+        .parse()
+        .map_err(|e: url::ParseError| AccountMoveError::InternalServerError(e.to_string()))?;
+    Move::send(&user, target_id, inboxes, data)
+        .await
+        .map_err(|e| AccountMoveError::InternalServerError(e.to_string()))?;
+
     Ok(())
 }
@@ -1,10 +1,10 @@
 use crate::{
+    activities,
     api::chapter::create_chapter,
     objects::{novel::DbNovel, person::User},
     DbHandle,
 };
 use activitypub_federation::{
-    activity_queue::send_activity,
     config::Data,
     fetch::object_id::ObjectId,
     kinds::{activity::AddType, object::ArticleType},
@@ -116,7 +116,7 @@ impl Add {
             id: id.clone(),
         };
         let add = WithContext::new_default(add);
-        send_activity(add, &user, vec![inbox], data).await?;
+        activities::deliver(add, &user, vec![inbox], data).await?;
         Ok(id)
     }
 }
@@ -159,10 +159,6 @@ impl ActivityHandler for Add {
             sensitive: self.object.inner().sensitive,
         };
 
-        create_chapter(chapter, &self.target, data).await?;
-
-        // TODO: Send Announce if accepted. Requires follows to be implemented.
-
-        Ok(())
+        create_chapter(chapter, &self.target, data).await
     }
 }
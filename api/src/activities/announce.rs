@@ -0,0 +1,105 @@
+use crate::{
+    activities::{self, activity_id, follower_inboxes},
+    objects::{actor::DbActor, chapter::Chapter, novel::DbNovel},
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    kinds::activity::AnnounceType,
+    protocol::{context::WithContext, verification::verify_domains_match},
+    traits::ActivityHandler,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::query;
+use url::Url;
+
+/// Sent by novels to their followers for each new chapter. From anyone else
+/// it is a boost of a novel or chapter.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Announce {
+    actor: Url,
+    object: Url,
+    #[serde(rename = "type")]
+    kind: AnnounceType,
+    id: Url,
+}
+
+impl Announce {
+    /// Tells the remote followers of the local `novel` about its new chapter
+    /// `chapter`.
+    pub async fn send(novel: &DbNovel, chapter: Url, data: &Data<DbHandle>) -> anyhow::Result<()> {
+        let inboxes = follower_inboxes(data.app_data(), &novel.apub_id).await?;
+        let activity = Self {
+            actor: novel.apub_id.parse()?,
+            object: chapter,
+            kind: Default::default(),
+            id: activity_id(novel)?,
+        };
+        let activity = WithContext::new_default(activity);
+        activities::deliver(activity, novel, inboxes, data).await
+    }
+}
+
+#[async_trait]
+impl ActivityHandler for Announce {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        &self.actor
+    }
+
+    async fn verify(&self, _data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        verify_domains_match(&self.actor, &self.id)?;
+        Ok(())
+    }
+
+    /// Stores the announced novel or chapter so that it shows up in the
+    /// timelines of local followers. Boosts of anything else are ignored.
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        ObjectId::<DbActor>::from(self.actor.clone())
+            .dereference(data)
+            .await?;
+
+        match ObjectId::<Chapter>::from(self.object.clone())
+            .dereference(data)
+            .await
+        {
+            Ok(chapter) => {
+                ObjectId::<DbNovel>::from(chapter.audience.parse::<Url>()?)
+                    .dereference(data)
+                    .await?;
+                if chapter.audience.eq_ignore_ascii_case(self.actor.as_str()) {
+                    return Ok(());
+                }
+            }
+            Err(_) => {
+                if ObjectId::<DbNovel>::from(self.object.clone())
+                    .dereference(data)
+                    .await
+                    .is_err()
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        query!(
+            r#"INSERT INTO announces (id, actor, object)
+               VALUES ($1, $2, $3)
+               ON CONFLICT DO NOTHING"#,
+            self.id.to_string(),
+            self.actor.to_string(),
+            self.object.to_string()
+        )
+        .execute(data.app_data().as_ref())
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{
+    activities::{self, activity_id},
+    objects::person::User,
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    kinds::activity::BlockType,
+    protocol::context::WithContext,
+    traits::{ActivityHandler, Actor},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::query;
+use url::Url;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Block {
+    pub(crate) actor: ObjectId<User>,
+    pub(crate) object: ObjectId<User>,
+    #[serde(rename = "type")]
+    kind: BlockType,
+    id: Url,
+}
+
+impl Block {
+    pub(crate) fn new(user: &User, target: &User, id: Url) -> anyhow::Result<Self> {
+        Ok(Self {
+            actor: user.apub_id.parse::<Url>()?.into(),
+            object: target.apub_id.parse::<Url>()?.into(),
+            kind: Default::default(),
+            id,
+        })
+    }
+
+    /// Tells the instance of `target` that `user` blocked them.
+    pub async fn send(user: &User, target: &User, data: &Data<DbHandle>) -> anyhow::Result<Url> {
+        let id = activity_id(user)?;
+        let activity = WithContext::new_default(Self::new(user, target, id.clone())?);
+        activities::deliver(activity, user, vec![target.inbox()], data).await?;
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl ActivityHandler for Block {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        self.actor.inner()
+    }
+
+    async fn verify(&self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        self.object.dereference_local(data).await?;
+        Ok(())
+    }
+
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        // Remote blocks aren't stored. Dropping the follows in both directions
+        // is all that is needed to stop delivery between the two.
+        let actor = self.actor.inner().to_string().to_lowercase();
+        let object = self.object.inner().to_string().to_lowercase();
+        query!(
+            r#"DELETE FROM follows
+               WHERE (lower(follower)=$1 AND lower(following)=$2)
+                  OR (lower(follower)=$2 AND lower(following)=$1)"#,
+            actor,
+            object
+        )
+        .execute(data.app_data().as_ref())
+        .await?;
+        Ok(())
+    }
+}
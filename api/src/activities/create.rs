@@ -0,0 +1,72 @@
+use crate::{
+    activities::{self, activity_id, follower_inboxes},
+    objects::{novel::DbNovel, person::User},
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    kinds::activity::CreateType,
+    protocol::{context::WithContext, verification::verify_domains_match},
+    traits::ActivityHandler,
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent by users to their followers when they start a novel.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Create {
+    actor: ObjectId<User>,
+    object: ObjectId<DbNovel>,
+    #[serde(rename = "type")]
+    kind: CreateType,
+    id: Url,
+}
+
+impl Create {
+    /// Tells the remote followers of the local `user` about `novel`.
+    pub async fn send(user: &User, novel: Url, data: &Data<DbHandle>) -> anyhow::Result<()> {
+        let inboxes = follower_inboxes(data.app_data(), &user.apub_id).await?;
+        let activity = Self {
+            actor: user.apub_id.parse::<Url>()?.into(),
+            object: novel.into(),
+            kind: Default::default(),
+            id: activity_id(user)?,
+        };
+        let activity = WithContext::new_default(activity);
+        activities::deliver(activity, user, inboxes, data).await
+    }
+}
+
+#[async_trait]
+impl ActivityHandler for Create {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        self.actor.inner()
+    }
+
+    async fn verify(&self, _data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        verify_domains_match(self.actor.inner(), self.object.inner())?;
+        Ok(())
+    }
+
+    /// Stores the novel so that it shows up in the timelines of local
+    /// followers of its authors.
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        let novel = self.object.dereference(data).await?;
+        novel
+            .authors
+            .iter()
+            .any(|a| a.apub_id.eq_ignore_ascii_case(self.actor.inner().as_str()))
+            .then_some(())
+            .ok_or(anyhow!("Not an author of the novel"))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    activities::{self, activity_id},
+    objects::instance_actor::InstanceActor,
+    report, DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    kinds::activity::FlagType,
+    protocol::{
+        context::WithContext, helpers::deserialize_one_or_many, verification::verify_domains_match,
+    },
+    traits::ActivityHandler,
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Most objects a received `Flag` may list.
+const MAX_OBJECTS: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Flag {
+    /// A user, or the instance actor for forwarded reports.
+    actor: Url,
+    #[serde(deserialize_with = "deserialize_one_or_many")]
+    object: Vec<Url>,
+    #[serde(default)]
+    content: String,
+    #[serde(rename = "type")]
+    kind: FlagType,
+    id: Url,
+}
+
+impl Flag {
+    /// Forwards a report about `object` to the instance it lives on. It is
+    /// sent by the instance actor, so that the reporter stays anonymous.
+    pub async fn send(
+        object: Url,
+        content: String,
+        inbox: Url,
+        scheme: &str,
+        data: &Data<DbHandle>,
+    ) -> anyhow::Result<Url> {
+        let instance = InstanceActor::read(scheme, data).await?;
+        let id = activity_id(&instance)?;
+        let activity = Self {
+            actor: instance.apub_id.parse()?,
+            object: vec![object],
+            content,
+            kind: Default::default(),
+            id: id.clone(),
+        };
+        let activity = WithContext::new_default(activity);
+        activities::deliver(activity, &instance, vec![inbox], data).await?;
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl ActivityHandler for Flag {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        &self.actor
+    }
+
+    async fn verify(&self, _data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        verify_domains_match(&self.actor, &self.id)?;
+        if self.object.is_empty() || self.object.len() > MAX_OBJECTS {
+            return Err(anyhow!("Flag must list 1 to {MAX_OBJECTS} objects"));
+        }
+        Ok(())
+    }
+
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        // Only objects that live on this instance end up in the queue. Anything
+        // else in `object` is context for the origin instance's moderators.
+        for object in &self.object {
+            report::receive_remote(
+                data.app_data(),
+                self.actor.as_str(),
+                object.as_str(),
+                &self.content,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
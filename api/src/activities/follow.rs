@@ -0,0 +1,193 @@
+use crate::{
+    activities::{self, activity_id},
+    objects::{actor::DbActor, person::User},
+    user_filter, DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    kinds::activity::{AcceptType, FollowType, RejectType},
+    protocol::{context::WithContext, verification::verify_urls_match},
+    traits::{ActivityHandler, Actor, Object},
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::query;
+use url::Url;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Follow {
+    pub(crate) actor: ObjectId<User>,
+    /// A user or a novel.
+    pub(crate) object: Url,
+    #[serde(rename = "type")]
+    kind: FollowType,
+    id: Url,
+}
+
+impl Follow {
+    pub(crate) fn new(user: &User, object: Url, id: Url) -> anyhow::Result<Self> {
+        Ok(Self {
+            actor: user.apub_id.parse::<Url>()?.into(),
+            object,
+            kind: Default::default(),
+            id,
+        })
+    }
+
+    /// Asks `target`, a remote user or novel, to accept `user` as a follower.
+    pub async fn send(
+        user: &User,
+        target: &impl Actor,
+        data: &Data<DbHandle>,
+    ) -> anyhow::Result<Url> {
+        let id = activity_id(user)?;
+        let activity = WithContext::new_default(Self::new(user, target.id(), id.clone())?);
+        activities::deliver(activity, user, vec![target.inbox()], data).await?;
+        Ok(id)
+    }
+}
+
+/// The local user or novel a follow is for.
+async fn followed(object: &Url, data: &Data<DbHandle>) -> anyhow::Result<DbActor> {
+    DbActor::read_from_id(object.clone(), data)
+        .await?
+        .filter(|actor| actor.private_key_pem().is_some())
+        .ok_or_else(|| anyhow!("Local actor not found"))
+}
+
+#[async_trait]
+impl ActivityHandler for Follow {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        self.actor.inner()
+    }
+
+    async fn verify(&self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        followed(&self.object, data).await?;
+        Ok(())
+    }
+
+    /// Follows of a novel are rejected when any of its authors blocked the
+    /// follower.
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        let followed = followed(&self.object, data).await?;
+        let follower = self.actor.dereference(data).await?;
+
+        let owners = match &followed {
+            DbActor::User(user) => vec![user.apub_id.clone()],
+            DbActor::Novel(novel) => novel.authors.iter().map(|a| a.apub_id.clone()).collect(),
+        };
+        let mut blocked = false;
+        for owner in owners {
+            blocked |= user_filter::is_blocked(data.app_data(), &owner, self.actor.inner()).await?;
+        }
+        if blocked {
+            let reject = Reject {
+                actor: followed.id(),
+                object: self,
+                kind: Default::default(),
+                id: activity_id(&followed)?,
+            };
+            let reject = WithContext::new_default(reject);
+            return activities::deliver(reject, &followed, vec![follower.inbox()], data).await;
+        }
+
+        query!(
+            "INSERT INTO follows (follower, following) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            follower.apub_id,
+            followed.id().to_string()
+        )
+        .execute(data.app_data().as_ref())
+        .await?;
+
+        let accept = Accept {
+            actor: followed.id(),
+            object: self,
+            kind: Default::default(),
+            id: activity_id(&followed)?,
+        };
+        let accept = WithContext::new_default(accept);
+        activities::deliver(accept, &followed, vec![follower.inbox()], data).await
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Accept {
+    actor: Url,
+    object: Follow,
+    #[serde(rename = "type")]
+    kind: AcceptType,
+    id: Url,
+}
+
+#[async_trait]
+impl ActivityHandler for Accept {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        &self.actor
+    }
+
+    async fn verify(&self, _data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        verify_urls_match(&self.actor, &self.object.object)?;
+        Ok(())
+    }
+
+    /// Local follows are stored when they are sent, so there is nothing left
+    /// to do.
+    async fn receive(self, _data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Reject {
+    actor: Url,
+    object: Follow,
+    #[serde(rename = "type")]
+    kind: RejectType,
+    id: Url,
+}
+
+#[async_trait]
+impl ActivityHandler for Reject {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        &self.actor
+    }
+
+    async fn verify(&self, _data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        verify_urls_match(&self.actor, &self.object.object)?;
+        Ok(())
+    }
+
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        query!(
+            "DELETE FROM follows WHERE lower(follower)=$1 AND lower(following)=$2",
+            self.object.actor.inner().to_string().to_lowercase(),
+            self.actor.to_string().to_lowercase()
+        )
+        .execute(data.app_data().as_ref())
+        .await?;
+        Ok(())
+    }
+}
@@ -1 +1,123 @@
+use crate::{domain_policy, DbHandle};
+use activitypub_federation::{
+    activity_queue::send_activity,
+    config::Data,
+    traits::{ActivityHandler, Actor},
+};
+use chrono::Local;
+use serde::Serialize;
+use sqlx::{query, PgPool};
+use url::Url;
+
 pub mod add;
+pub mod announce;
+pub mod block;
+pub mod create;
+pub mod flag;
+pub mod follow;
+pub mod move_account;
+pub mod undo;
+
+/// Builds an id for an activity sent by the local actor `actor`.
+pub(crate) fn activity_id(actor: &impl Actor) -> anyhow::Result<Url> {
+    Ok(actor
+        .id()
+        .join(&format!("/activities/{}", Local::now().timestamp_nanos()))?)
+}
+
+/// The inboxes of the remote followers of `apub_id`.
+pub(crate) async fn follower_inboxes(pool: &PgPool, apub_id: &str) -> anyhow::Result<Vec<Url>> {
+    query!(
+        r#"SELECT DISTINCT u.inbox FROM follows f
+           JOIN users u ON lower(u.apub_id)=lower(f.follower)
+           WHERE lower(f.following)=$1 AND u.private_key IS NULL"#,
+        apub_id.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| Ok(row.inbox.parse()?))
+    .collect()
+}
+
+/// Queues `activity` for delivery to `inboxes` and counts the deliveries
+/// for the admin dashboard. Inboxes on blocked domains are skipped.
+///
+/// The queue retries deliveries in the background without reporting how
+/// they end, so only activities that couldn't be queued count as failed.
+pub async fn deliver<A, ActorType>(
+    activity: A,
+    actor: &ActorType,
+    inboxes: Vec<Url>,
+    data: &Data<DbHandle>,
+) -> anyhow::Result<()>
+where
+    A: ActivityHandler<Error = anyhow::Error> + Serialize,
+    ActorType: Actor,
+{
+    let mut allowed = Vec::with_capacity(inboxes.len());
+    for inbox in inboxes {
+        if !domain_policy::is_url_blocked(data.app_data(), &inbox, data.domain()).await? {
+            allowed.push(inbox);
+        }
+    }
+    let inboxes = allowed;
+    if inboxes.is_empty() {
+        return Ok(());
+    }
+
+    let activity_id = activity.id().to_string();
+    let kind = serde_json::to_value(&activity)?
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("Unknown")
+        .to_string();
+
+    let count = inboxes.len() as i32;
+    let actor_id = actor.id().to_string();
+
+    let res = send_activity(activity, actor, inboxes, data).await;
+    let (queued, failed) = match res {
+        Ok(_) => (count, 0),
+        Err(_) => (0, count),
+    };
+    let new_hour = query!(
+        r#"INSERT INTO delivery_counts (hour, queued, failed)
+           VALUES (date_trunc('hour', now()), $1, $2)
+           ON CONFLICT (hour) DO UPDATE SET
+             queued=delivery_counts.queued + $1, failed=delivery_counts.failed + $2
+           RETURNING xmax = 0 AS "inserted!""#,
+        queued,
+        failed
+    )
+    .fetch_one(data.app_data().as_ref())
+    .await?
+    .inserted;
+
+    if let Err(e) = &res {
+        query!(
+            r#"INSERT INTO delivery_failures (activity_id, kind, actor, inboxes, error)
+               VALUES ($1, $2, $3, $4, $5)"#,
+            activity_id,
+            kind,
+            actor_id,
+            count,
+            e.to_string()
+        )
+        .execute(data.app_data().as_ref())
+        .await?;
+    }
+
+    // Anything older than a week is dropped once an hour, when the next
+    // count starts.
+    if new_hour {
+        query!("DELETE FROM delivery_counts WHERE hour < now() - interval '7 days'")
+            .execute(data.app_data().as_ref())
+            .await?;
+        query!("DELETE FROM delivery_failures WHERE published < now() - interval '7 days'")
+            .execute(data.app_data().as_ref())
+            .await?;
+    }
+
+    res
+}
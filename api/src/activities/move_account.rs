@@ -0,0 +1,212 @@
+use crate::{
+    activities::{self, activity_id, follow::Follow},
+    objects::person::User,
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    kinds::activity::MoveType,
+    protocol::{context::WithContext, verification::verify_urls_match},
+    traits::{ActivityHandler, Actor, Object},
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::{query, PgPool};
+use url::Url;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Move {
+    actor: ObjectId<User>,
+    object: ObjectId<User>,
+    target: ObjectId<User>,
+    #[serde(rename = "type")]
+    kind: MoveType,
+    id: Url,
+}
+
+impl Move {
+    pub async fn send(
+        user: &User,
+        target: Url,
+        inboxes: Vec<Url>,
+        data: &Data<DbHandle>,
+    ) -> anyhow::Result<Url> {
+        let id = activity_id(user)?;
+        let actor: Url = user.apub_id.parse()?;
+        let activity = Self {
+            actor: actor.clone().into(),
+            object: actor.into(),
+            target: target.into(),
+            kind: Default::default(),
+            id: id.clone(),
+        };
+        let activity = WithContext::new_default(activity);
+        activities::deliver(activity, user, inboxes, data).await?;
+        Ok(id)
+    }
+}
+
+/// Moves the follows and author roles of `old` over to `target`. Local users
+/// who followed `old` send a `Follow` to a remote `target`, since it doesn't
+/// know about them yet.
+pub(crate) async fn move_follows(
+    old: &str,
+    target: &User,
+    data: &Data<DbHandle>,
+) -> anyhow::Result<()> {
+    let followers = query!(
+        r#"SELECT u.apub_id FROM follows f
+           JOIN users u ON lower(u.apub_id)=lower(f.follower)
+           WHERE lower(f.following)=$1 AND u.private_key IS NOT NULL
+             AND NOT EXISTS(SELECT 1 FROM follows
+                            WHERE lower(follower)=lower(f.follower)
+                              AND lower(following)=$2)"#,
+        old.to_lowercase(),
+        target.apub_id.to_lowercase()
+    )
+    .fetch_all(data.app_data().as_ref())
+    .await?;
+
+    repoint(old, &target.apub_id, data.app_data().as_ref()).await?;
+
+    if target.private_key_pem().is_some() {
+        return Ok(());
+    }
+    for follower in followers {
+        let user = match User::read_from_id(follower.apub_id.parse()?, data).await? {
+            Some(user) => user,
+            None => continue,
+        };
+        // The follow already applies locally, like one made by the user.
+        match Follow::send(&user, target, data).await {
+            Ok(id) => {
+                query!(
+                    r#"UPDATE follows SET activity_id=$3
+                       WHERE lower(follower)=$1 AND lower(following)=$2"#,
+                    user.apub_id.to_lowercase(),
+                    target.apub_id.to_lowercase(),
+                    id.to_string()
+                )
+                .execute(data.app_data().as_ref())
+                .await?;
+            }
+            Err(e) => log::warn!(
+                "Failed to send follow of {} for {}: {e}",
+                target.apub_id,
+                user.apub_id
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Points every follow and author role held by `old` at `new` so that they
+/// survive an account migration.
+async fn repoint(old: &str, new: &str, pool: &PgPool) -> anyhow::Result<()> {
+    let old = old.to_lowercase();
+    let new = new.to_lowercase();
+    let mut tx = pool.begin().await?;
+
+    query!(
+        r#"DELETE FROM follows f
+           WHERE lower(f.follower)=$1 AND EXISTS(
+             SELECT 1 FROM follows
+             WHERE lower(follower)=$2 AND following=f.following
+           )"#,
+        old,
+        new
+    )
+    .execute(&mut tx)
+    .await?;
+    query!(
+        "UPDATE follows SET follower=$2 WHERE lower(follower)=$1",
+        old,
+        new
+    )
+    .execute(&mut tx)
+    .await?;
+
+    query!(
+        r#"DELETE FROM follows f
+           WHERE lower(f.following)=$1 AND EXISTS(
+             SELECT 1 FROM follows
+             WHERE lower(following)=$2 AND follower=f.follower
+           )"#,
+        old,
+        new
+    )
+    .execute(&mut tx)
+    .await?;
+    query!(
+        "UPDATE follows SET following=$2 WHERE lower(following)=$1",
+        old,
+        new
+    )
+    .execute(&mut tx)
+    .await?;
+
+    query!(
+        r#"DELETE FROM author_roles r
+           WHERE lower(r.author)=$1 AND EXISTS(
+             SELECT 1 FROM author_roles
+             WHERE lower(author)=$2 AND id=r.id
+           )"#,
+        old,
+        new
+    )
+    .execute(&mut tx)
+    .await?;
+    query!(
+        "UPDATE author_roles SET author=$2 WHERE lower(author)=$1",
+        old,
+        new
+    )
+    .execute(&mut tx)
+    .await?;
+
+    query!(
+        "UPDATE users SET moved_to=$2 WHERE lower(apub_id)=$1",
+        old,
+        new
+    )
+    .execute(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[async_trait]
+impl ActivityHandler for Move {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        self.actor.inner()
+    }
+
+    async fn verify(&self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        verify_urls_match(self.actor.inner(), self.object.inner())?;
+        let target = self.target.dereference(data).await?;
+
+        target
+            .also_known_as
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(self.object.inner().as_str()))
+            .then_some(())
+            .ok_or(anyhow!(
+                "Target does not list the moved account as an alias"
+            ))
+    }
+
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        let target = self.target.dereference(data).await?;
+        move_follows(self.object.inner().as_str(), &target, data).await
+    }
+}
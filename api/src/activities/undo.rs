@@ -0,0 +1,93 @@
+use crate::{
+    activities::{self, activity_id, block::Block, follow::Follow},
+    objects::person::User,
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    kinds::activity::UndoType,
+    protocol::{context::WithContext, verification::verify_urls_match},
+    traits::ActivityHandler,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::query;
+use url::Url;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum UndoObject {
+    Follow(Follow),
+    Block(Block),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Undo {
+    actor: ObjectId<User>,
+    object: UndoObject,
+    #[serde(rename = "type")]
+    kind: UndoType,
+    id: Url,
+}
+
+impl Undo {
+    /// Withdraws `object`, an activity previously sent by `user`.
+    pub async fn send(
+        user: &User,
+        object: UndoObject,
+        inbox: Url,
+        data: &Data<DbHandle>,
+    ) -> anyhow::Result<Url> {
+        let id = activity_id(user)?;
+        let activity = Self {
+            actor: user.apub_id.parse::<Url>()?.into(),
+            object,
+            kind: Default::default(),
+            id: id.clone(),
+        };
+        let activity = WithContext::new_default(activity);
+        activities::deliver(activity, user, vec![inbox], data).await?;
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl ActivityHandler for Undo {
+    type DataType = DbHandle;
+    type Error = anyhow::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        self.actor.inner()
+    }
+
+    async fn verify(&self, _data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        let inner = match &self.object {
+            UndoObject::Follow(f) => f.actor.inner(),
+            UndoObject::Block(b) => b.actor.inner(),
+        };
+        verify_urls_match(self.actor.inner(), inner)?;
+        Ok(())
+    }
+
+    async fn receive(self, data: &Data<Self::DataType>) -> anyhow::Result<()> {
+        match self.object {
+            UndoObject::Follow(follow) => {
+                query!(
+                    "DELETE FROM follows WHERE lower(follower)=$1 AND lower(following)=$2",
+                    follow.actor.inner().to_string().to_lowercase(),
+                    follow.object.to_string().to_lowercase()
+                )
+                .execute(data.app_data().as_ref())
+                .await?;
+            }
+            // Remote blocks aren't stored, so there is nothing to lift.
+            UndoObject::Block(_) => (),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,830 @@
+use crate::{
+    account::{require_role, user_role, UserValidateError},
+    api::trending::{self, TrendingCandidate},
+    domain_policy::{self, DomainPolicy},
+    enums::{
+        ActorRestriction, DomainPolicyKind, InstanceRole, ModAction, RegistrationMode, TrendingKind,
+    },
+    instance,
+    moderation_log::{self, LogEntry, LogFilter},
+    restriction,
+};
+use actix_session::Session;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, query_as, PgPool};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AdminError {
+    #[error("Admin: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Admin: Forbidden: {0}")]
+    Forbidden(String),
+    #[error("Admin: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Admin: NotFound")]
+    NotFound,
+    #[error("Admin: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+impl From<UserValidateError> for AdminError {
+    fn from(e: UserValidateError) -> Self {
+        match e {
+            UserValidateError::Unauthorized(e) => AdminError::Unauthorized(e),
+            UserValidateError::Forbidden(e) => AdminError::Forbidden(e),
+            e => AdminError::InternalServerError(e.to_string()),
+        }
+    }
+}
+
+/// Checks that the signed in user has at least `role`, returning their apub_id
+/// and actual role.
+pub async fn staff(
+    pool: &PgPool,
+    session: &Session,
+    role: InstanceRole,
+) -> Result<(String, InstanceRole), AdminError> {
+    Ok(require_role(pool, session, role).await?)
+}
+
+/// Changes the instance role of a local user. Admins may appoint moderators,
+/// only the owner may appoint admins, and ownership can't be transferred.
+pub async fn set_role(
+    pool: &PgPool,
+    session: &Session,
+    target: &str,
+    role: InstanceRole,
+    reason: Option<String>,
+) -> Result<(), AdminError> {
+    let (apub_id, current) = staff(pool, session, InstanceRole::Admin).await?;
+
+    if apub_id.eq_ignore_ascii_case(target) {
+        return Err(AdminError::BadRequest(
+            "You can't change your own role".to_string(),
+        ));
+    }
+    if role == InstanceRole::Owner {
+        return Err(AdminError::BadRequest(
+            "There can only be one owner".to_string(),
+        ));
+    }
+
+    let existing = user_role(pool, target).await?;
+    if existing == InstanceRole::Owner {
+        return Err(AdminError::Forbidden(
+            "The owner's role can't be changed".to_string(),
+        ));
+    }
+    if current != InstanceRole::Owner
+        && (role >= InstanceRole::Admin || existing >= InstanceRole::Admin)
+    {
+        return Err(AdminError::Forbidden(
+            "Only the owner can appoint or remove admins".to_string(),
+        ));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    let res = query!(
+        "UPDATE users SET role=$2 WHERE lower(apub_id)=$1 AND private_key IS NOT NULL",
+        target.to_lowercase(),
+        role.to_string()
+    )
+    .execute(&mut tx)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    if res.rows_affected() == 0 {
+        return Err(AdminError::NotFound);
+    }
+
+    let reason = match reason.filter(|r| !r.trim().is_empty()) {
+        Some(r) => format!("{existing} -> {role}: {r}"),
+        None => format!("{existing} -> {role}"),
+    };
+    moderation_log::record(&mut tx, &apub_id, ModAction::SetRole, target, Some(&reason))
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaffMember {
+    pub apub_id: String,
+    pub preferred_username: String,
+    pub role: String,
+}
+
+/// Lists local users with a role above `user`.
+pub async fn list_staff(pool: &PgPool, session: &Session) -> Result<Vec<StaffMember>, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+
+    Ok(query!(
+        r#"SELECT apub_id, preferred_username, role
+           FROM users
+           WHERE role <> 'user' AND private_key IS NOT NULL
+           ORDER BY preferred_username"#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| StaffMember {
+        apub_id: row.apub_id,
+        preferred_username: row.preferred_username,
+        role: row.role,
+    })
+    .collect())
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub local_users: i64,
+    pub remote_users: i64,
+    pub pending_users: i64,
+    pub local_novels: i64,
+    pub remote_novels: i64,
+    pub instances: i64,
+}
+
+pub async fn stats(pool: &PgPool, session: &Session) -> Result<Stats, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+
+    let row = query!(
+        r#"SELECT
+           (SELECT count(*) FROM users WHERE private_key IS NOT NULL AND approved) AS "local_users!",
+           (SELECT count(*) FROM users WHERE private_key IS NULL) AS "remote_users!",
+           (SELECT count(*) FROM users WHERE NOT approved) AS "pending_users!",
+           (SELECT count(*) FROM novels WHERE private_key IS NOT NULL) AS "local_novels!",
+           (SELECT count(*) FROM novels WHERE private_key IS NULL) AS "remote_novels!",
+           (SELECT count(DISTINCT substring(lower(apub_id) from '^[a-z]+://([^/]+)'))
+            FROM (SELECT apub_id FROM users WHERE private_key IS NULL
+                  UNION ALL
+                  SELECT apub_id FROM novels WHERE private_key IS NULL) remote) AS "instances!""#
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    Ok(Stats {
+        local_users: row.local_users,
+        remote_users: row.remote_users,
+        pending_users: row.pending_users,
+        local_novels: row.local_novels,
+        remote_novels: row.remote_novels,
+        instances: row.instances,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminUser {
+    pub apub_id: String,
+    pub preferred_username: String,
+    pub name: String,
+    pub local: bool,
+    pub role: String,
+    pub approved: bool,
+    pub suspended: bool,
+    pub silenced: bool,
+    pub force_sensitive: bool,
+    pub published: DateTime<Utc>,
+}
+
+/// Lists the most recently registered or discovered users.
+pub async fn recent_users(
+    pool: &PgPool,
+    session: &Session,
+    limit: i64,
+) -> Result<Vec<AdminUser>, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+
+    Ok(query!(
+        r#"SELECT u.apub_id, preferred_username, name, private_key IS NOT NULL AS "local!",
+             role, approved, published,
+             suspended_at IS NOT NULL OR coalesce(r.suspended, false) AS "suspended!",
+             coalesce(r.silenced, false) AS "silenced!",
+             coalesce(r.force_sensitive, false) AS "force_sensitive!"
+           FROM users u
+           LEFT JOIN actor_restrictions r ON r.apub_id=lower(u.apub_id)
+           ORDER BY published DESC
+           LIMIT $1"#,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| AdminUser {
+        apub_id: row.apub_id,
+        preferred_username: row.preferred_username,
+        name: row.name,
+        local: row.local,
+        role: row.role,
+        approved: row.approved,
+        suspended: row.suspended,
+        silenced: row.silenced,
+        force_sensitive: row.force_sensitive,
+        published: row.published,
+    })
+    .collect())
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminNovel {
+    pub apub_id: String,
+    pub preferred_username: String,
+    pub title: String,
+    pub local: bool,
+    pub hidden: bool,
+    pub published: DateTime<Utc>,
+}
+
+/// Lists the most recently created or discovered novels.
+pub async fn recent_novels(
+    pool: &PgPool,
+    session: &Session,
+    limit: i64,
+) -> Result<Vec<AdminNovel>, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+
+    Ok(query!(
+        r#"SELECT apub_id, preferred_username, title, private_key IS NOT NULL AS "local!",
+             hidden, published
+           FROM novels
+           ORDER BY published DESC
+           LIMIT $1"#,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| AdminNovel {
+        apub_id: row.apub_id,
+        preferred_username: row.preferred_username.to_string(),
+        title: row.title,
+        local: row.local,
+        hidden: row.hidden,
+        published: row.published,
+    })
+    .collect())
+}
+
+#[derive(Debug, Serialize)]
+pub struct KnownInstance {
+    pub domain: String,
+    pub users: i64,
+    pub novels: i64,
+    pub policy: Option<String>,
+}
+
+/// Lists the remote instances that users and novels have been seen from,
+/// busiest first.
+pub async fn known_instances(
+    pool: &PgPool,
+    session: &Session,
+) -> Result<Vec<KnownInstance>, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+
+    Ok(query!(
+        r#"SELECT i.domain AS "domain!", i.users AS "users!", i.novels AS "novels!",
+             (SELECT policy FROM domain_policies p
+              WHERE split_part(i.domain, ':', 1)=p.domain
+                 OR right(split_part(i.domain, ':', 1), length(p.domain) + 1)='.' || p.domain
+              ORDER BY length(p.domain) DESC
+              LIMIT 1) AS policy
+           FROM (SELECT substring(lower(apub_id) from '^[a-z]+://([^/]+)') AS domain,
+                   count(*) FILTER (WHERE kind='user') AS users,
+                   count(*) FILTER (WHERE kind='novel') AS novels
+                 FROM (SELECT apub_id, 'user' AS kind FROM users WHERE private_key IS NULL
+                       UNION ALL
+                       SELECT apub_id, 'novel' AS kind FROM novels WHERE private_key IS NULL) remote
+                 GROUP BY 1) i
+           ORDER BY i.users + i.novels DESC"#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| KnownInstance {
+        domain: row.domain,
+        users: row.users,
+        novels: row.novels,
+        policy: row.policy,
+    })
+    .collect())
+}
+
+/// An activity that couldn't be handed to the delivery queue.
+#[derive(Debug, Serialize)]
+pub struct Delivery {
+    pub activity_id: String,
+    pub kind: String,
+    /// How many inboxes it was meant for.
+    pub inboxes: i32,
+    pub error: String,
+    pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryStats {
+    /// Deliveries handed to the queue in the last 24 hours. The queue retries
+    /// them in the background and doesn't report how they end.
+    pub queued: i64,
+    /// Deliveries that couldn't be queued in the last 24 hours.
+    pub failed: i64,
+    pub recent_failures: Vec<Delivery>,
+}
+
+pub async fn delivery_stats(pool: &PgPool, session: &Session) -> Result<DeliveryStats, AdminError> {
+    staff(pool, session, InstanceRole::Admin).await?;
+
+    let counts = query!(
+        r#"SELECT coalesce(sum(queued), 0) AS "queued!", coalesce(sum(failed), 0) AS "failed!"
+           FROM delivery_counts
+           WHERE hour > now() - interval '1 day'"#
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    let recent_failures = query_as!(
+        Delivery,
+        r#"SELECT activity_id, kind, inboxes, error, published
+           FROM delivery_failures
+           ORDER BY published DESC
+           LIMIT 20"#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    Ok(DeliveryStats {
+        queued: counts.queued,
+        failed: counts.failed,
+        recent_failures,
+    })
+}
+
+/// Suspends or unsuspends a user. Staff can only suspend users with a lower
+/// role than their own. Suspension signs the user out everywhere and revokes
+/// their API tokens.
+pub async fn suspend_user(
+    pool: &PgPool,
+    session: &Session,
+    target: &str,
+    suspend: bool,
+    reason: Option<String>,
+) -> Result<(), AdminError> {
+    let (moderator, current) = staff(pool, session, InstanceRole::Moderator).await?;
+
+    if user_role(pool, target).await? >= current {
+        return Err(AdminError::Forbidden(
+            "You can only suspend users with a lower role".to_string(),
+        ));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    let res = query!(
+        r#"UPDATE users
+           SET suspended_at = CASE WHEN $2 THEN coalesce(suspended_at, now()) ELSE NULL END
+           WHERE lower(apub_id)=$1"#,
+        target.to_lowercase(),
+        suspend
+    )
+    .execute(&mut tx)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+    if res.rows_affected() == 0 {
+        return Err(AdminError::NotFound);
+    }
+
+    if suspend {
+        query!(
+            "DELETE FROM sessions WHERE lower(apub_id)=$1",
+            target.to_lowercase()
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+        query!(
+            "DELETE FROM access_tokens WHERE lower(apub_id)=$1",
+            target.to_lowercase()
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+        query!(
+            "DELETE FROM oauth_codes WHERE lower(apub_id)=$1",
+            target.to_lowercase()
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+    }
+
+    let action = if suspend {
+        ModAction::SuspendUser
+    } else {
+        ModAction::UnsuspendUser
+    };
+    moderation_log::record(&mut tx, &moderator, action, target, reason.as_deref())
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Applies or lifts `restriction` on a local or remote actor. Staff can only
+/// restrict users with a lower role than their own.
+///
+/// Suspending a local user goes through [`suspend_user`]. Suspending a remote
+/// actor drops its follows and rejects everything it sends from then on.
+/// Forcing sensitive marks the existing novels of a local user as sensitive.
+/// Lifting it doesn't change them back since their original state is unknown.
+pub async fn restrict_actor(
+    pool: &PgPool,
+    session: &Session,
+    target: &str,
+    restriction: ActorRestriction,
+    enabled: bool,
+    reason: Option<String>,
+) -> Result<(), AdminError> {
+    let target = target.trim().to_lowercase();
+    let local = query!(
+        r#"SELECT EXISTS(
+             SELECT 1 FROM users WHERE lower(apub_id)=$1 AND private_key IS NOT NULL
+           ) AS "local!""#,
+        target
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?
+    .local;
+
+    if local && restriction == ActorRestriction::Suspend {
+        return suspend_user(pool, session, &target, enabled, reason).await;
+    }
+
+    let (moderator, current) = staff(pool, session, InstanceRole::Moderator).await?;
+    if user_role(pool, &target).await? >= current {
+        return Err(AdminError::Forbidden(
+            "You can only restrict users with a lower role".to_string(),
+        ));
+    }
+    if !local && target.parse::<url::Url>().is_err() {
+        return Err(AdminError::BadRequest("Invalid actor id".to_string()));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    restriction::set(&mut tx, &target, &restriction, enabled)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    match (&restriction, enabled) {
+        (ActorRestriction::Suspend, true) => {
+            query!(
+                "DELETE FROM follows WHERE lower(follower)=$1 OR lower(following)=$1",
+                target
+            )
+            .execute(&mut tx)
+            .await
+            .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+        }
+        (ActorRestriction::ForceSensitive, true) if local => {
+            query!(
+                r#"UPDATE novels SET sensitive=true
+                   WHERE lower(apub_id) IN (
+                     SELECT lower(id) FROM author_roles WHERE lower(author)=$1
+                   )"#,
+                target
+            )
+            .execute(&mut tx)
+            .await
+            .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+        }
+        _ => (),
+    }
+
+    let action = match (restriction, enabled) {
+        (ActorRestriction::Suspend, true) => ModAction::SuspendUser,
+        (ActorRestriction::Suspend, false) => ModAction::UnsuspendUser,
+        (ActorRestriction::Silence, true) => ModAction::SilenceActor,
+        (ActorRestriction::Silence, false) => ModAction::UnsilenceActor,
+        (ActorRestriction::ForceSensitive, true) => ModAction::ForceSensitive,
+        (ActorRestriction::ForceSensitive, false) => ModAction::UnforceSensitive,
+    };
+    moderation_log::record(&mut tx, &moderator, action, &target, reason.as_deref())
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Hides or unhides a novel from local listings and pages.
+pub async fn hide_novel(
+    pool: &PgPool,
+    session: &Session,
+    novel: &str,
+    hidden: bool,
+    reason: Option<String>,
+) -> Result<(), AdminError> {
+    let (moderator, _) = staff(pool, session, InstanceRole::Moderator).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    let res = query!(
+        "UPDATE novels SET hidden=$2 WHERE lower(apub_id)=$1",
+        novel.to_lowercase(),
+        hidden
+    )
+    .execute(&mut tx)
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    if res.rows_affected() == 0 {
+        return Err(AdminError::NotFound);
+    }
+
+    let action = if hidden {
+        ModAction::HideNovel
+    } else {
+        ModAction::UnhideNovel
+    };
+    moderation_log::record(&mut tx, &moderator, action, novel, reason.as_deref())
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Changes the registration mode of the instance.
+pub async fn set_registration_mode(
+    pool: &PgPool,
+    session: &Session,
+    mode: RegistrationMode,
+) -> Result<(), AdminError> {
+    let (admin, _) = staff(pool, session, InstanceRole::Admin).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    instance::set_registration_mode(&mut tx, mode.clone())
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+    moderation_log::record(
+        &mut tx,
+        &admin,
+        ModAction::SetRegistrationMode,
+        &mode.to_string(),
+        None,
+    )
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Approves or rejects a pending registration.
+pub async fn review_registration(
+    pool: &PgPool,
+    session: &Session,
+    target: &str,
+    approve: bool,
+    reason: Option<String>,
+) -> Result<(), AdminError> {
+    let (moderator, _) = staff(pool, session, InstanceRole::Moderator).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    let action = if approve {
+        instance::approve_user(&mut tx, target)
+            .await
+            .map(|_| ModAction::ApproveRegistration)
+    } else {
+        instance::reject_user(&mut tx, target)
+            .await
+            .map(|_| ModAction::RejectRegistration)
+    }
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+    moderation_log::record(&mut tx, &moderator, action, target, reason.as_deref())
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+pub async fn domain_policies(
+    pool: &PgPool,
+    session: &Session,
+) -> Result<Vec<DomainPolicy>, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+    domain_policy::list_policies(pool)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Sets the federation policy for `domain` and its subdomains.
+pub async fn set_domain_policy(
+    pool: &PgPool,
+    session: &Session,
+    domain: &str,
+    policy: DomainPolicyKind,
+    reason: Option<String>,
+) -> Result<(), AdminError> {
+    let (admin, _) = staff(pool, session, InstanceRole::Admin).await?;
+    let domain = domain_policy::normalize_domain(domain)
+        .ok_or_else(|| AdminError::BadRequest("Invalid domain".to_string()))?;
+    let reason = reason.filter(|r| !r.trim().is_empty());
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    domain_policy::set_policy(&mut tx, &domain, policy.clone(), reason.clone())
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+    let reason = match reason {
+        Some(r) => format!("{policy}: {r}"),
+        None => policy.to_string(),
+    };
+    moderation_log::record(
+        &mut tx,
+        &admin,
+        ModAction::SetDomainPolicy,
+        &domain,
+        Some(&reason),
+    )
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+pub async fn remove_domain_policy(
+    pool: &PgPool,
+    session: &Session,
+    domain: &str,
+) -> Result<(), AdminError> {
+    let (admin, _) = staff(pool, session, InstanceRole::Admin).await?;
+    let domain = domain_policy::normalize_domain(domain)
+        .ok_or_else(|| AdminError::BadRequest("Invalid domain".to_string()))?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    match domain_policy::remove_policy(&mut tx, &domain).await {
+        Ok(true) => (),
+        Ok(false) => return Err(AdminError::NotFound),
+        Err(e) => return Err(AdminError::InternalServerError(e.to_string())),
+    }
+    moderation_log::record(
+        &mut tx,
+        &admin,
+        ModAction::RemoveDomainPolicy,
+        &domain,
+        None,
+    )
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Restricts federation to domains with an `allow` policy when `enabled`.
+pub async fn set_allowlist_mode(
+    pool: &PgPool,
+    session: &Session,
+    enabled: bool,
+) -> Result<(), AdminError> {
+    let (admin, _) = staff(pool, session, InstanceRole::Admin).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    instance::set_allowlist_mode(&mut tx, enabled)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+    moderation_log::record(
+        &mut tx,
+        &admin,
+        ModAction::SetAllowlistMode,
+        &enabled.to_string(),
+        None,
+    )
+    .await
+    .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Lists moderation log entries for staff, newest first. `filter.limit` is
+/// capped at 200 unless `export` is set.
+pub async fn moderation_log(
+    pool: &PgPool,
+    session: &Session,
+    mut filter: LogFilter,
+    export: bool,
+) -> Result<Vec<LogEntry>, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+    if !export {
+        filter.limit = Some(filter.limit.unwrap_or(50).clamp(1, 200));
+    }
+    moderation_log::list(pool, &filter)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Lists the trending novels and tags for moderators to review.
+pub async fn trending_candidates(
+    pool: &PgPool,
+    session: &Session,
+) -> Result<Vec<TrendingCandidate>, AdminError> {
+    staff(pool, session, InstanceRole::Moderator).await?;
+    trending::candidates(pool, 50)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
+
+/// Lets a trending novel or tag show up publicly, or hides it from the
+/// trending lists.
+pub async fn review_trending(
+    pool: &PgPool,
+    session: &Session,
+    kind: TrendingKind,
+    target: &str,
+    approved: bool,
+    reason: Option<String>,
+) -> Result<(), AdminError> {
+    let (moderator, _) = staff(pool, session, InstanceRole::Moderator).await?;
+    if target.trim().trim_start_matches('#').is_empty() {
+        return Err(AdminError::BadRequest("Empty target".to_string()));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    trending::set_review(&mut tx, &moderator, &kind, target, approved)
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    let action = if approved {
+        ModAction::ApproveTrending
+    } else {
+        ModAction::HideTrending
+    };
+    moderation_log::record(&mut tx, &moderator, action, target, reason.as_deref())
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AdminError::InternalServerError(e.to_string()))
+}
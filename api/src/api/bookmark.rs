@@ -0,0 +1,302 @@
+use crate::{
+    api::chapter::{collapse_spaces, paragraph_ids, paragraphs},
+    enums::BookmarkKind,
+    util::local_handle,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{query, PgPool};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// How long highlighted passages may be.
+pub const QUOTE_LIMIT: usize = 1000;
+/// How long notes may be.
+pub const NOTE_LIMIT: usize = 2000;
+
+#[derive(Debug, Error)]
+pub enum BookmarkError {
+    #[error("Bookmark: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Bookmark: NotFound")]
+    NotFound,
+    #[error("Bookmark: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct Bookmark {
+    pub id: Uuid,
+    pub chapter: String,
+    pub kind: BookmarkKind,
+    /// The index of the paragraph it was made in.
+    pub paragraph: i32,
+    /// The ID of the paragraph it was made in. Empty for bookmarks made
+    /// before paragraphs had IDs.
+    pub paragraph_id: String,
+    pub quote: String,
+    pub note: String,
+    pub published: DateTime<Utc>,
+    /// The ID of the paragraph it points at in the chapter as it is now.
+    /// Unset when the passage can't be found anymore.
+    pub anchor: Option<String>,
+}
+
+/// A bookmark with where to find it, for the owner's list of them.
+#[derive(Debug, Serialize)]
+pub struct BookmarkListing {
+    pub bookmark: Bookmark,
+    pub novel_title: String,
+    pub chapter_title: String,
+    /// Where the chapter is shown on this instance.
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewBookmark {
+    pub chapter: String,
+    pub kind: BookmarkKind,
+    /// The ID of the paragraph in the rendered chapter.
+    pub paragraph: String,
+    /// The highlighted passage, or the start of the bookmarked paragraph.
+    pub quote: String,
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Finds the ID of the paragraph a bookmark points at now. It is
+/// `paragraph_id` as long as that paragraph is unchanged. Otherwise the one
+/// nearest to `paragraph` that still contains `quote` is used, since edits
+/// mostly shift paragraphs a little.
+pub fn resolve_anchor(
+    paragraphs: &[String],
+    ids: &[String],
+    paragraph_id: &str,
+    paragraph: i32,
+    quote: &str,
+) -> Option<String> {
+    if ids.iter().any(|id| id == paragraph_id) {
+        return Some(paragraph_id.to_string());
+    }
+    let quote = collapse_spaces(quote);
+    paragraphs
+        .iter()
+        .zip(ids)
+        .enumerate()
+        .filter(|(_, (p, _))| p.contains(&quote))
+        .min_by_key(|(i, _)| (*i as i32 - paragraph).abs())
+        .map(|(_, (_, id))| id.clone())
+}
+
+fn validate_note(note: &str) -> Result<String, BookmarkError> {
+    let note = note.trim();
+    if note.chars().count() > NOTE_LIMIT {
+        return Err(BookmarkError::BadRequest(format!(
+            "Notes can't be longer than {NOTE_LIMIT} characters"
+        )));
+    }
+    Ok(note.to_string())
+}
+
+/// Saves a bookmark or highlight of `owner` in a chapter.
+pub async fn add_bookmark(
+    pool: &PgPool,
+    owner: &str,
+    bookmark: NewBookmark,
+) -> Result<Uuid, BookmarkError> {
+    let quote = collapse_spaces(&bookmark.quote);
+    if quote.is_empty() || quote.chars().count() > QUOTE_LIMIT {
+        return Err(BookmarkError::BadRequest(format!(
+            "Passages must be between 1 and {QUOTE_LIMIT} characters"
+        )));
+    }
+    let note = validate_note(&bookmark.note)?;
+
+    let chapter = query!(
+        r#"SELECT c.apub_id, c.content FROM chapters c
+           JOIN novels n ON lower(n.apub_id)=lower(c.audience)
+           WHERE lower(c.apub_id)=$1 AND NOT n.hidden"#,
+        bookmark.chapter.trim().to_lowercase()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?
+    .ok_or(BookmarkError::NotFound)?;
+
+    let paragraphs = paragraphs(&chapter.content);
+    let paragraph = match paragraph_ids(&paragraphs)
+        .iter()
+        .position(|id| *id == bookmark.paragraph)
+    {
+        Some(i) if paragraphs[i].contains(&quote) => i as i32,
+        _ => {
+            return Err(BookmarkError::BadRequest(
+                "The passage isn't in that paragraph".to_string(),
+            ))
+        }
+    };
+
+    query!(
+        r#"INSERT INTO bookmarks (owner, chapter, kind, paragraph, paragraph_id, quote, note)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+           RETURNING id"#,
+        owner,
+        chapter.apub_id,
+        bookmark.kind.to_string(),
+        paragraph,
+        bookmark.paragraph,
+        quote,
+        note
+    )
+    .fetch_one(pool)
+    .await
+    .map(|row| row.id)
+    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))
+}
+
+/// Changes the note of a bookmark of `owner`.
+pub async fn set_note(
+    pool: &PgPool,
+    owner: &str,
+    id: Uuid,
+    note: &str,
+) -> Result<(), BookmarkError> {
+    let note = validate_note(note)?;
+    let res = query!(
+        "UPDATE bookmarks SET note=$3 WHERE id=$1 AND lower(owner)=$2",
+        id,
+        owner.to_lowercase(),
+        note
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?;
+    if res.rows_affected() == 0 {
+        return Err(BookmarkError::NotFound);
+    }
+    Ok(())
+}
+
+pub async fn delete_bookmark(pool: &PgPool, owner: &str, id: Uuid) -> Result<(), BookmarkError> {
+    let res = query!(
+        "DELETE FROM bookmarks WHERE id=$1 AND lower(owner)=$2",
+        id,
+        owner.to_lowercase()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?;
+    if res.rows_affected() == 0 {
+        return Err(BookmarkError::NotFound);
+    }
+    Ok(())
+}
+
+/// The bookmarks and highlights of `owner` in one chapter, in reading order.
+pub async fn chapter_bookmarks(
+    pool: &PgPool,
+    owner: &str,
+    chapter: &str,
+) -> Result<Vec<Bookmark>, BookmarkError> {
+    let rows = query!(
+        r#"SELECT b.id, b.chapter, b.kind, b.paragraph, b.paragraph_id, b.quote, b.note,
+             b.published, c.content
+           FROM bookmarks b
+           JOIN chapters c ON lower(c.apub_id)=lower(b.chapter)
+           WHERE lower(b.owner)=$1 AND lower(b.chapter)=$2
+           ORDER BY b.paragraph, b.published"#,
+        owner.to_lowercase(),
+        chapter.trim().to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?;
+
+    let paragraphs = rows
+        .first()
+        .map(|row| paragraphs(&row.content))
+        .unwrap_or_default();
+    let ids = paragraph_ids(&paragraphs);
+    rows.into_iter()
+        .map(|row| {
+            Ok(Bookmark {
+                anchor: resolve_anchor(
+                    &paragraphs,
+                    &ids,
+                    &row.paragraph_id,
+                    row.paragraph,
+                    &row.quote,
+                ),
+                id: row.id,
+                chapter: row.chapter,
+                kind: BookmarkKind::from_str(&row.kind)
+                    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?,
+                paragraph: row.paragraph,
+                paragraph_id: row.paragraph_id,
+                quote: row.quote,
+                note: row.note,
+                published: row.published,
+            })
+        })
+        .collect()
+}
+
+/// Every bookmark and highlight of `owner`, newest first.
+pub async fn list_bookmarks(
+    pool: &PgPool,
+    owner: &str,
+) -> Result<Vec<BookmarkListing>, BookmarkError> {
+    query!(
+        r#"SELECT b.id, b.chapter, b.kind, b.paragraph, b.paragraph_id, b.quote, b.note,
+             b.published, c.content, c.title AS chapter_title, n.title AS novel_title, n.apub_id AS novel,
+             n.preferred_username, n.private_key IS NOT NULL AS "local!"
+           FROM bookmarks b
+           JOIN chapters c ON lower(c.apub_id)=lower(b.chapter)
+           JOIN novels n ON lower(n.apub_id)=lower(c.audience)
+           WHERE lower(b.owner)=$1 AND NOT n.hidden
+           ORDER BY b.published DESC"#,
+        owner.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        let segment = row.chapter.rsplit('/').next().unwrap_or_default();
+        let paragraphs = paragraphs(&row.content);
+        Ok(BookmarkListing {
+            path: format!(
+                "/novel/{}/{}",
+                local_handle(
+                    &row.preferred_username.to_string().to_lowercase(),
+                    &row.novel,
+                    row.local
+                )
+                .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?,
+                segment
+            ),
+            novel_title: row.novel_title,
+            chapter_title: row.chapter_title,
+            bookmark: Bookmark {
+                anchor: resolve_anchor(
+                    &paragraphs,
+                    &paragraph_ids(&paragraphs),
+                    &row.paragraph_id,
+                    row.paragraph,
+                    &row.quote,
+                ),
+                id: row.id,
+                chapter: row.chapter,
+                kind: BookmarkKind::from_str(&row.kind)
+                    .map_err(|e| BookmarkError::InternalServerError(e.to_string()))?,
+                paragraph: row.paragraph,
+                paragraph_id: row.paragraph_id,
+                quote: row.quote,
+                note: row.note,
+                published: row.published,
+            },
+        })
+    })
+    .collect()
+}
@@ -0,0 +1,165 @@
+use crate::{api::novel::NovelListing, enums::BrowseSort, util::language_code};
+use serde::{Deserialize, Serialize};
+use sqlx::{query, PgPool};
+use thiserror::Error;
+
+pub const PAGE_SIZE: i64 = 20;
+
+#[derive(Debug, Error)]
+pub enum BrowseError {
+    #[error("Browse: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Browse: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BrowseQuery {
+    pub genre: Option<String>,
+    /// An ISO 639-1 code or an English language name.
+    pub language: Option<String>,
+    #[serde(default)]
+    pub sort: BrowseSort,
+    /// Starts at 0.
+    #[serde(default)]
+    pub page: i64,
+}
+
+/// A value of a facet and how many novels have it.
+#[derive(Debug, Serialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrowsePage {
+    pub total: i64,
+    pub novels: Vec<NovelListing>,
+    /// Genres of the novels matching the language filter.
+    pub genres: Vec<FacetCount>,
+    /// Languages of the novels matching the genre filter, as ISO 639-1
+    /// codes.
+    pub languages: Vec<FacetCount>,
+}
+
+/// Lists local and known remote novels, filtered by genre and language. The
+/// facet counts leave out their own filter so that other values can be
+/// picked.
+pub async fn browse(pool: &PgPool, query: BrowseQuery) -> Result<BrowsePage, BrowseError> {
+    let genre = query
+        .genre
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty());
+    let language = match query.language.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(l) => Some(
+            language_code(l)
+                .ok_or_else(|| BrowseError::BadRequest("Unknown language".to_string()))?,
+        ),
+    };
+    let page = query.page.max(0);
+
+    let total = query!(
+        r#"SELECT count(*) AS "count!" FROM novels n
+           WHERE NOT n.hidden AND NOT authors_hidden(n.apub_id)
+             AND ($1::text IS NULL OR n.genre=$1)
+             AND ($2::text IS NULL OR n.language=$2)"#,
+        genre,
+        language
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| BrowseError::InternalServerError(e.to_string()))?
+    .count;
+
+    let novels = query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published, n.private_key IS NOT NULL AS "local!"
+           FROM novels n
+           LEFT JOIN LATERAL (
+             SELECT max(coalesce(c.updated, c.published)) AS updated FROM chapters c
+             WHERE lower(c.audience)=lower(n.apub_id)
+           ) c ON true
+           LEFT JOIN LATERAL (
+             SELECT count(*) AS followers FROM follows f
+             WHERE lower(f.following)=lower(n.apub_id)
+           ) f ON true
+           WHERE NOT n.hidden AND NOT authors_hidden(n.apub_id)
+             AND ($1::text IS NULL OR n.genre=$1)
+             AND ($2::text IS NULL OR n.language=$2)
+           ORDER BY
+             CASE WHEN $3='updated' THEN coalesce(c.updated, n.published) END DESC,
+             CASE WHEN $3='followed' THEN f.followers END DESC,
+             n.published DESC
+           LIMIT $4 OFFSET $5"#,
+        genre,
+        language,
+        query.sort.to_string(),
+        PAGE_SIZE,
+        page.saturating_mul(PAGE_SIZE)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| BrowseError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        NovelListing::new(
+            row.apub_id,
+            row.preferred_username,
+            row.title,
+            row.summary,
+            row.genre,
+            row.tags,
+            row.language,
+            row.sensitive,
+            row.published,
+            row.local,
+        )
+        .map_err(|e| BrowseError::InternalServerError(e.to_string()))
+    })
+    .collect::<Result<_, _>>()?;
+
+    let genres = query!(
+        r#"SELECT n.genre AS "value!", count(*) AS "count!" FROM novels n
+           WHERE NOT n.hidden AND NOT authors_hidden(n.apub_id)
+             AND ($1::text IS NULL OR n.language=$1)
+           GROUP BY n.genre
+           ORDER BY 2 DESC, 1"#,
+        language
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| BrowseError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| FacetCount {
+        value: row.value,
+        count: row.count,
+    })
+    .collect();
+
+    let languages = query!(
+        r#"SELECT n.language AS "value!", count(*) AS "count!" FROM novels n
+           WHERE NOT n.hidden AND NOT authors_hidden(n.apub_id)
+             AND ($1::text IS NULL OR n.genre=$1)
+           GROUP BY n.language
+           ORDER BY 2 DESC, 1"#,
+        genre
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| BrowseError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| FacetCount {
+        value: row.value,
+        count: row.count,
+    })
+    .collect();
+
+    Ok(BrowsePage {
+        total,
+        novels,
+        genres,
+        languages,
+    })
+}
@@ -1,5 +1,9 @@
 use crate::{
-    activities::add::{Add, NewChapter},
+    activities::{
+        add::{Add, NewChapter},
+        announce::Announce,
+    },
+    domain_policy::resolve_actor,
     objects::{
         chapter::{Chapter, ChapterList},
         novel::DbNovel,
@@ -8,45 +12,54 @@ use crate::{
 };
 use activitypub_federation::{
     config::Data,
-    fetch::{collection_id::CollectionId, object_id::ObjectId, webfinger::webfinger_resolve_actor},
+    fetch::{collection_id::CollectionId, object_id::ObjectId},
     traits::Actor,
 };
-use actix_session::Session;
+use lazy_static::lazy_static;
+use regex::Regex;
+use sha2::{Digest, Sha256};
 use sqlx::query;
+use std::collections::HashMap;
 use url::{ParseError, Url};
 
+lazy_static! {
+    static ref BREAK_RE: Regex = Regex::new(r"(?i)</p\s*>|<br\s*/?>|\n").unwrap();
+    static ref MARKUP_RE: Regex = Regex::new(r"<[^>]*>").unwrap();
+    static ref SPACE_RE: Regex = Regex::new(r"\s+").unwrap();
+}
+
 pub enum ChapterCreationError {
     InternalError(String),
     NotFound,
     Unauthorized,
 }
 
+/// Adds a chapter to `novel` as `apub_id`. Remote novels are sent an `Add`.
 pub async fn new_chapter(
     novel: String,
     chapter: NewChapter,
-    session: Session,
+    apub_id: Url,
     data: &Data<DbHandle>,
     scheme: &str,
 ) -> Result<(), ChapterCreationError> {
-    let apub_id: Url = session
-        .get::<String>("id")
-        .map_err(|e| ChapterCreationError::InternalError(e.to_string()))?
-        .ok_or(ChapterCreationError::Unauthorized)?
-        .parse()
-        .map_err(|e: ParseError| ChapterCreationError::InternalError(e.to_string()))?;
-    session.renew();
-
     let (path, is_local) = if novel.contains('@') {
         (novel, false)
     } else {
         (format!("{}@{}", novel, data.domain()), true)
     };
 
-    let novel: DbNovel = webfinger_resolve_actor(&path, data)
+    let novel: DbNovel = resolve_actor(&path, data)
         .await
         .map_err(|_| ChapterCreationError::NotFound)?;
 
     if is_local {
+        if !novel
+            .authors
+            .iter()
+            .any(|a| a.apub_id.eq_ignore_ascii_case(apub_id.as_str()))
+        {
+            return Err(ChapterCreationError::Unauthorized);
+        }
         let novel_id = novel
             .apub_id
             .parse()
@@ -64,6 +77,8 @@ pub async fn new_chapter(
     Ok(())
 }
 
+/// Stores a new chapter of the local `novel` and announces it to the novel's
+/// remote followers.
 pub async fn create_chapter(
     chapter: NewChapter,
     novel: &ObjectId<DbNovel>,
@@ -99,6 +114,11 @@ pub async fn create_chapter(
     .execute(data.app_data().as_ref())
     .await?;
 
+    // The chapter is already stored, so a failed delivery is only logged.
+    if let Err(e) = Announce::send(&novel, apub_id.parse()?, data).await {
+        log::warn!("Failed to announce {apub_id}: {e}");
+    }
+
     Ok(())
 }
 
@@ -117,7 +137,7 @@ pub async fn get_chapters(
         format!("{}@{}", novel, data.domain())
     };
 
-    let novel: DbNovel = webfinger_resolve_actor(&path, data)
+    let novel: DbNovel = resolve_actor(&path, data)
         .await
         .map_err(|_| ChapterError::NotFound)?;
     let outbox: CollectionId<ChapterList> = novel
@@ -143,3 +163,123 @@ pub async fn get_chapters(
 
     Ok(ch)
 }
+
+/// A chapter as shown to readers.
+pub struct ChapterPage {
+    pub chapter: Chapter,
+    pub sequence: i32,
+    pub novel_title: String,
+    /// The URLs of the chapters before and after it.
+    pub previous: Option<String>,
+    pub next: Option<String>,
+}
+
+/// Finds a chapter of `novel` by the last segment of its URL, which is how
+/// chapters are linked below their novel's path. Like `get_novel`, chapters
+/// are gone once every author of the novel is suspended.
+pub async fn get_chapter(
+    novel: String,
+    chapter: String,
+    data: &Data<DbHandle>,
+) -> Result<ChapterPage, ChapterError> {
+    let path = if novel.contains('@') {
+        novel
+    } else {
+        format!("{}@{}", novel, data.domain())
+    };
+
+    let novel: DbNovel = resolve_actor(&path, data)
+        .await
+        .map_err(|_| ChapterError::NotFound)?;
+    if novel.hidden {
+        return Err(ChapterError::NotFound);
+    }
+
+    let row = query!(
+        r#"SELECT c.apub_id, c.audience, c.title, c.summary, c.sensitive, c.content,
+             c.published, c.updated, c.last_refresh, c.sequence,
+             (SELECT p.apub_id FROM chapters p
+              WHERE lower(p.audience)=lower(c.audience) AND p.sequence < c.sequence
+              ORDER BY p.sequence DESC LIMIT 1) AS "previous?",
+             (SELECT n.apub_id FROM chapters n
+              WHERE lower(n.audience)=lower(c.audience) AND n.sequence > c.sequence
+              ORDER BY n.sequence LIMIT 1) AS "next?"
+           FROM chapters c
+           WHERE lower(c.audience)=$1 AND regexp_replace(lower(c.apub_id), '^.*/', '')=$2
+             AND NOT authors_suspended(c.audience)"#,
+        novel.apub_id.to_lowercase(),
+        chapter.to_lowercase()
+    )
+    .fetch_optional(data.app_data().as_ref())
+    .await
+    .map_err(|e| ChapterError::InternalError(e.to_string()))?
+    .ok_or(ChapterError::NotFound)?;
+
+    Ok(ChapterPage {
+        chapter: Chapter {
+            apub_id: row.apub_id,
+            audience: row.audience,
+            title: row.title,
+            summary: row.summary,
+            sensitive: row.sensitive,
+            content: row.content,
+            published: row.published,
+            updated: row.updated,
+            last_refresh: row.last_refresh,
+        },
+        sequence: row.sequence,
+        novel_title: novel.title,
+        previous: row.previous,
+        next: row.next,
+    })
+}
+
+/// Splits chapter content into paragraphs of plain text. Remote chapters
+/// come as HTML, so their markup is dropped rather than rendered.
+pub fn paragraphs(content: &str) -> Vec<String> {
+    BREAK_RE
+        .split(content)
+        .map(|p| {
+            collapse_spaces(
+                &MARKUP_RE
+                    .replace_all(p, "")
+                    .replace("&nbsp;", " ")
+                    .replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&quot;", "\"")
+                    .replace("&#39;", "'")
+                    .replace("&amp;", "&"),
+            )
+        })
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// The IDs chapters render `paragraphs` with. They are made from the text,
+/// so a paragraph keeps its ID while others around it are edited. Repeated
+/// paragraphs are numbered.
+pub fn paragraph_ids(paragraphs: &[String]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    paragraphs
+        .iter()
+        .map(|p| {
+            let hash = Sha256::digest(p.as_bytes())
+                .iter()
+                .take(6)
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>();
+            let count = seen.entry(hash.clone()).or_default();
+            *count += 1;
+            match *count {
+                1 => format!("p-{hash}"),
+                n => format!("p-{hash}-{n}"),
+            }
+        })
+        .collect()
+}
+
+/// Trims `text` and turns every run of whitespace in it into one space, the
+/// way browsers show it.
+pub fn collapse_spaces(text: &str) -> String {
+    SPACE_RE.replace_all(text.trim(), " ").to_string()
+}
@@ -0,0 +1,180 @@
+use crate::{
+    activities::{
+        follow::Follow,
+        undo::{Undo, UndoObject},
+    },
+    objects::{actor::DbActor, person::User},
+    restriction, user_filter, DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    traits::{Actor, Object},
+};
+use sqlx::{query, PgPool};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Debug, Error)]
+pub enum FollowError {
+    #[error("Follow: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Follow: NotFound")]
+    NotFound,
+    #[error("Follow: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+/// Finds the user or novel behind the actor URL `target`. Hidden novels and
+/// suspended users can't be followed.
+async fn resolve_target(target: &str, data: &Data<DbHandle>) -> Result<DbActor, FollowError> {
+    let url: Url = target
+        .trim()
+        .parse()
+        .map_err(|_| FollowError::BadRequest("Invalid actor".to_string()))?;
+    let actor = ObjectId::<DbActor>::from(url)
+        .dereference(data)
+        .await
+        .map_err(|_| FollowError::NotFound)?;
+    let unavailable = match &actor {
+        DbActor::User(user) => restriction::is_suspended(data.app_data(), &user.apub_id)
+            .await
+            .map_err(|e| FollowError::InternalServerError(e.to_string()))?,
+        DbActor::Novel(novel) => novel.hidden,
+    };
+    if unavailable {
+        return Err(FollowError::NotFound);
+    }
+    Ok(actor)
+}
+
+async fn local_user(owner: &str, data: &Data<DbHandle>) -> Result<User, FollowError> {
+    User::read_from_id(
+        owner
+            .parse()
+            .map_err(|e: url::ParseError| FollowError::InternalServerError(e.to_string()))?,
+        data,
+    )
+    .await
+    .map_err(|e| FollowError::InternalServerError(e.to_string()))?
+    .ok_or_else(|| FollowError::InternalServerError("Local user not found".to_string()))
+}
+
+/// Makes `owner` follow `target`, a user or novel.
+///
+/// Follows of remote actors take effect right away and are federated with a
+/// `Follow` activity. They are dropped again if the actor sends a `Reject`.
+/// Local actors whose owners blocked `owner` can't be followed.
+pub async fn follow(data: &Data<DbHandle>, owner: &str, target: &str) -> Result<(), FollowError> {
+    let actor = resolve_target(target, data).await?;
+    let target = actor.id().to_string();
+    if target.eq_ignore_ascii_case(owner) {
+        return Err(FollowError::BadRequest(
+            "You can't follow yourself".to_string(),
+        ));
+    }
+
+    let owners = match &actor {
+        DbActor::User(user) => vec![user.apub_id.clone()],
+        DbActor::Novel(novel) => novel.authors.iter().map(|a| a.apub_id.clone()).collect(),
+    };
+    let owner_url: Url = owner
+        .parse()
+        .map_err(|e: url::ParseError| FollowError::InternalServerError(e.to_string()))?;
+    for o in owners {
+        if user_filter::is_blocked(data.app_data(), &o, &owner_url)
+            .await
+            .map_err(|e| FollowError::InternalServerError(e.to_string()))?
+        {
+            return Err(FollowError::BadRequest(
+                "You can't follow this account".to_string(),
+            ));
+        }
+    }
+
+    let inserted = query!(
+        "INSERT INTO follows (follower, following) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        owner,
+        target
+    )
+    .execute(data.app_data().as_ref())
+    .await
+    .map_err(|e| FollowError::InternalServerError(e.to_string()))?
+    .rows_affected()
+        > 0;
+
+    if inserted && actor.private_key_pem().is_none() {
+        let follower = local_user(owner, data).await?;
+        // The follow already applies locally, so a failed delivery is only
+        // logged. It also shows up in the admin dashboard.
+        match Follow::send(&follower, &actor, data).await {
+            Ok(id) => {
+                query!(
+                    r#"UPDATE follows SET activity_id=$3
+                       WHERE lower(follower)=$1 AND lower(following)=$2"#,
+                    owner.to_lowercase(),
+                    target.to_lowercase(),
+                    id.to_string()
+                )
+                .execute(data.app_data().as_ref())
+                .await
+                .map_err(|e| FollowError::InternalServerError(e.to_string()))?;
+            }
+            Err(e) => log::warn!("Failed to send follow of {target}: {e}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Stops `owner` from following `target`. Withdrawing a federated follow
+/// sends an `Undo`.
+pub async fn unfollow(data: &Data<DbHandle>, owner: &str, target: &str) -> Result<(), FollowError> {
+    let removed = query!(
+        r#"DELETE FROM follows
+           WHERE lower(follower)=$1 AND lower(following)=$2
+           RETURNING following, activity_id"#,
+        owner.to_lowercase(),
+        target.trim().to_lowercase()
+    )
+    .fetch_optional(data.app_data().as_ref())
+    .await
+    .map_err(|e| FollowError::InternalServerError(e.to_string()))?
+    .ok_or(FollowError::NotFound)?;
+
+    if let Some(id) = removed.activity_id {
+        let follower = local_user(owner, data).await?;
+        let undo = async {
+            let target: Url = removed.following.parse()?;
+            let actor = ObjectId::<DbActor>::from(target.clone())
+                .dereference(data)
+                .await?;
+            let follow = Follow::new(&follower, target, id.parse()?)?;
+            Undo::send(&follower, UndoObject::Follow(follow), actor.inbox(), data).await
+        };
+        if let Err(e) = undo.await {
+            log::warn!(
+                "Failed to send undo of follow of {}: {e}",
+                removed.following
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `owner` follows `target`.
+pub async fn is_following(pool: &PgPool, owner: &str, target: &str) -> Result<bool, FollowError> {
+    Ok(query!(
+        r#"SELECT EXISTS(
+             SELECT 1 FROM follows
+             WHERE lower(follower)=$1 AND lower(following)=$2
+           ) AS "following!""#,
+        owner.to_lowercase(),
+        target.trim().to_lowercase()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| FollowError::InternalServerError(e.to_string()))?
+    .following)
+}
@@ -1,2 +1,13 @@
+pub mod bookmark;
+pub mod browse;
 pub mod chapter;
+pub mod follow;
 pub mod novel;
+pub mod progress;
+pub mod search;
+pub mod shelf;
+pub mod tag;
+pub mod takeout;
+pub mod timeline;
+pub mod trending;
+pub mod user;
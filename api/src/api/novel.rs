@@ -1,19 +1,22 @@
 use crate::{
+    activities::create::Create,
+    domain_policy::resolve_actor,
     enums::{Genres, Roles},
-    objects::novel::{DbNovel, Novel},
-    util::{AppState, TAG_RE},
+    objects::{
+        novel::{DbNovel, Novel},
+        person::User,
+    },
+    restriction,
+    util::{local_handle, parse_tags, AppState},
     DbHandle,
 };
 use activitypub_federation::{
-    config::Data,
-    fetch::webfinger::{extract_webfinger_name, webfinger_resolve_actor},
-    http_signatures::generate_actor_keypair,
-    traits::Object,
+    config::Data, fetch::webfinger::extract_webfinger_name,
+    http_signatures::generate_actor_keypair, traits::Object,
 };
-use actix_session::Session;
 use actix_web::web;
+use chrono::{DateTime, Utc};
 use isolang::Language;
-use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use sqlx::query;
 use thiserror::Error;
@@ -40,19 +43,13 @@ pub struct NewNovel {
     pub tags: String,
 }
 
+/// Creates a novel with `apub_id` as its first author.
 pub async fn create_novel(
     state: web::Data<AppState>,
     pool: Data<DbHandle>,
-    session: Session,
+    apub_id: String,
     info: NewNovel,
 ) -> Result<String, CreateNovelError> {
-    let apub_id = match session.get::<String>("id") {
-        Err(e) => return Err(CreateNovelError::InternalServerError(e.to_string())),
-        Ok(Some(u)) => u,
-        Ok(None) => return Err(CreateNovelError::Unauthorized("Not signed in".to_string())),
-    };
-    session.renew();
-
     let scheme = &state.scheme;
 
     let re = regex::Regex::new(r#"[\r\n]+"#).unwrap();
@@ -61,12 +58,11 @@ pub async fn create_novel(
         None => return Err(CreateNovelError::BadRequest("Invalid language".to_string())),
         Some(l) => l.to_639_1(),
     };
-    let tags = TAG_RE
-        .find_iter(&info.tags)
-        .map(|t| t.as_str().to_string())
-        .sorted_by(|a, b| a.cmp(b))
-        .dedup_by(|a, b| a.to_lowercase() == b.to_lowercase())
-        .collect_vec();
+    let tags = parse_tags(&info.tags);
+    let sensitive = info.sensitive
+        || restriction::any_force_sensitive(pool.app_data(), &[apub_id.clone()])
+            .await
+            .map_err(|e| CreateNovelError::InternalServerError(e.to_string()))?;
     let uuid = Uuid::new_v4();
     let keypair = generate_actor_keypair()
         .map_err(|e| CreateNovelError::InternalServerError(e.to_string()))?;
@@ -89,7 +85,7 @@ pub async fn create_novel(
         info.genre.to_string(),
         tags.as_slice(),
         lang,
-        info.sensitive,
+        sensitive,
         format!("{}/inbox", url),
         format!("{}/outbox", url),
         keypair.public_key,
@@ -111,9 +107,73 @@ pub async fn create_novel(
     .execute(pool.app_data().as_ref())
     .await
     .map_err(|e| CreateNovelError::InternalServerError(e.to_string()))?;
+
+    // The novel is already stored, so a failed delivery is only logged.
+    let announce = async {
+        let author = User::read_from_id(apub_id.parse()?, &pool)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Local user not found"))?;
+        Create::send(&author, id.parse()?, &pool).await
+    };
+    if let Err(e) = announce.await {
+        log::warn!("Failed to send creation of {id}: {e}");
+    }
+
     Ok(uuid.to_string().to_lowercase())
 }
 
+/// A novel as shown in lists like tag pages and timelines.
+#[derive(Debug, Serialize)]
+pub struct NovelListing {
+    pub apub_id: String,
+    /// Where the novel is shown on this instance.
+    pub path: String,
+    pub title: String,
+    pub summary: String,
+    pub genre: String,
+    pub tags: Vec<String>,
+    pub language: String,
+    pub sensitive: bool,
+    pub published: DateTime<Utc>,
+    pub local: bool,
+}
+
+impl NovelListing {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        apub_id: String,
+        preferred_username: Uuid,
+        title: String,
+        summary: String,
+        genre: String,
+        tags: Vec<String>,
+        language: String,
+        sensitive: bool,
+        published: DateTime<Utc>,
+        local: bool,
+    ) -> Result<Self, url::ParseError> {
+        Ok(Self {
+            path: format!(
+                "/novel/{}",
+                local_handle(
+                    &preferred_username.to_string().to_lowercase(),
+                    &apub_id,
+                    local
+                )?
+            ),
+            apub_id,
+            title,
+            summary,
+            genre,
+            tags,
+            language,
+            sensitive,
+            published,
+            local,
+        })
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum GetNovelError {
     #[error("GetNovel PermanentRedirect: {0}")]
@@ -122,6 +182,9 @@ pub enum GetNovelError {
     WebfingerNotFound,
     #[error("GetNovel NovelNotFound")]
     NovelNotFound,
+    /// Every author of the novel is suspended.
+    #[error("GetNovel Gone: {0}")]
+    Gone(String),
     #[error("GetNovel InternalServerError: {0}")]
     InternalServerError(String),
 }
@@ -133,7 +196,7 @@ pub async fn get_novel(uuid: String, data: &Data<DbHandle>) -> Result<Box<Novel>
         return Err(GetNovelError::PermanentRedirect(format!("/novel/{id}")));
     }
     let novel = if uuid.contains('@') {
-        webfinger_resolve_actor(&uuid, data)
+        resolve_actor(&uuid, data)
             .await
             .map_err(|_| GetNovelError::NovelNotFound)?
     } else {
@@ -144,6 +207,22 @@ pub async fn get_novel(uuid: String, data: &Data<DbHandle>) -> Result<Box<Novel>
             Ok(None) => return Err(GetNovelError::NovelNotFound),
         }
     };
+    if novel.hidden {
+        return Err(GetNovelError::NovelNotFound);
+    }
+    let mut suspended = !novel.authors.is_empty();
+    for author in &novel.authors {
+        if !restriction::is_suspended(data.app_data(), &author.apub_id)
+            .await
+            .map_err(|e| GetNovelError::InternalServerError(e.to_string()))?
+        {
+            suspended = false;
+            break;
+        }
+    }
+    if suspended {
+        return Err(GetNovelError::Gone(novel.apub_id));
+    }
     match novel.into_json(data).await {
         Ok(v) => Ok(Box::new(v)),
         Err(e) => Err(GetNovelError::InternalServerError(e.to_string())),
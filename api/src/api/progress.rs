@@ -0,0 +1,226 @@
+use crate::api::novel::NovelListing;
+use serde::Serialize;
+use sqlx::{query, PgPool};
+use thiserror::Error;
+
+/// How many followed novels with unread chapters are listed.
+pub const UNREAD_LIMIT: i64 = 20;
+
+#[derive(Debug, Error)]
+pub enum ProgressError {
+    #[error("Progress: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Progress: NotFound")]
+    NotFound,
+    #[error("Progress: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+/// Where to continue reading a novel.
+#[derive(Debug, Serialize)]
+pub struct ReadingPosition {
+    /// The first unread chapter.
+    pub chapter: String,
+    /// How far into `chapter` the reader scrolled, from 0 to 1.
+    pub position: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NovelProgress {
+    /// Unset once every chapter is read.
+    pub resume: Option<ReadingPosition>,
+    /// The chapters read so far, lowercased.
+    pub read: Vec<String>,
+    pub unread: i64,
+}
+
+/// A followed novel with chapters the reader hasn't read yet.
+#[derive(Debug, Serialize)]
+pub struct UnreadNovel {
+    pub novel: NovelListing,
+    pub unread: i64,
+    pub resume: ReadingPosition,
+}
+
+/// How far `reader` got in `novel`, by chapter.
+pub async fn get_progress(
+    pool: &PgPool,
+    reader: &str,
+    novel: &str,
+) -> Result<NovelProgress, ProgressError> {
+    let chapters = query!(
+        r#"SELECT c.apub_id,
+             coalesce(c.sequence < p.sequence OR (c.sequence = p.sequence AND p.position >= 1),
+                      false) AS "read!",
+             CASE WHEN c.sequence = p.sequence THEN p.position ELSE 0 END AS "position!"
+           FROM chapters c
+           LEFT JOIN reading_progress p ON p.reader=$1 AND p.novel=lower(c.audience)
+           WHERE lower(c.audience)=$2
+           ORDER BY c.sequence"#,
+        reader.to_lowercase(),
+        novel.trim().to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ProgressError::InternalServerError(e.to_string()))?;
+
+    let resume = chapters.iter().find(|c| !c.read).map(|c| ReadingPosition {
+        chapter: c.apub_id.clone(),
+        position: c.position,
+    });
+    let unread = chapters.iter().filter(|c| !c.read).count() as i64;
+    let read = chapters
+        .into_iter()
+        .filter(|c| c.read)
+        .map(|c| c.apub_id.to_lowercase())
+        .collect();
+
+    Ok(NovelProgress {
+        resume,
+        read,
+        unread,
+    })
+}
+
+/// The novels `reader` follows that have unread chapters, those with the
+/// newest ones first. Novels whose authors are all suspended are left out,
+/// like `get_novel` does.
+pub async fn unread_following(
+    pool: &PgPool,
+    reader: &str,
+) -> Result<Vec<UnreadNovel>, ProgressError> {
+    query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published, n.private_key IS NOT NULL AS "local!",
+             u.unread AS "unread!", u.chapter AS "chapter!", u.position AS "position!"
+           FROM follows f
+           JOIN novels n ON lower(n.apub_id)=lower(f.following)
+           LEFT JOIN reading_progress p ON p.reader=$1 AND p.novel=lower(n.apub_id)
+           CROSS JOIN LATERAL (
+             SELECT count(*) AS unread,
+               (array_agg(c.apub_id ORDER BY c.sequence))[1] AS chapter,
+               (array_agg(CASE WHEN c.sequence = p.sequence THEN p.position ELSE 0 END
+                          ORDER BY c.sequence))[1] AS position,
+               max(c.published) AS latest
+             FROM chapters c
+             WHERE lower(c.audience)=lower(n.apub_id)
+               AND (p.sequence IS NULL OR c.sequence > p.sequence
+                    OR (c.sequence = p.sequence AND p.position < 1))
+           ) u
+           WHERE lower(f.follower)=$1 AND u.unread > 0
+             AND NOT n.hidden AND NOT authors_suspended(n.apub_id)
+           ORDER BY u.latest DESC
+           LIMIT $2"#,
+        reader.to_lowercase(),
+        UNREAD_LIMIT
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ProgressError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        Ok(UnreadNovel {
+            novel: NovelListing::new(
+                row.apub_id,
+                row.preferred_username,
+                row.title,
+                row.summary,
+                row.genre,
+                row.tags,
+                row.language,
+                row.sensitive,
+                row.published,
+                row.local,
+            )
+            .map_err(|e| ProgressError::InternalServerError(e.to_string()))?,
+            unread: row.unread,
+            resume: ReadingPosition {
+                chapter: row.chapter,
+                position: row.position,
+            },
+        })
+    })
+    .collect()
+}
+
+/// Records that `reader` got `position` far into `chapter`. Progress only
+/// moves forward, so that going back to an earlier chapter doesn't lose it.
+pub async fn set_position(
+    pool: &PgPool,
+    reader: &str,
+    chapter: &str,
+    position: f32,
+) -> Result<(), ProgressError> {
+    if !(0.0..=1.0).contains(&position) {
+        return Err(ProgressError::BadRequest(
+            "Positions must be between 0 and 1".to_string(),
+        ));
+    }
+    advance(pool, reader, chapter, position).await
+}
+
+/// Marks `chapter` read or unread for `reader`. Progress is kept per novel,
+/// so marking a chapter read marks the ones before it read too, and marking
+/// it unread does the same for the ones after it.
+pub async fn mark_read(
+    pool: &PgPool,
+    reader: &str,
+    chapter: &str,
+    read: bool,
+) -> Result<(), ProgressError> {
+    if read {
+        return advance(pool, reader, chapter, 1.0).await;
+    }
+
+    let (novel, sequence) = locate(pool, chapter).await?;
+    query!(
+        r#"UPDATE reading_progress SET sequence=$3, position=0, updated=now()
+           WHERE reader=$1 AND novel=$2 AND ($3, 0::real) < (sequence, position)"#,
+        reader.to_lowercase(),
+        novel,
+        sequence
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| ProgressError::InternalServerError(e.to_string()))?;
+    Ok(())
+}
+
+/// The lowercased novel and the sequence of `chapter`.
+async fn locate(pool: &PgPool, chapter: &str) -> Result<(String, i32), ProgressError> {
+    query!(
+        r#"SELECT lower(c.audience) AS "novel!", c.sequence
+           FROM chapters c
+           JOIN novels n ON lower(n.apub_id)=lower(c.audience)
+           WHERE lower(c.apub_id)=$1 AND NOT n.hidden"#,
+        chapter.trim().to_lowercase()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ProgressError::InternalServerError(e.to_string()))?
+    .map(|row| (row.novel, row.sequence))
+    .ok_or(ProgressError::NotFound)
+}
+
+async fn advance(
+    pool: &PgPool,
+    reader: &str,
+    chapter: &str,
+    position: f32,
+) -> Result<(), ProgressError> {
+    let (novel, sequence) = locate(pool, chapter).await?;
+    query!(
+        r#"INSERT INTO reading_progress (reader, novel, sequence, position)
+           VALUES ($1, $2, $3, $4)
+           ON CONFLICT (reader, novel) DO UPDATE SET sequence=$3, position=$4, updated=now()
+           WHERE ($3::int, $4::real) > (reading_progress.sequence, reading_progress.position)"#,
+        reader.to_lowercase(),
+        novel,
+        sequence,
+        position
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| ProgressError::InternalServerError(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,407 @@
+use crate::{
+    api::novel::NovelListing,
+    domain_policy::resolve_actor,
+    enums::Genres,
+    objects::{chapter::Chapter, novel::DbNovel, person::User},
+    restriction, throttle,
+    util::{is_local_url, language_code, local_handle, RedisHandle},
+    DbHandle,
+};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId, traits::Actor};
+use serde::{Deserialize, Serialize};
+use sqlx::{query, PgPool};
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error("Search: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Search: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Search: NotFound: {0}")]
+    NotFound(String),
+    #[error("Search: TooManyRequests: {0}")]
+    TooManyRequests(String),
+    #[error("Search: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub genre: Option<Genres>,
+    /// An ISO 639-1 code or an English language name.
+    pub language: Option<String>,
+    pub tag: Option<String>,
+    /// Only sensitive novels when `true`, none when `false` and both when
+    /// unset.
+    pub sensitive: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NovelHit {
+    #[serde(flatten)]
+    pub novel: NovelListing,
+    pub rank: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChapterHit {
+    pub apub_id: String,
+    pub novel: String,
+    pub novel_username: Uuid,
+    pub novel_title: String,
+    pub novel_local: bool,
+    pub sequence: i32,
+    pub title: String,
+    /// A fragment of the chapter around the best match, with matches wrapped
+    /// in `<b>`.
+    pub headline: String,
+    pub sensitive: bool,
+    pub rank: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserHit {
+    pub apub_id: String,
+    pub preferred_username: String,
+    pub name: String,
+    pub summary: String,
+    pub local: bool,
+    pub rank: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResults {
+    pub novels: Vec<NovelHit>,
+    pub chapters: Vec<ChapterHit>,
+    pub users: Vec<UserHit>,
+}
+
+/// Searches novels, chapters and users, best matches first. The query is
+/// parsed with `websearch_to_tsquery` in the language of each novel, so it
+/// supports quoted phrases, `or` and `-excluded` words.
+///
+/// Hidden novels, suspended and silenced accounts, accounts on silenced
+/// domains and novels by any of them are left out. The filters only apply to
+/// novels and chapters.
+pub async fn search(pool: &PgPool, query: SearchQuery) -> Result<SearchResults, SearchError> {
+    let q = query.q.trim();
+    if q.is_empty() {
+        return Err(SearchError::BadRequest("Empty search".to_string()));
+    }
+    if q.chars().count() > 200 {
+        return Err(SearchError::BadRequest(
+            "Search must be at most 200 characters".to_string(),
+        ));
+    }
+    let language = match query.language.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(l) => Some(
+            language_code(l)
+                .ok_or_else(|| SearchError::BadRequest("Unknown language".to_string()))?,
+        ),
+    };
+    let genre = query.genre.map(|g| g.to_string());
+    let tag = query
+        .tag
+        .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+        .filter(|t| !t.is_empty());
+    let limit = query.limit.unwrap_or(20).clamp(1, 50);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let novels = query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published, n.private_key IS NOT NULL AS "local!",
+             ts_rank(n.search, websearch_to_tsquery(lang_regconfig(n.language), $1)) AS "rank!"
+           FROM novels n
+           WHERE n.search @@ websearch_to_tsquery(lang_regconfig(n.language), $1)
+             AND NOT n.hidden
+             AND ($2::text IS NULL OR n.genre=$2)
+             AND ($3::text IS NULL OR n.language=$3)
+             AND ($4::text IS NULL OR lower_tags(n.tags) @> ARRAY[$4])
+             AND ($5::boolean IS NULL OR n.sensitive=$5)
+             AND NOT authors_hidden(n.apub_id)
+           ORDER BY 11 DESC, n.published DESC
+           LIMIT $6 OFFSET $7"#,
+        q,
+        genre,
+        language,
+        tag,
+        query.sensitive,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        Ok(NovelHit {
+            novel: NovelListing::new(
+                row.apub_id,
+                row.preferred_username,
+                row.title,
+                row.summary,
+                row.genre,
+                row.tags,
+                row.language,
+                row.sensitive,
+                row.published,
+                row.local,
+            )
+            .map_err(|e| SearchError::InternalServerError(e.to_string()))?,
+            rank: row.rank,
+        })
+    })
+    .collect::<Result<_, _>>()?;
+
+    let chapters = query!(
+        r#"SELECT c.apub_id, n.apub_id AS novel, n.preferred_username AS novel_username,
+             n.title AS novel_title, n.private_key IS NOT NULL AS "novel_local!",
+             c.sequence, c.title,
+             ts_headline(lang_regconfig(n.language), c.content,
+               websearch_to_tsquery(lang_regconfig(n.language), $1),
+               'MaxFragments=1, MaxWords=30, MinWords=10') AS "headline!",
+             c.sensitive OR n.sensitive AS "sensitive!",
+             ts_rank(c.search, websearch_to_tsquery(lang_regconfig(n.language), $1)) AS "rank!"
+           FROM chapters c
+           JOIN novels n ON lower(n.apub_id)=lower(c.audience)
+           WHERE c.search @@ websearch_to_tsquery(lang_regconfig(n.language), $1)
+             AND NOT n.hidden
+             AND ($2::text IS NULL OR n.genre=$2)
+             AND ($3::text IS NULL OR n.language=$3)
+             AND ($4::text IS NULL OR lower_tags(n.tags) @> ARRAY[$4])
+             AND ($5::boolean IS NULL OR (c.sensitive OR n.sensitive)=$5)
+             AND NOT authors_hidden(n.apub_id)
+           ORDER BY 10 DESC, c.published DESC
+           LIMIT $6 OFFSET $7"#,
+        q,
+        genre,
+        language,
+        tag,
+        query.sensitive,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| ChapterHit {
+        apub_id: row.apub_id,
+        novel: row.novel,
+        novel_username: row.novel_username,
+        novel_title: row.novel_title,
+        novel_local: row.novel_local,
+        sequence: row.sequence,
+        title: row.title,
+        headline: row.headline,
+        sensitive: row.sensitive,
+        rank: row.rank,
+    })
+    .collect();
+
+    let users = query!(
+        r#"SELECT u.apub_id, u.preferred_username, u.name, u.summary,
+             u.private_key IS NOT NULL AS "local!",
+             ts_rank(u.search, websearch_to_tsquery('simple', $1)) AS "rank!"
+           FROM users u
+           WHERE u.search @@ websearch_to_tsquery('simple', $1)
+             AND u.approved
+             AND NOT actor_hidden(u.apub_id)
+           ORDER BY 6 DESC, u.preferred_username
+           LIMIT $2 OFFSET $3"#,
+        q,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| UserHit {
+        apub_id: row.apub_id,
+        preferred_username: row.preferred_username,
+        name: row.name,
+        summary: row.summary,
+        local: row.local,
+        rank: row.rank,
+    })
+    .collect();
+
+    Ok(SearchResults {
+        novels,
+        chapters,
+        users,
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResolvedKind {
+    Novel,
+    User,
+}
+
+/// A remote or local actor found from a handle or URL.
+#[derive(Debug, Serialize)]
+pub struct Resolved {
+    pub kind: ResolvedKind,
+    pub apub_id: String,
+    /// Where the actor is shown on this instance.
+    pub path: String,
+}
+
+/// Whether `q` looks like something [`resolve`] handles rather than words to
+/// search for.
+pub fn is_resolvable(q: &str) -> bool {
+    let q = q.trim();
+    !q.contains(char::is_whitespace)
+        && (q.starts_with("https://")
+            || q.starts_with("http://")
+            || q.trim_start_matches('@').contains('@'))
+}
+
+fn found_novel(novel: DbNovel) -> Result<Resolved, SearchError> {
+    if novel.hidden {
+        return Err(SearchError::NotFound("Novel not found".to_string()));
+    }
+    let local = novel.private_key_pem().is_some();
+    Ok(Resolved {
+        kind: ResolvedKind::Novel,
+        path: format!(
+            "/novel/{}",
+            local_handle(
+                &novel.preferred_username.to_string().to_lowercase(),
+                &novel.apub_id,
+                local
+            )
+            .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+        ),
+        apub_id: novel.apub_id,
+    })
+}
+
+async fn found_user(user: User, data: &Data<DbHandle>) -> Result<Resolved, SearchError> {
+    if restriction::is_suspended(data.app_data(), &user.apub_id)
+        .await
+        .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+    {
+        return Err(SearchError::NotFound("User not found".to_string()));
+    }
+    let local = user.private_key_pem().is_some();
+    Ok(Resolved {
+        kind: ResolvedKind::User,
+        path: format!(
+            "/user/{}",
+            local_handle(&user.preferred_username, &user.apub_id, local)
+                .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+        ),
+        apub_id: user.apub_id,
+    })
+}
+
+/// Checks that `user` may make this instance fetch a remote object. Only
+/// signed in users may, and only so many times, since every lookup makes
+/// requests to whatever server it names and stores what comes back.
+async fn allow_fetch(user: Option<&str>, redis: &RedisHandle) -> Result<(), SearchError> {
+    let user = match user {
+        Some(user) => user,
+        None => {
+            return Err(SearchError::Unauthorized(
+                "Sign in to look up novels and users on other servers".to_string(),
+            ))
+        }
+    };
+    if !throttle::resolve(redis, user)
+        .await
+        .map_err(SearchError::InternalServerError)?
+    {
+        return Err(SearchError::TooManyRequests(
+            "Too many lookups on other servers, try again later".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Finds the novel or user behind a handle like `uuid@example.com` or
+/// `user@example.com`, or behind the URL of a novel, user or chapter, and
+/// stores it if it is remote. Chapters resolve to their novel.
+///
+/// Anything on another server is only fetched for the signed in `user`,
+/// see [`allow_fetch`].
+///
+/// Returns `None` when `q` isn't a handle or URL, so that it can be searched
+/// for instead.
+pub async fn resolve(
+    q: &str,
+    data: &Data<DbHandle>,
+    redis: &RedisHandle,
+    user: Option<&str>,
+) -> Result<Option<Resolved>, SearchError> {
+    if !is_resolvable(q) {
+        return Ok(None);
+    }
+    let q = q.trim();
+    let not_found = || SearchError::NotFound(format!("Couldn't find {q}"));
+
+    if let Ok(url) = q.parse::<Url>() {
+        if !is_local_url(&url, data.domain()) {
+            allow_fetch(user, redis).await?;
+        }
+        if let Ok(novel) = ObjectId::<DbNovel>::from(url.clone())
+            .dereference(data)
+            .await
+        {
+            return found_novel(novel).map(Some);
+        }
+        if let Ok(user) = ObjectId::<User>::from(url.clone()).dereference(data).await {
+            return found_user(user, data).await.map(Some);
+        }
+        let chapter = ObjectId::<Chapter>::from(url)
+            .dereference(data)
+            .await
+            .map_err(|_| not_found())?;
+        let novel =
+            ObjectId::<DbNovel>::from(chapter.audience.parse::<Url>().map_err(|_| not_found())?)
+                .dereference(data)
+                .await
+                .map_err(|_| not_found())?;
+        return found_novel(novel).map(Some);
+    }
+
+    let handle = q.trim_start_matches('@');
+    let (name, domain) = handle.rsplit_once('@').ok_or_else(not_found)?;
+    let local = domain.eq_ignore_ascii_case(data.domain());
+    if !local {
+        allow_fetch(user, redis).await?;
+    }
+    if let Ok(uuid) = Uuid::parse_str(name) {
+        let novel = if local {
+            DbNovel::read_from_uuid(uuid, data)
+                .await
+                .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+        } else {
+            resolve_actor::<DbNovel>(handle, data).await.ok()
+        };
+        if let Some(novel) = novel {
+            return found_novel(novel).map(Some);
+        }
+    }
+    let user = if local {
+        User::read_from_username(name, data.app_data())
+            .await
+            .map_err(|e| SearchError::InternalServerError(e.to_string()))?
+    } else {
+        resolve_actor::<User>(handle, data).await.ok()
+    };
+    match user {
+        Some(user) => found_user(user, data).await.map(Some),
+        None => Err(not_found()),
+    }
+}
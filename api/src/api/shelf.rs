@@ -0,0 +1,355 @@
+use crate::{api::novel::NovelListing, enums::ShelfKind, objects::novel::DbNovel, DbHandle};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, PgPool};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ShelfError {
+    #[error("Shelf: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Shelf: NotFound")]
+    NotFound,
+    #[error("Shelf: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct Shelf {
+    pub id: Uuid,
+    pub owner: String,
+    pub kind: ShelfKind,
+    pub name: String,
+    /// Shown on the owner's profile and federated.
+    pub public: bool,
+    /// How many novels are on it.
+    pub novels: i64,
+    pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShelfPage {
+    pub shelf: Shelf,
+    pub owner_name: String,
+    /// Where the owner is shown on this instance.
+    pub owner_path: String,
+    /// Newest additions first.
+    pub novels: Vec<NovelListing>,
+}
+
+/// Creates the built-in shelves of `owner` that don't exist yet.
+async fn ensure_builtin(pool: &PgPool, owner: &str) -> Result<(), ShelfError> {
+    for kind in ShelfKind::iter() {
+        if let Some(name) = kind.default_name() {
+            query!(
+                r#"INSERT INTO shelves (owner, kind, name) VALUES ($1, $2, $3)
+                   ON CONFLICT DO NOTHING"#,
+                owner,
+                kind.to_string(),
+                name
+            )
+            .execute(pool)
+            .await
+            .map_err(|e| ShelfError::InternalServerError(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Lists the shelves of the local user `owner`, built-in ones first. Only
+/// public ones are returned unless `all` is set.
+pub async fn list_shelves(pool: &PgPool, owner: &str, all: bool) -> Result<Vec<Shelf>, ShelfError> {
+    if all {
+        ensure_builtin(pool, owner).await?;
+    }
+    query!(
+        r#"SELECT s.id, s.owner, s.kind, s.name, s.public, s.published,
+             (SELECT count(*) FROM shelf_items i
+              JOIN novels n ON lower(n.apub_id)=lower(i.novel)
+              WHERE i.shelf=s.id AND NOT n.hidden) AS "novels!"
+           FROM shelves s
+           WHERE lower(s.owner)=$1 AND ($2 OR s.public)
+           ORDER BY s.kind='custom', s.published, s.name"#,
+        owner.to_lowercase(),
+        all
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        Ok(Shelf {
+            id: row.id,
+            owner: row.owner,
+            kind: ShelfKind::from_str(&row.kind)
+                .map_err(|e| ShelfError::InternalServerError(e.to_string()))?,
+            name: row.name,
+            public: row.public,
+            novels: row.novels,
+            published: row.published,
+        })
+    })
+    .collect()
+}
+
+/// Reads a shelf and its novels. Private shelves are only found by their
+/// owner, `viewer`.
+pub async fn get_shelf(
+    pool: &PgPool,
+    viewer: Option<&str>,
+    id: Uuid,
+) -> Result<ShelfPage, ShelfError> {
+    let row = query!(
+        r#"SELECT s.id, s.owner, s.kind, s.name, s.public, s.published,
+             u.preferred_username, u.name AS owner_name
+           FROM shelves s
+           JOIN users u ON lower(u.apub_id)=lower(s.owner)
+           WHERE s.id=$1 AND (s.public OR lower(s.owner)=$2)
+             AND u.suspended_at IS NULL"#,
+        id,
+        viewer.map(str::to_lowercase)
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?
+    .ok_or(ShelfError::NotFound)?;
+
+    let novels = query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published, n.private_key IS NOT NULL AS "local!"
+           FROM shelf_items i
+           JOIN novels n ON lower(n.apub_id)=lower(i.novel)
+           WHERE i.shelf=$1 AND NOT n.hidden AND NOT authors_hidden(n.apub_id)
+           ORDER BY i.published DESC"#,
+        id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        NovelListing::new(
+            row.apub_id,
+            row.preferred_username,
+            row.title,
+            row.summary,
+            row.genre,
+            row.tags,
+            row.language,
+            row.sensitive,
+            row.published,
+            row.local,
+        )
+        .map_err(|e| ShelfError::InternalServerError(e.to_string()))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ShelfPage {
+        shelf: Shelf {
+            id: row.id,
+            owner: row.owner,
+            kind: ShelfKind::from_str(&row.kind)
+                .map_err(|e| ShelfError::InternalServerError(e.to_string()))?,
+            name: row.name,
+            public: row.public,
+            novels: novels.len() as i64,
+            published: row.published,
+        },
+        owner_name: row.owner_name,
+        owner_path: format!("/user/{}", row.preferred_username),
+        novels,
+    })
+}
+
+/// Creates a custom shelf for `owner`.
+pub async fn create_shelf(
+    pool: &PgPool,
+    owner: &str,
+    name: &str,
+    public: bool,
+) -> Result<Uuid, ShelfError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(ShelfError::BadRequest(
+            "Names must be between 1 and 100 characters".to_string(),
+        ));
+    }
+    ensure_builtin(pool, owner).await?;
+    query!(
+        r#"INSERT INTO shelves (owner, kind, name, public) VALUES ($1, $2, $3, $4)
+           ON CONFLICT DO NOTHING
+           RETURNING id"#,
+        owner,
+        ShelfKind::Custom.to_string(),
+        name,
+        public
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?
+    .map(|row| row.id)
+    .ok_or_else(|| ShelfError::BadRequest("You already have a shelf with this name".to_string()))
+}
+
+/// Makes a shelf of `owner` public or private.
+pub async fn set_public(
+    pool: &PgPool,
+    owner: &str,
+    id: Uuid,
+    public: bool,
+) -> Result<(), ShelfError> {
+    let res = query!(
+        "UPDATE shelves SET public=$3 WHERE id=$1 AND lower(owner)=$2",
+        id,
+        owner.to_lowercase(),
+        public
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?;
+    if res.rows_affected() == 0 {
+        return Err(ShelfError::NotFound);
+    }
+    Ok(())
+}
+
+/// Deletes a custom shelf of `owner`. Built-in shelves can't be deleted.
+pub async fn delete_shelf(pool: &PgPool, owner: &str, id: Uuid) -> Result<(), ShelfError> {
+    let res = query!(
+        "DELETE FROM shelves WHERE id=$1 AND lower(owner)=$2 AND kind=$3",
+        id,
+        owner.to_lowercase(),
+        ShelfKind::Custom.to_string()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?;
+    if res.rows_affected() == 0 {
+        return Err(ShelfError::NotFound);
+    }
+    Ok(())
+}
+
+/// Puts `novel`, a local or remote novel URL, on a shelf of `owner`. Adding
+/// it to a built-in shelf takes it off the other built-in ones.
+pub async fn add_novel(
+    data: &Data<DbHandle>,
+    owner: &str,
+    id: Uuid,
+    novel: &str,
+) -> Result<(), ShelfError> {
+    let url: Url = novel
+        .trim()
+        .parse()
+        .map_err(|_| ShelfError::BadRequest("Invalid novel".to_string()))?;
+    let novel = ObjectId::<DbNovel>::from(url)
+        .dereference(data)
+        .await
+        .map_err(|_| ShelfError::NotFound)?;
+    if novel.hidden {
+        return Err(ShelfError::NotFound);
+    }
+
+    let mut tx = data
+        .app_data()
+        .begin()
+        .await
+        .map_err(|e| ShelfError::InternalServerError(e.to_string()))?;
+
+    let kind = query!(
+        "SELECT kind FROM shelves WHERE id=$1 AND lower(owner)=$2",
+        id,
+        owner.to_lowercase()
+    )
+    .fetch_optional(&mut tx)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?
+    .ok_or(ShelfError::NotFound)?
+    .kind;
+
+    if kind != ShelfKind::Custom.to_string() {
+        query!(
+            r#"DELETE FROM shelf_items i USING shelves s
+               WHERE i.shelf=s.id AND lower(s.owner)=$1 AND s.kind<>$2 AND s.id<>$3
+                 AND lower(i.novel)=$4"#,
+            owner.to_lowercase(),
+            ShelfKind::Custom.to_string(),
+            id,
+            novel.apub_id.to_lowercase()
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| ShelfError::InternalServerError(e.to_string()))?;
+    }
+
+    query!(
+        "INSERT INTO shelf_items (shelf, novel) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        id,
+        novel.apub_id
+    )
+    .execute(&mut tx)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ShelfError::InternalServerError(e.to_string()))
+}
+
+/// Takes `novel` off a shelf of `owner`.
+pub async fn remove_novel(
+    pool: &PgPool,
+    owner: &str,
+    id: Uuid,
+    novel: &str,
+) -> Result<(), ShelfError> {
+    let res = query!(
+        r#"DELETE FROM shelf_items i USING shelves s
+           WHERE i.shelf=s.id AND s.id=$1 AND lower(s.owner)=$2 AND lower(i.novel)=$3"#,
+        id,
+        owner.to_lowercase(),
+        novel.trim().to_lowercase()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?;
+    if res.rows_affected() == 0 {
+        return Err(ShelfError::NotFound);
+    }
+    Ok(())
+}
+
+/// Lists every shelf of `owner` along with whether `novel` is on it.
+pub async fn shelves_with_novel(
+    pool: &PgPool,
+    owner: &str,
+    novel: &str,
+) -> Result<Vec<(Shelf, bool)>, ShelfError> {
+    let shelves = list_shelves(pool, owner, true).await?;
+    let on = query!(
+        r#"SELECT i.shelf FROM shelf_items i
+           JOIN shelves s ON s.id=i.shelf
+           WHERE lower(s.owner)=$1 AND lower(i.novel)=$2"#,
+        owner.to_lowercase(),
+        novel.trim().to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ShelfError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| row.shelf)
+    .collect::<Vec<_>>();
+
+    Ok(shelves
+        .into_iter()
+        .map(|s| {
+            let contains = on.contains(&s.id);
+            (s, contains)
+        })
+        .collect())
+}
@@ -0,0 +1,78 @@
+use crate::api::novel::NovelListing;
+use serde::Serialize;
+use sqlx::{query, PgPool};
+use thiserror::Error;
+
+pub const PAGE_SIZE: i64 = 20;
+
+#[derive(Debug, Error)]
+pub enum TagError {
+    #[error("Tag: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Tag: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagPage {
+    pub tag: String,
+    pub total: i64,
+    pub novels: Vec<NovelListing>,
+}
+
+/// Lists local and known remote novels tagged with `tag`, newest first.
+/// `page` starts at 0.
+pub async fn novels_with_tag(pool: &PgPool, tag: &str, page: i64) -> Result<TagPage, TagError> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    if tag.is_empty() {
+        return Err(TagError::BadRequest("Empty tag".to_string()));
+    }
+
+    let total = query!(
+        r#"SELECT count(*) AS "count!" FROM novels n
+           WHERE lower_tags(n.tags) @> ARRAY[$1]
+             AND NOT n.hidden
+             AND NOT authors_hidden(n.apub_id)"#,
+        tag
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| TagError::InternalServerError(e.to_string()))?
+    .count;
+
+    let novels = query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published, n.private_key IS NOT NULL AS "local!"
+           FROM novels n
+           WHERE lower_tags(n.tags) @> ARRAY[$1]
+             AND NOT n.hidden
+             AND NOT authors_hidden(n.apub_id)
+           ORDER BY n.published DESC
+           LIMIT $2 OFFSET $3"#,
+        tag,
+        PAGE_SIZE,
+        page.max(0).saturating_mul(PAGE_SIZE)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TagError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        NovelListing::new(
+            row.apub_id,
+            row.preferred_username,
+            row.title,
+            row.summary,
+            row.genre,
+            row.tags,
+            row.language,
+            row.sensitive,
+            row.published,
+            row.local,
+        )
+        .map_err(|e| TagError::InternalServerError(e.to_string()))
+    })
+    .collect::<Result<_, _>>()?;
+
+    Ok(TagPage { tag, total, novels })
+}
@@ -0,0 +1,693 @@
+use crate::{
+    account::{current_user, UserValidateError},
+    domain_policy,
+    enums::{Genres, Roles, ShelfKind},
+    objects::{
+        novel::{Author, DbNovel},
+        person::User,
+    },
+    restriction, throttle, user_filter,
+    util::{AppState, RedisHandle},
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data, fetch::object_id::ObjectId, http_signatures::generate_actor_keypair,
+    traits::Object,
+};
+use actix_session::Session;
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{query, Postgres, Transaction};
+use std::{collections::HashMap, str::FromStr};
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum TakeoutError {
+    #[error("Takeout: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Takeout: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Takeout: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+/// Everything a user owns on this instance, in a form that can be restored on
+/// another one.
+#[derive(Serialize, Deserialize)]
+pub struct Takeout {
+    pub version: u32,
+    pub exported: DateTime<Utc>,
+    pub profile: ProfileExport,
+    pub keypair: KeypairExport,
+    pub novels: Vec<NovelExport>,
+    pub following: Vec<String>,
+    pub followers: Vec<String>,
+    #[serde(default)]
+    pub shelves: Vec<ShelfExport>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProfileExport {
+    pub apub_id: String,
+    pub preferred_username: String,
+    pub name: String,
+    pub summary: String,
+    pub email: String,
+    pub also_known_as: Vec<String>,
+    pub published: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KeypairExport {
+    pub public_key: String,
+    pub private_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NovelExport {
+    pub apub_id: String,
+    pub title: String,
+    pub summary: String,
+    pub authors: Vec<Author>,
+    pub genre: Genres,
+    pub tags: Vec<String>,
+    pub language: String,
+    pub sensitive: bool,
+    pub published: DateTime<Utc>,
+    pub chapters: Vec<ChapterExport>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ChapterExport {
+    pub sequence: i32,
+    pub title: String,
+    pub summary: String,
+    pub sensitive: bool,
+    pub content: String,
+    pub published: DateTime<Utc>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ShelfExport {
+    pub kind: ShelfKind,
+    pub name: String,
+    pub public: bool,
+    pub published: DateTime<Utc>,
+    /// Novel URLs, oldest addition first.
+    pub novels: Vec<String>,
+}
+
+/// A co-author of an imported novel who hasn't been restored yet.
+#[derive(Serialize, Deserialize)]
+pub struct PendingAuthor {
+    pub novel: String,
+    pub apub_id: String,
+    pub role: Roles,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ImportResult {
+    pub novels: Vec<String>,
+    pub pending_authors: Vec<PendingAuthor>,
+    /// Remote novels on reading lists that weren't added because they
+    /// couldn't be fetched.
+    pub skipped_novels: Vec<String>,
+}
+
+const TAKEOUT_VERSION: u32 = 2;
+
+/// Remote novels on reading lists that one import may fetch. The rest are
+/// only added if this instance already knows them.
+const MAX_REMOTE_NOVELS: usize = 50;
+
+async fn session_id(data: &Data<DbHandle>, session: &Session) -> Result<String, TakeoutError> {
+    current_user(data.app_data().as_ref(), session)
+        .await
+        .map_err(|e| match e {
+            UserValidateError::Unauthorized(e) => TakeoutError::Unauthorized(e),
+            e => TakeoutError::InternalServerError(e.to_string()),
+        })
+}
+
+pub async fn export(data: &Data<DbHandle>, session: Session) -> Result<Takeout, TakeoutError> {
+    let apub_id = session_id(data, &session).await?;
+    let pool = data.app_data().as_ref();
+
+    let user = query!(
+        r#"SELECT apub_id, preferred_username, name, summary, email AS "email!", also_known_as,
+             published, public_key, private_key
+           FROM users WHERE apub_id=$1"#,
+        apub_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    .ok_or_else(|| TakeoutError::Unauthorized("Expired session".to_string()))?;
+
+    let mut authors: HashMap<String, Vec<Author>> = HashMap::new();
+    query!(
+        r#"SELECT id, author, role FROM author_roles
+           WHERE id IN (SELECT id FROM author_roles WHERE lower(author)=$1)"#,
+        apub_id.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .for_each(|row| {
+        authors.entry(row.id).or_default().push(Author {
+            apub_id: row.author,
+            role: Roles::from_str(&row.role).unwrap_or(Roles::None),
+        })
+    });
+
+    let mut novels = vec![];
+    for row in query!(
+        r#"SELECT apub_id, title, summary, genre, tags, language, sensitive, published
+           FROM novels
+           WHERE apub_id IN (SELECT id FROM author_roles WHERE lower(author)=$1)
+           ORDER BY published"#,
+        apub_id.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    {
+        let chapters = query!(
+            r#"SELECT sequence, title, summary, sensitive, content, published, updated
+               FROM chapters WHERE lower(audience)=$1
+               ORDER BY sequence"#,
+            row.apub_id.to_lowercase()
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+        .into_iter()
+        .map(|c| ChapterExport {
+            sequence: c.sequence,
+            title: c.title,
+            summary: c.summary,
+            sensitive: c.sensitive,
+            content: c.content,
+            published: c.published,
+            updated: c.updated,
+        })
+        .collect();
+
+        novels.push(NovelExport {
+            authors: authors.remove(&row.apub_id).unwrap_or_default(),
+            apub_id: row.apub_id,
+            title: row.title,
+            summary: row.summary,
+            genre: Genres::from_str(&row.genre).unwrap_or(Genres::Other),
+            tags: row.tags,
+            language: row.language,
+            sensitive: row.sensitive,
+            published: row.published,
+            chapters,
+        });
+    }
+
+    let following = query!(
+        "SELECT following FROM follows WHERE lower(follower)=$1",
+        apub_id.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| row.following)
+    .collect();
+
+    let followers = query!(
+        "SELECT follower FROM follows WHERE lower(following)=$1",
+        apub_id.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| row.follower)
+    .collect();
+
+    let mut shelves = vec![];
+    for row in query!(
+        r#"SELECT id, kind, name, public, published FROM shelves
+           WHERE lower(owner)=$1
+           ORDER BY kind='custom', published, name"#,
+        apub_id.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    {
+        let novels = query!(
+            "SELECT novel FROM shelf_items WHERE shelf=$1 ORDER BY published",
+            row.id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+        .into_iter()
+        .map(|i| i.novel)
+        .collect();
+
+        shelves.push(ShelfExport {
+            kind: ShelfKind::from_str(&row.kind)
+                .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?,
+            name: row.name,
+            public: row.public,
+            published: row.published,
+            novels,
+        });
+    }
+
+    Ok(Takeout {
+        version: TAKEOUT_VERSION,
+        exported: Utc::now(),
+        profile: ProfileExport {
+            apub_id: user.apub_id,
+            preferred_username: user.preferred_username,
+            name: user.name,
+            summary: user.summary,
+            email: user.email,
+            also_known_as: user.also_known_as,
+            published: user.published,
+        },
+        keypair: KeypairExport {
+            public_key: user.public_key,
+            private_key: user.private_key,
+        },
+        novels,
+        following,
+        followers,
+        shelves,
+    })
+}
+
+/// Recreates the novels and chapters of a takeout archive under the signed in
+/// user. Every novel gets a new identity on this instance. Only the importing
+/// user is attached as an author, the other co-authors are recorded in
+/// `pending_authors` until the importer restores or dismisses them. Reading
+/// lists are restored with novels that were part of the archive pointing at
+/// their new copies. Remote novels on them are fetched only up to
+/// [`MAX_REMOTE_NOVELS`], each counting as a remote lookup of the user.
+pub async fn import(
+    state: web::Data<AppState>,
+    data: &Data<DbHandle>,
+    redis: &RedisHandle,
+    session: Session,
+    takeout: Takeout,
+) -> Result<ImportResult, TakeoutError> {
+    let apub_id = session_id(data, &session).await?;
+
+    if takeout.version > TAKEOUT_VERSION {
+        return Err(TakeoutError::BadRequest(format!(
+            "Unsupported takeout version {}",
+            takeout.version
+        )));
+    }
+
+    let scheme = &state.scheme;
+    let old_id = takeout.profile.apub_id.to_lowercase();
+    let mut tx = data
+        .app_data()
+        .begin()
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+    let mut imported = vec![];
+    let mut pending = vec![];
+    let mut moved = HashMap::new();
+
+    for novel in takeout.novels {
+        let uuid = Uuid::new_v4();
+        let keypair = generate_actor_keypair()
+            .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+        let url = format!(
+            "{}://{}/novel/{}",
+            scheme,
+            data.domain(),
+            uuid.to_string().to_lowercase()
+        );
+
+        query!(
+            r#"INSERT INTO novels
+               (apub_id, preferred_username, title, summary, genre, tags, language,
+                 sensitive, inbox, outbox, public_key, private_key, published)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)"#,
+            url,
+            uuid,
+            novel.title,
+            novel.summary,
+            novel.genre.to_string(),
+            novel.tags.as_slice(),
+            novel.language,
+            novel.sensitive,
+            format!("{}/inbox", url),
+            format!("{}/outbox", url),
+            keypair.public_key,
+            keypair.private_key,
+            novel.published
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+
+        let (own, others): (Vec<Author>, Vec<Author>) = novel
+            .authors
+            .into_iter()
+            .partition(|a| a.apub_id.to_lowercase() == old_id);
+        let role = own.into_iter().next().map_or(Roles::None, |a| a.role);
+        query!(
+            "INSERT INTO author_roles VALUES ($1, $2, $3)",
+            url,
+            apub_id,
+            role.to_string()
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+
+        for author in others {
+            query!(
+                r#"INSERT INTO pending_authors (novel, author, role) VALUES ($1, $2, $3)
+                   ON CONFLICT DO NOTHING"#,
+                url,
+                author.apub_id,
+                author.role.to_string()
+            )
+            .execute(&mut tx)
+            .await
+            .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+            pending.push(PendingAuthor {
+                novel: url.clone(),
+                apub_id: author.apub_id,
+                role: author.role,
+            });
+        }
+
+        for chapter in novel.chapters {
+            query!(
+                r#"INSERT INTO chapters
+                   (apub_id, audience, title, summary, sensitive, content, sequence,
+                     published, updated)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+                format!("{}/{}", url, chapter.sequence),
+                url,
+                chapter.title,
+                chapter.summary,
+                chapter.sensitive,
+                chapter.content,
+                chapter.sequence,
+                chapter.published,
+                chapter.updated
+            )
+            .execute(&mut tx)
+            .await
+            .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+        }
+
+        moved.insert(novel.apub_id.to_lowercase(), url);
+        imported.push(uuid.to_string().to_lowercase());
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+
+    let pool = data.app_data().as_ref();
+    let mut fetched = 0;
+    let mut skipped = vec![];
+    for shelf in takeout.shelves {
+        let name = shelf.kind.default_name().map_or(shelf.name, str::to_string);
+        // Built-in shelves may already exist, custom ones with the same name
+        // are merged.
+        query!(
+            r#"INSERT INTO shelves (owner, kind, name, public, published)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT DO NOTHING"#,
+            apub_id,
+            shelf.kind.to_string(),
+            name,
+            shelf.public,
+            shelf.published
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+        let id = match query!(
+            r#"SELECT id FROM shelves
+               WHERE lower(owner)=$1 AND kind=$2 AND ($2<>$3 OR lower(name)=lower($4))"#,
+            apub_id.to_lowercase(),
+            shelf.kind.to_string(),
+            ShelfKind::Custom.to_string(),
+            name
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+        {
+            Some(row) => row.id,
+            None => continue,
+        };
+
+        for novel in shelf.novels {
+            let novel = match moved.get(&novel.to_lowercase()) {
+                Some(url) => url.clone(),
+                None => match Url::parse(&novel) {
+                    Ok(url) => {
+                        match remote_novel(data, redis, &apub_id, url, &mut fetched).await? {
+                            Some(n) => n,
+                            None => {
+                                skipped.push(novel);
+                                continue;
+                            }
+                        }
+                    }
+                    Err(_) => continue,
+                },
+            };
+            query!(
+                "INSERT INTO shelf_items (shelf, novel) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+                id,
+                novel
+            )
+            .execute(pool)
+            .await
+            .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+        }
+    }
+
+    Ok(ImportResult {
+        novels: imported,
+        pending_authors: pending,
+        skipped_novels: skipped,
+    })
+}
+
+/// The ID of the novel at `url` for a reading list being imported by
+/// `owner`. Known novels are used as they are. Unknown ones are fetched while
+/// `fetched` is below [`MAX_REMOTE_NOVELS`], their domain isn't blocked and
+/// `owner` has remote lookups left. Hidden and unavailable novels are `None`.
+async fn remote_novel(
+    data: &Data<DbHandle>,
+    redis: &RedisHandle,
+    owner: &str,
+    url: Url,
+    fetched: &mut usize,
+) -> Result<Option<String>, TakeoutError> {
+    let known = DbNovel::read_from_id(url.clone(), data)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+    if let Some(novel) = known {
+        return Ok((!novel.hidden).then_some(novel.apub_id));
+    }
+
+    if *fetched >= MAX_REMOTE_NOVELS
+        || domain_policy::is_url_blocked(data.app_data(), &url, data.domain())
+            .await
+            .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+        || !throttle::resolve(redis, owner)
+            .await
+            .map_err(TakeoutError::InternalServerError)?
+    {
+        return Ok(None);
+    }
+    *fetched += 1;
+
+    match ObjectId::<DbNovel>::from(url).dereference(data).await {
+        Ok(n) if !n.hidden => Ok(Some(n.apub_id)),
+        _ => Ok(None),
+    }
+}
+
+/// The co-authors of the signed in user's imported novels that haven't been
+/// restored or dismissed yet.
+pub async fn pending_authors(
+    data: &Data<DbHandle>,
+    session: Session,
+) -> Result<Vec<PendingAuthor>, TakeoutError> {
+    let apub_id = session_id(data, &session).await?;
+
+    query!(
+        r#"SELECT p.novel, p.author, p.role FROM pending_authors p
+           WHERE EXISTS(
+             SELECT 1 FROM author_roles a
+             WHERE lower(a.id)=lower(p.novel) AND lower(a.author)=$1)
+           ORDER BY p.published, p.novel, p.author"#,
+        apub_id.to_lowercase()
+    )
+    .fetch_all(data.app_data().as_ref())
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        Ok(PendingAuthor {
+            novel: row.novel,
+            apub_id: row.author,
+            role: Roles::from_str(&row.role)
+                .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?,
+        })
+    })
+    .collect()
+}
+
+/// Takes `author` out of the pending co-authors of `novel`, which `owner`
+/// must be an author of, and returns its role.
+async fn take_pending(
+    tx: &mut Transaction<'_, Postgres>,
+    owner: &str,
+    novel: &str,
+    author: &str,
+) -> Result<Roles, TakeoutError> {
+    let role = query!(
+        r#"DELETE FROM pending_authors p
+           WHERE lower(p.novel)=$1 AND lower(p.author)=$2
+             AND EXISTS(
+               SELECT 1 FROM author_roles a
+               WHERE lower(a.id)=$1 AND lower(a.author)=$3)
+           RETURNING p.role"#,
+        novel.to_lowercase(),
+        author.to_lowercase(),
+        owner.to_lowercase()
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    .ok_or_else(|| TakeoutError::BadRequest("No such pending co-author".to_string()))?
+    .role;
+
+    Roles::from_str(&role).map_err(|e| TakeoutError::InternalServerError(e.to_string()))
+}
+
+/// Whether either of `owner`, a local user, and `author` blocked the other.
+/// Blocks by remote authors aren't known here.
+async fn blocked_either(data: &Data<DbHandle>, owner: &str, author: &str) -> anyhow::Result<bool> {
+    let pool = data.app_data();
+    Ok(
+        user_filter::is_blocked(pool, owner, &author.parse()?).await?
+            || user_filter::is_blocked(pool, author, &owner.parse()?).await?,
+    )
+}
+
+/// Attaches the pending co-author `author` to the imported `novel` again with
+/// the role they had in the archive. The author is fetched if they are on
+/// another instance. Suspended authors can't be restored, and neither can
+/// ones who blocked the signed in user or that they blocked.
+pub async fn restore_author(
+    data: &Data<DbHandle>,
+    session: Session,
+    novel: &str,
+    author: &str,
+) -> Result<(), TakeoutError> {
+    let apub_id = session_id(data, &session).await?;
+    // Check before fetching the author so callers can't make us dereference
+    // arbitrary urls. The row is claimed again below once the author is known.
+    let pending = query!(
+        r#"SELECT EXISTS(
+             SELECT 1 FROM pending_authors p
+             JOIN author_roles a ON lower(a.id)=lower(p.novel)
+             WHERE lower(p.novel)=$1 AND lower(p.author)=$2 AND lower(a.author)=$3
+           ) AS "exists!""#,
+        novel.to_lowercase(),
+        author.to_lowercase(),
+        apub_id.to_lowercase()
+    )
+    .fetch_one(data.app_data())
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    .exists;
+    if !pending {
+        return Err(TakeoutError::BadRequest(
+            "No such pending co-author".to_string(),
+        ));
+    }
+
+    let url: Url = author
+        .parse()
+        .map_err(|_| TakeoutError::BadRequest("Invalid author".to_string()))?;
+    let user = ObjectId::<User>::from(url)
+        .dereference(data)
+        .await
+        .map_err(|e| TakeoutError::BadRequest(format!("Author not found: {e}")))?;
+    if restriction::is_suspended(data.app_data(), &user.apub_id)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    {
+        return Err(TakeoutError::BadRequest("Author not found".to_string()));
+    }
+    if blocked_either(data, &apub_id, &user.apub_id)
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?
+    {
+        return Err(TakeoutError::BadRequest(
+            "Blocked users can't be co-authors".to_string(),
+        ));
+    }
+
+    let mut tx = data
+        .app_data()
+        .begin()
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+    let role = take_pending(&mut tx, &apub_id, novel, author).await?;
+    query!(
+        r#"INSERT INTO author_roles
+           SELECT n.apub_id, $2, $3 FROM novels n WHERE lower(n.apub_id)=$1
+           ON CONFLICT DO NOTHING"#,
+        novel.to_lowercase(),
+        user.apub_id,
+        role.to_string()
+    )
+    .execute(&mut tx)
+    .await
+    .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))
+}
+
+/// Drops `author` from the pending co-authors of the imported `novel` without
+/// restoring them.
+pub async fn dismiss_author(
+    data: &Data<DbHandle>,
+    session: Session,
+    novel: &str,
+    author: &str,
+) -> Result<(), TakeoutError> {
+    let apub_id = session_id(data, &session).await?;
+    let mut tx = data
+        .app_data()
+        .begin()
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))?;
+    take_pending(&mut tx, &apub_id, novel, author).await?;
+    tx.commit()
+        .await
+        .map_err(|e| TakeoutError::InternalServerError(e.to_string()))
+}
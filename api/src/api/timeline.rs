@@ -0,0 +1,294 @@
+use crate::{api::novel::NovelListing, enums::TimelineEntryKind, util::local_handle};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, PgPool};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const PAGE_SIZE: i64 = 20;
+
+#[derive(Debug, Error)]
+pub enum TimelineError {
+    #[error("Timeline: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TimelineQuery {
+    /// Only entries published before this, to get the page after one ending
+    /// there.
+    pub before: Option<DateTime<Utc>>,
+    /// The ID of the last entry of the page ending at `before`. Entries
+    /// published at that exact time are then only skipped up to that one, so
+    /// that entries sharing a timestamp across pages aren't lost.
+    pub before_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PublicTimelineQuery {
+    /// Like in [`TimelineQuery`].
+    pub before: Option<DateTime<Utc>>,
+    /// Like in [`TimelineQuery`].
+    pub before_id: Option<String>,
+    /// Only this instance's novels and chapters.
+    #[serde(default)]
+    pub local: bool,
+    /// Include sensitive novels and chapters.
+    #[serde(default)]
+    pub sensitive: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineChapter {
+    pub apub_id: String,
+    pub sequence: i32,
+    pub title: String,
+    pub summary: String,
+    pub sensitive: bool,
+}
+
+/// Who boosted a novel or chapter.
+#[derive(Debug, Serialize)]
+pub struct Booster {
+    pub apub_id: String,
+    pub name: String,
+    /// Where the user is shown on this instance.
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineEntry {
+    pub kind: TimelineEntryKind,
+    pub published: DateTime<Utc>,
+    pub novel: NovelListing,
+    /// Set for new chapters and boosted chapters.
+    pub chapter: Option<TimelineChapter>,
+    /// Set for boosts.
+    pub booster: Option<Booster>,
+}
+
+/// Where the next page of a timeline starts, with the fields named like the
+/// query parameters to pass for it.
+#[derive(Debug, Serialize)]
+pub struct TimelineCursor {
+    pub before: DateTime<Utc>,
+    pub before_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelinePage {
+    pub entries: Vec<TimelineEntry>,
+    /// Where the next page starts. Unset on the last one.
+    pub next: Option<TimelineCursor>,
+}
+
+/// The home timeline of the local user `owner`, newest first: new chapters of
+/// the novels they follow, new novels by the people they follow and whatever
+/// those people boosted.
+///
+/// Hidden novels, novels whose authors are all suspended, suspended boosters
+/// and actors that `owner` blocked or muted, directly or through their
+/// domain, are left out. Silenced actors still show up for their followers.
+pub async fn home(
+    pool: &PgPool,
+    owner: &str,
+    query: TimelineQuery,
+) -> Result<TimelinePage, TimelineError> {
+    let rows = query_as!(
+        TimelineRow,
+        r#"WITH entries AS (
+               SELECT 'chapter' AS kind, c.apub_id AS id, c.published,
+                 c.audience AS novel, c.apub_id AS chapter, NULL::text AS booster
+               FROM chapters c
+               WHERE lower(c.audience) IN (
+                 SELECT lower(following) FROM follows WHERE lower(follower)=$1)
+             UNION ALL
+               SELECT 'novel', n.apub_id, n.published, n.apub_id, NULL, NULL
+               FROM novels n
+               WHERE EXISTS(
+                 SELECT 1 FROM author_roles a
+                 JOIN follows f ON lower(f.following)=lower(a.author)
+                 WHERE lower(a.id)=lower(n.apub_id) AND lower(f.follower)=$1)
+             UNION ALL
+               SELECT 'boost', b.id, b.published, coalesce(c.audience, b.object),
+                 c.apub_id, b.actor
+               FROM announces b
+               LEFT JOIN chapters c ON lower(c.apub_id)=lower(b.object)
+               WHERE lower(b.actor) IN (
+                 SELECT lower(following) FROM follows WHERE lower(follower)=$1)
+           )
+           SELECT e.kind AS "kind!", e.id AS "id!", e.published AS "published!",
+             n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published AS novel_published,
+             n.private_key IS NOT NULL AS "local!",
+             c.apub_id AS "chapter_id?", c.sequence AS "sequence?",
+             c.title AS "chapter_title?", c.summary AS "chapter_summary?",
+             c.sensitive AS "chapter_sensitive?",
+             u.apub_id AS "booster_id?", u.preferred_username AS "booster_username?",
+             u.name AS "booster_name?", u.private_key IS NOT NULL AS "booster_local!"
+           FROM entries e
+           JOIN novels n ON lower(n.apub_id)=lower(e.novel)
+           LEFT JOIN chapters c ON lower(c.apub_id)=lower(e.chapter)
+           LEFT JOIN users u ON lower(u.apub_id)=lower(e.booster)
+           WHERE ($2::timestamptz IS NULL OR (e.published, e.id) < ($2, $3::text))
+             AND NOT n.hidden
+             AND NOT authors_suspended(n.apub_id)
+             AND NOT authors_muted_by($1, n.apub_id)
+             AND (e.booster IS NULL
+                  OR (u.apub_id IS NOT NULL
+                      AND NOT actor_suspended(e.booster)
+                      AND NOT muted_by($1, e.booster)))
+           ORDER BY e.published DESC, e.id DESC
+           LIMIT $4"#,
+        owner.to_lowercase(),
+        query.before,
+        query.before_id,
+        PAGE_SIZE
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TimelineError::InternalServerError(e.to_string()))?;
+
+    page(rows)
+}
+
+/// New chapters and novels, newest first, either of this instance when
+/// `local` is set or of every instance it knows about. `viewer` is the signed
+/// in user, if any.
+///
+/// Sensitive novels and chapters are only included when asked for. Hidden
+/// novels, suspended and silenced actors, actors on silenced domains and, for
+/// `viewer`, actors they blocked or muted are left out.
+pub async fn public(
+    pool: &PgPool,
+    viewer: Option<&str>,
+    query: PublicTimelineQuery,
+) -> Result<TimelinePage, TimelineError> {
+    let rows = query_as!(
+        TimelineRow,
+        r#"WITH entries AS (
+               SELECT 'chapter' AS kind, c.apub_id AS id, c.published,
+                 c.audience AS novel, c.apub_id AS chapter
+               FROM chapters c
+             UNION ALL
+               SELECT 'novel', n.apub_id, n.published, n.apub_id, NULL
+               FROM novels n
+           )
+           SELECT e.kind AS "kind!", e.id AS "id!", e.published AS "published!",
+             n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published AS novel_published,
+             n.private_key IS NOT NULL AS "local!",
+             c.apub_id AS "chapter_id?", c.sequence AS "sequence?",
+             c.title AS "chapter_title?", c.summary AS "chapter_summary?",
+             c.sensitive AS "chapter_sensitive?",
+             NULL::text AS "booster_id?", NULL::text AS "booster_username?",
+             NULL::text AS "booster_name?", false AS "booster_local!"
+           FROM entries e
+           JOIN novels n ON lower(n.apub_id)=lower(e.novel)
+           LEFT JOIN chapters c ON lower(c.apub_id)=lower(e.chapter)
+           WHERE ($1::timestamptz IS NULL OR (e.published, e.id) < ($1, $2::text))
+             AND (NOT $3::boolean OR n.private_key IS NOT NULL)
+             AND ($4::boolean OR NOT (n.sensitive OR coalesce(c.sensitive, false)))
+             AND NOT n.hidden
+             AND NOT authors_hidden(n.apub_id)
+             AND ($5::text IS NULL OR NOT authors_muted_by($5, n.apub_id))
+           ORDER BY e.published DESC, e.id DESC
+           LIMIT $6"#,
+        query.before,
+        query.before_id,
+        query.local,
+        query.sensitive,
+        viewer.map(str::to_lowercase),
+        PAGE_SIZE
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TimelineError::InternalServerError(e.to_string()))?;
+
+    page(rows)
+}
+
+/// A row of the timeline queries. Booster columns are only set for boosts.
+struct TimelineRow {
+    kind: String,
+    id: String,
+    published: DateTime<Utc>,
+    apub_id: String,
+    preferred_username: Uuid,
+    title: String,
+    summary: String,
+    genre: String,
+    tags: Vec<String>,
+    language: String,
+    sensitive: bool,
+    novel_published: DateTime<Utc>,
+    local: bool,
+    chapter_id: Option<String>,
+    sequence: Option<i32>,
+    chapter_title: Option<String>,
+    chapter_summary: Option<String>,
+    chapter_sensitive: Option<bool>,
+    booster_id: Option<String>,
+    booster_username: Option<String>,
+    booster_name: Option<String>,
+    booster_local: bool,
+}
+
+fn entry(row: TimelineRow) -> Result<TimelineEntry, TimelineError> {
+    let chapter = match (row.chapter_id, row.sequence, row.chapter_title) {
+        (Some(apub_id), Some(sequence), Some(title)) => Some(TimelineChapter {
+            apub_id,
+            sequence,
+            title,
+            summary: row.chapter_summary.unwrap_or_default(),
+            sensitive: row.chapter_sensitive.unwrap_or_default() || row.sensitive,
+        }),
+        _ => None,
+    };
+    let booster = match (row.booster_id, row.booster_username, row.booster_name) {
+        (Some(apub_id), Some(username), Some(name)) => Some(Booster {
+            path: format!(
+                "/user/{}",
+                local_handle(&username, &apub_id, row.booster_local)
+                    .map_err(|e| TimelineError::InternalServerError(e.to_string()))?
+            ),
+            apub_id,
+            name,
+        }),
+        _ => None,
+    };
+    Ok(TimelineEntry {
+        kind: TimelineEntryKind::from_str(&row.kind)
+            .map_err(|e| TimelineError::InternalServerError(e.to_string()))?,
+        published: row.published,
+        novel: NovelListing::new(
+            row.apub_id,
+            row.preferred_username,
+            row.title,
+            row.summary,
+            row.genre,
+            row.tags,
+            row.language,
+            row.sensitive,
+            row.novel_published,
+            row.local,
+        )
+        .map_err(|e| TimelineError::InternalServerError(e.to_string()))?,
+        chapter,
+        booster,
+    })
+}
+
+fn page(rows: Vec<TimelineRow>) -> Result<TimelinePage, TimelineError> {
+    let next = (rows.len() as i64 == PAGE_SIZE)
+        .then(|| {
+            rows.last().map(|r| TimelineCursor {
+                before: r.published,
+                before_id: r.id.clone(),
+            })
+        })
+        .flatten();
+    let entries = rows.into_iter().map(entry).collect::<Result<Vec<_>, _>>()?;
+    Ok(TimelinePage { entries, next })
+}
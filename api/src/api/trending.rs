@@ -0,0 +1,219 @@
+use crate::{api::novel::NovelListing, enums::TrendingKind, util::local_handle};
+use serde::Serialize;
+use sqlx::{query, Executor, PgPool, Postgres};
+use thiserror::Error;
+
+/// How many novels are shown as trending.
+pub const NOVEL_LIMIT: i64 = 5;
+/// How many tags are shown as trending.
+pub const TAG_LIMIT: i64 = 10;
+/// How many novels of one author may trend at the same time.
+pub const AUTHOR_CAP: i64 = 2;
+/// How many novels a tag needs among the trending ones to trend itself.
+pub const TAG_MIN_NOVELS: i64 = 2;
+
+#[derive(Debug, Error)]
+pub enum TrendingError {
+    #[error("Trending: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrendingTag {
+    pub tag: String,
+    /// How many trending novels have the tag.
+    pub novels: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Trending {
+    pub novels: Vec<NovelListing>,
+    pub tags: Vec<TrendingTag>,
+}
+
+/// A novel or tag that is trending, whether or not it was reviewed yet.
+#[derive(Debug, Serialize)]
+pub struct TrendingCandidate {
+    pub kind: TrendingKind,
+    /// The novel's apub_id or the tag.
+    pub target: String,
+    /// The novel's title or the tag.
+    pub name: String,
+    /// Where the novel or tag is shown on this instance.
+    pub path: String,
+    pub score: f64,
+    /// Unset until a moderator reviewed it.
+    pub approved: Option<bool>,
+}
+
+/// The novels and tags trending over the last week that moderators approved,
+/// best first. See the `trending_novels` SQL function for how they are
+/// scored.
+///
+/// Tags are scored by the approved trending novels they are on. Actors the
+/// signed in `viewer` blocked or muted are left out.
+pub async fn trending(pool: &PgPool, viewer: Option<&str>) -> Result<Trending, TrendingError> {
+    let viewer = viewer.map(str::to_lowercase);
+
+    let novels = query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, n.summary, n.genre, n.tags,
+             n.language, n.sensitive, n.published, n.private_key IS NOT NULL AS "local!"
+           FROM trending_novels($1) t
+           JOIN novels n ON lower(n.apub_id)=t.novel
+           JOIN trending_reviews r ON r.kind='novel' AND r.target=t.novel AND r.approved
+           WHERE $2::text IS NULL OR NOT authors_muted_by($2, n.apub_id)
+           ORDER BY t.score DESC
+           LIMIT $3"#,
+        AUTHOR_CAP,
+        viewer,
+        NOVEL_LIMIT
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TrendingError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        NovelListing::new(
+            row.apub_id,
+            row.preferred_username,
+            row.title,
+            row.summary,
+            row.genre,
+            row.tags,
+            row.language,
+            row.sensitive,
+            row.published,
+            row.local,
+        )
+        .map_err(|e| TrendingError::InternalServerError(e.to_string()))
+    })
+    .collect::<Result<_, _>>()?;
+
+    let tags = query!(
+        r#"SELECT x.tag AS "tag!", count(*) AS "novels!"
+           FROM trending_novels($1) t
+           JOIN novels n ON lower(n.apub_id)=t.novel
+           JOIN trending_reviews nr ON nr.kind='novel' AND nr.target=t.novel AND nr.approved
+           CROSS JOIN LATERAL unnest(lower_tags(n.tags)) AS x(tag)
+           JOIN trending_reviews r ON r.kind='tag' AND r.target=x.tag AND r.approved
+           WHERE NOT n.hidden AND ($2::text IS NULL OR NOT authors_muted_by($2, n.apub_id))
+           GROUP BY x.tag
+           HAVING count(*) >= $3
+           ORDER BY sum(t.score) DESC, x.tag
+           LIMIT $4"#,
+        AUTHOR_CAP,
+        viewer,
+        TAG_MIN_NOVELS,
+        TAG_LIMIT
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| TrendingError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| TrendingTag {
+        tag: row.tag,
+        novels: row.novels,
+    })
+    .collect();
+
+    Ok(Trending { novels, tags })
+}
+
+/// Lists trending novels and tags with their review status, best first, so
+/// that moderators can approve them before they show up. Up to `limit` of
+/// each are returned. Tags only count novels that were approved.
+pub(crate) async fn candidates(
+    pool: &PgPool,
+    limit: i64,
+) -> anyhow::Result<Vec<TrendingCandidate>> {
+    let mut candidates = query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, t.score AS "score!",
+             n.private_key IS NOT NULL AS "local!", r.approved AS "approved?"
+           FROM trending_novels($1) t
+           JOIN novels n ON lower(n.apub_id)=t.novel
+           LEFT JOIN trending_reviews r ON r.kind='novel' AND r.target=t.novel
+           ORDER BY t.score DESC
+           LIMIT $2"#,
+        AUTHOR_CAP,
+        limit
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(TrendingCandidate {
+            kind: TrendingKind::Novel,
+            path: format!(
+                "/novel/{}",
+                local_handle(
+                    &row.preferred_username.to_string().to_lowercase(),
+                    &row.apub_id,
+                    row.local
+                )?
+            ),
+            target: row.apub_id,
+            name: row.title,
+            score: row.score,
+            approved: row.approved,
+        })
+    })
+    .collect::<Result<Vec<_>, url::ParseError>>()?;
+
+    candidates.extend(
+        query!(
+            r#"SELECT x.tag AS "tag!", sum(t.score) AS "score!", bool_or(r.approved) AS approved
+               FROM trending_novels($1) t
+               JOIN novels n ON lower(n.apub_id)=t.novel
+               JOIN trending_reviews nr ON nr.kind='novel' AND nr.target=t.novel AND nr.approved
+               CROSS JOIN LATERAL unnest(lower_tags(n.tags)) AS x(tag)
+               LEFT JOIN trending_reviews r ON r.kind='tag' AND r.target=x.tag
+               WHERE NOT n.hidden
+               GROUP BY x.tag
+               HAVING count(*) >= $2
+               ORDER BY 2 DESC, 1
+               LIMIT $3"#,
+            AUTHOR_CAP,
+            TAG_MIN_NOVELS,
+            limit
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| TrendingCandidate {
+            kind: TrendingKind::Tag,
+            name: format!("#{}", row.tag),
+            path: format!("/tags/{}", row.tag),
+            target: row.tag,
+            score: row.score,
+            approved: row.approved,
+        }),
+    );
+
+    Ok(candidates)
+}
+
+/// Approves a novel or tag for the trending lists, or keeps it out of them.
+pub(crate) async fn set_review<'e, E>(
+    executor: E,
+    reviewer: &str,
+    kind: &TrendingKind,
+    target: &str,
+    approved: bool,
+) -> sqlx::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    query!(
+        r#"INSERT INTO trending_reviews (kind, target, approved, reviewer)
+           VALUES ($1, $2, $3, $4)
+           ON CONFLICT (kind, target)
+           DO UPDATE SET approved=$3, reviewer=$4, published=now()"#,
+        kind.to_string(),
+        target.trim().trim_start_matches('#').to_lowercase(),
+        approved,
+        reviewer
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use crate::{
+    api::shelf::{self, Shelf},
+    domain_policy::resolve_actor,
+    objects::person::User,
+    restriction,
+    util::local_handle,
+    DbHandle,
+};
+use activitypub_federation::{config::Data, traits::Actor};
+use serde::Serialize;
+use sqlx::query;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("Profile: NotFound")]
+    NotFound,
+    #[error("Profile: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileNovel {
+    pub apub_id: String,
+    /// Path of the novel on this instance.
+    pub path: String,
+    pub title: String,
+    pub summary: String,
+    pub role: String,
+    pub sensitive: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Profile {
+    pub apub_id: String,
+    /// `name` for local users and `name@host` for remote ones.
+    pub handle: String,
+    pub name: String,
+    pub summary: String,
+    pub local: bool,
+    pub moved_to: Option<String>,
+    pub novels: Vec<ProfileNovel>,
+    /// Public reading lists. Only known for local users.
+    pub shelves: Vec<Shelf>,
+}
+
+/// Looks up a user by local username or `name@host`, fetching remote users
+/// that aren't known yet, along with the novels they write that this instance
+/// knows about.
+pub async fn get_profile(name: &str, data: &Data<DbHandle>) -> Result<Profile, ProfileError> {
+    let name = name.trim().trim_start_matches('@');
+    let user = match name.rsplit_once('@') {
+        Some((username, domain)) if domain.eq_ignore_ascii_case(data.domain()) => {
+            User::read_from_username(username, data.app_data())
+                .await
+                .map_err(|e| ProfileError::InternalServerError(e.to_string()))?
+        }
+        Some(_) => resolve_actor::<User>(name, data).await.ok(),
+        None => User::read_from_username(name, data.app_data())
+            .await
+            .map_err(|e| ProfileError::InternalServerError(e.to_string()))?,
+    }
+    .ok_or(ProfileError::NotFound)?;
+
+    if restriction::is_suspended(data.app_data(), &user.apub_id)
+        .await
+        .map_err(|e| ProfileError::InternalServerError(e.to_string()))?
+    {
+        return Err(ProfileError::NotFound);
+    }
+
+    let novels = query!(
+        r#"SELECT n.apub_id, n.preferred_username, n.title, n.summary, n.sensitive,
+             n.private_key IS NOT NULL AS "local!", a.role
+           FROM author_roles a
+           JOIN novels n ON lower(n.apub_id)=lower(a.id)
+           WHERE lower(a.author)=$1 AND NOT n.hidden
+           ORDER BY n.published DESC"#,
+        user.apub_id.to_lowercase()
+    )
+    .fetch_all(data.app_data().as_ref())
+    .await
+    .map_err(|e| ProfileError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        Ok(ProfileNovel {
+            path: format!(
+                "/novel/{}",
+                novel_handle(row.preferred_username, &row.apub_id, row.local)?
+            ),
+            apub_id: row.apub_id,
+            title: row.title,
+            summary: row.summary,
+            role: row.role,
+            sensitive: row.sensitive,
+        })
+    })
+    .collect::<Result<_, ProfileError>>()?;
+
+    let local = user.private_key_pem().is_some();
+    let shelves = if local {
+        shelf::list_shelves(data.app_data(), &user.apub_id, false)
+            .await
+            .map_err(|e| ProfileError::InternalServerError(e.to_string()))?
+    } else {
+        vec![]
+    };
+    Ok(Profile {
+        handle: local_handle(&user.preferred_username, &user.apub_id, local)
+            .map_err(|e| ProfileError::InternalServerError(e.to_string()))?,
+        apub_id: user.apub_id,
+        name: user.name,
+        summary: user.summary,
+        local,
+        moved_to: user.moved_to,
+        novels,
+        shelves,
+    })
+}
+
+fn novel_handle(uuid: Uuid, apub_id: &str, local: bool) -> Result<String, ProfileError> {
+    local_handle(&uuid.to_string().to_lowercase(), apub_id, local)
+        .map_err(|e| ProfileError::InternalServerError(e.to_string()))
+}
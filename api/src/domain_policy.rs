@@ -0,0 +1,158 @@
+use crate::{enums::DomainPolicyKind, instance, DbHandle};
+use activitypub_federation::{
+    config::Data,
+    fetch::webfinger::webfinger_resolve_actor,
+    traits::{Actor, Object},
+};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{query, Executor, PgConnection, PgPool, Postgres};
+use std::str::FromStr;
+use url::Url;
+
+#[derive(Debug, Serialize)]
+pub struct DomainPolicy {
+    pub domain: String,
+    pub policy: DomainPolicyKind,
+    pub reason: Option<String>,
+    pub published: DateTime<Utc>,
+}
+
+/// Normalizes user input like `https://Example.com/` or `@user@example.com`
+/// to `example.com`.
+pub fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim().to_lowercase();
+    let domain = domain
+        .split_once("://")
+        .map_or(domain.as_str(), |(_, rest)| rest)
+        .split('/')
+        .next()?
+        .rsplit('@')
+        .next()?
+        .trim_start_matches("*.")
+        .trim_end_matches('.');
+    (!domain.is_empty() && !domain.contains(char::is_whitespace)).then(|| domain.to_string())
+}
+
+/// The most specific policy for `domain`. A policy on `example.com` also
+/// covers its subdomains.
+pub async fn policy_for(pool: &PgPool, domain: &str) -> anyhow::Result<Option<DomainPolicyKind>> {
+    query!(
+        r#"SELECT policy FROM domain_policies
+           WHERE domain=$1 OR right($1, length(domain) + 1)='.' || domain
+           ORDER BY length(domain) DESC
+           LIMIT 1"#,
+        domain.to_lowercase()
+    )
+    .fetch_optional(pool)
+    .await?
+    .map(|row| DomainPolicyKind::from_str(&row.policy))
+    .transpose()
+    .map_err(|e| anyhow!("{e}"))
+}
+
+/// Whether federation with `domain` is blocked, either because it is
+/// suspended or because it isn't allowed in allowlist mode. `local` is the
+/// instance's own domain, which is never blocked.
+pub async fn is_blocked(pool: &PgPool, domain: &str, local: &str) -> anyhow::Result<bool> {
+    let domain = domain.to_lowercase();
+    let local_host = local.split(':').next().unwrap_or(local);
+    if domain == local || domain == local_host {
+        return Ok(false);
+    }
+
+    match policy_for(pool, &domain).await? {
+        Some(DomainPolicyKind::Suspend) => Ok(true),
+        Some(_) => Ok(false),
+        None => Ok(instance::settings(pool).await?.allowlist_mode),
+    }
+}
+
+/// Like [`is_blocked`] for the host of `url`.
+pub async fn is_url_blocked(pool: &PgPool, url: &Url, local: &str) -> anyhow::Result<bool> {
+    match url.host_str() {
+        Some(host) => is_blocked(pool, host, local).await,
+        None => Ok(true),
+    }
+}
+
+/// Resolves `identifier` with webfinger unless its domain is blocked.
+pub async fn resolve_actor<Kind>(identifier: &str, data: &Data<DbHandle>) -> anyhow::Result<Kind>
+where
+    Kind: Object<DataType = DbHandle, Error = anyhow::Error> + Actor + Send + 'static,
+    for<'de2> <Kind as Object>::Kind: Deserialize<'de2>,
+{
+    if let Some((_, domain)) = identifier.rsplit_once('@') {
+        if is_blocked(data.app_data(), domain, data.domain()).await? {
+            return Err(anyhow!("Federation with {domain} is blocked"));
+        }
+    }
+    webfinger_resolve_actor(identifier, data).await
+}
+
+pub async fn list_policies(pool: &PgPool) -> anyhow::Result<Vec<DomainPolicy>> {
+    query!("SELECT domain, policy, reason, published FROM domain_policies ORDER BY domain")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| {
+            Ok(DomainPolicy {
+                domain: row.domain,
+                policy: DomainPolicyKind::from_str(&row.policy)?,
+                reason: row.reason,
+                published: row.published,
+            })
+        })
+        .collect()
+}
+
+/// Sets the policy for `domain`. Suspending a domain also drops follows
+/// between it and local users.
+pub async fn set_policy(
+    conn: &mut PgConnection,
+    domain: &str,
+    policy: DomainPolicyKind,
+    reason: Option<String>,
+) -> sqlx::Result<()> {
+    query!(
+        r#"INSERT INTO domain_policies (domain, policy, reason)
+           VALUES ($1, $2, $3)
+           ON CONFLICT (domain) DO UPDATE SET policy=$2, reason=$3"#,
+        domain,
+        policy.to_string(),
+        reason
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if policy == DomainPolicyKind::Suspend {
+        query!(
+            r#"DELETE FROM follows
+               WHERE substring(lower(follower) from '^[a-z]+://([^/:]+)') = $1
+                  OR right(substring(lower(follower) from '^[a-z]+://([^/:]+)'),
+                           length($1) + 1) = '.' || $1
+                  OR substring(lower(following) from '^[a-z]+://([^/:]+)') = $1
+                  OR right(substring(lower(following) from '^[a-z]+://([^/:]+)'),
+                           length($1) + 1) = '.' || $1"#,
+            domain
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn remove_policy<'e, E>(executor: E, domain: &str) -> sqlx::Result<bool>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    Ok(
+        query!("DELETE FROM domain_policies WHERE domain=$1", domain)
+            .execute(executor)
+            .await?
+            .rows_affected()
+            > 0,
+    )
+}
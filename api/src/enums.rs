@@ -47,3 +47,297 @@ pub enum Roles {
     Translator,
     Other,
 }
+
+#[derive(
+    Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq, Eq, Hash,
+)]
+pub enum Scopes {
+    #[strum(serialize = "read")]
+    #[serde(rename = "read")]
+    Read,
+    #[strum(serialize = "write")]
+    #[serde(rename = "write")]
+    Write,
+    #[strum(serialize = "write:novels")]
+    #[serde(rename = "write:novels")]
+    WriteNovels,
+    #[strum(serialize = "write:chapters")]
+    #[serde(rename = "write:chapters")]
+    WriteChapters,
+    #[strum(serialize = "follow")]
+    #[serde(rename = "follow")]
+    Follow,
+}
+
+impl Scopes {
+    /// Whether a token granted `self` may be used where `required` is needed.
+    pub fn allows(&self, required: &Scopes) -> bool {
+        match (self, required) {
+            (Scopes::Write, Scopes::WriteNovels | Scopes::WriteChapters) => true,
+            _ => self == required,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum RegistrationMode {
+    #[strum(serialize = "open")]
+    #[serde(rename = "open")]
+    Open,
+    #[strum(serialize = "approval")]
+    #[serde(rename = "approval")]
+    Approval,
+    #[strum(serialize = "invite")]
+    #[serde(rename = "invite")]
+    Invite,
+    #[strum(serialize = "closed")]
+    #[serde(rename = "closed")]
+    Closed,
+}
+
+/// Instance-level privileges, from least to most privileged.
+#[derive(
+    Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq, PartialOrd,
+)]
+pub enum InstanceRole {
+    #[strum(serialize = "user")]
+    #[serde(rename = "user")]
+    User,
+    #[strum(serialize = "moderator")]
+    #[serde(rename = "moderator")]
+    Moderator,
+    #[strum(serialize = "admin")]
+    #[serde(rename = "admin")]
+    Admin,
+    #[strum(serialize = "owner")]
+    #[serde(rename = "owner")]
+    Owner,
+}
+
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum ReportTarget {
+    #[strum(serialize = "user")]
+    #[serde(rename = "user")]
+    User,
+    #[strum(serialize = "novel")]
+    #[serde(rename = "novel")]
+    Novel,
+    #[strum(serialize = "chapter")]
+    #[serde(rename = "chapter")]
+    Chapter,
+}
+
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum ReportStatus {
+    #[strum(serialize = "open")]
+    #[serde(rename = "open")]
+    Open,
+    #[strum(serialize = "resolved")]
+    #[serde(rename = "resolved")]
+    Resolved,
+    #[strum(serialize = "dismissed")]
+    #[serde(rename = "dismissed")]
+    Dismissed,
+}
+
+/// What federation is allowed with a remote domain.
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum DomainPolicyKind {
+    /// Federates normally. Required for every domain in allowlist mode.
+    #[strum(serialize = "allow")]
+    #[serde(rename = "allow")]
+    Allow,
+    /// Federates, but content is left out of public listings.
+    #[strum(serialize = "silence")]
+    #[serde(rename = "silence")]
+    Silence,
+    /// No federation in either direction.
+    #[strum(serialize = "suspend")]
+    #[serde(rename = "suspend")]
+    Suspend,
+}
+
+/// A user-level filter on another actor or a whole domain.
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum FilterKind {
+    /// Hides the target and stops it from interacting. Federated for users.
+    #[strum(serialize = "block")]
+    #[serde(rename = "block")]
+    Block,
+    /// Hides the target from feeds and notifications. Never federated.
+    #[strum(serialize = "mute")]
+    #[serde(rename = "mute")]
+    Mute,
+}
+
+/// An action recorded in the moderation log.
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum ModAction {
+    #[strum(serialize = "suspend_user")]
+    #[serde(rename = "suspend_user")]
+    SuspendUser,
+    #[strum(serialize = "unsuspend_user")]
+    #[serde(rename = "unsuspend_user")]
+    UnsuspendUser,
+    #[strum(serialize = "hide_novel")]
+    #[serde(rename = "hide_novel")]
+    HideNovel,
+    #[strum(serialize = "unhide_novel")]
+    #[serde(rename = "unhide_novel")]
+    UnhideNovel,
+    #[strum(serialize = "silence_actor")]
+    #[serde(rename = "silence_actor")]
+    SilenceActor,
+    #[strum(serialize = "unsilence_actor")]
+    #[serde(rename = "unsilence_actor")]
+    UnsilenceActor,
+    #[strum(serialize = "force_sensitive")]
+    #[serde(rename = "force_sensitive")]
+    ForceSensitive,
+    #[strum(serialize = "unforce_sensitive")]
+    #[serde(rename = "unforce_sensitive")]
+    UnforceSensitive,
+    #[strum(serialize = "set_role")]
+    #[serde(rename = "set_role")]
+    SetRole,
+    #[strum(serialize = "approve_registration")]
+    #[serde(rename = "approve_registration")]
+    ApproveRegistration,
+    #[strum(serialize = "reject_registration")]
+    #[serde(rename = "reject_registration")]
+    RejectRegistration,
+    #[strum(serialize = "set_registration_mode")]
+    #[serde(rename = "set_registration_mode")]
+    SetRegistrationMode,
+    #[strum(serialize = "set_domain_policy")]
+    #[serde(rename = "set_domain_policy")]
+    SetDomainPolicy,
+    #[strum(serialize = "remove_domain_policy")]
+    #[serde(rename = "remove_domain_policy")]
+    RemoveDomainPolicy,
+    #[strum(serialize = "set_allowlist_mode")]
+    #[serde(rename = "set_allowlist_mode")]
+    SetAllowlistMode,
+    #[strum(serialize = "resolve_report")]
+    #[serde(rename = "resolve_report")]
+    ResolveReport,
+    #[strum(serialize = "dismiss_report")]
+    #[serde(rename = "dismiss_report")]
+    DismissReport,
+    #[strum(serialize = "approve_trending")]
+    #[serde(rename = "approve_trending")]
+    ApproveTrending,
+    #[strum(serialize = "hide_trending")]
+    #[serde(rename = "hide_trending")]
+    HideTrending,
+}
+
+/// A moderation restriction on a local or remote actor.
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum ActorRestriction {
+    /// Disables the account and hides everything it published.
+    #[strum(serialize = "suspend")]
+    #[serde(rename = "suspend")]
+    Suspend,
+    /// Keeps the actor out of public listings and discovery.
+    #[strum(serialize = "silence")]
+    #[serde(rename = "silence")]
+    Silence,
+    /// Marks every novel by the actor as sensitive.
+    #[strum(serialize = "force_sensitive")]
+    #[serde(rename = "force_sensitive")]
+    ForceSensitive,
+}
+
+/// How novels are ordered when browsing. There is no highest rated order
+/// yet since novels can't be rated until reviews are added.
+#[derive(
+    Clone, Debug, Default, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq,
+)]
+pub enum BrowseSort {
+    #[default]
+    #[strum(serialize = "newest")]
+    #[serde(rename = "newest")]
+    Newest,
+    /// By the latest chapter published or edited.
+    #[strum(serialize = "updated")]
+    #[serde(rename = "updated")]
+    Updated,
+    #[strum(serialize = "followed")]
+    #[serde(rename = "followed")]
+    Followed,
+}
+
+/// What a timeline entry is about.
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum TimelineEntryKind {
+    #[strum(serialize = "chapter")]
+    #[serde(rename = "chapter")]
+    Chapter,
+    #[strum(serialize = "novel")]
+    #[serde(rename = "novel")]
+    Novel,
+    /// A novel or chapter boosted by someone else.
+    #[strum(serialize = "boost")]
+    #[serde(rename = "boost")]
+    Boost,
+}
+
+/// What can show up as trending.
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum TrendingKind {
+    #[strum(serialize = "novel")]
+    #[serde(rename = "novel")]
+    Novel,
+    #[strum(serialize = "tag")]
+    #[serde(rename = "tag")]
+    Tag,
+}
+
+/// What a reading list is for. A novel is on at most one of the built-in
+/// shelves of a user at a time.
+#[derive(Clone, Debug, Display, EnumString, EnumIter, Serialize, Deserialize, PartialEq)]
+pub enum ShelfKind {
+    #[strum(serialize = "subscribed")]
+    #[serde(rename = "subscribed")]
+    Subscribed,
+    #[strum(serialize = "read")]
+    #[serde(rename = "read")]
+    Read,
+    #[strum(serialize = "want_to_read")]
+    #[serde(rename = "want_to_read")]
+    WantToRead,
+    #[strum(serialize = "dropped")]
+    #[serde(rename = "dropped")]
+    Dropped,
+    /// Named by the user.
+    #[strum(serialize = "custom")]
+    #[serde(rename = "custom")]
+    Custom,
+}
+
+impl ShelfKind {
+    /// The name of the built-in shelf of this kind.
+    pub fn default_name(&self) -> Option<&'static str> {
+        match self {
+            ShelfKind::Subscribed => Some("Subscribed"),
+            ShelfKind::Read => Some("Read"),
+            ShelfKind::WantToRead => Some("Want to Read"),
+            ShelfKind::Dropped => Some("Dropped"),
+            ShelfKind::Custom => None,
+        }
+    }
+}
+
+/// What a reader saved in a chapter.
+#[derive(Clone, Debug, Display, EnumString, Serialize, Deserialize, PartialEq)]
+pub enum BookmarkKind {
+    /// A paragraph to come back to.
+    #[strum(serialize = "bookmark")]
+    #[serde(rename = "bookmark")]
+    Bookmark,
+    /// A passage within a paragraph.
+    #[strum(serialize = "highlight")]
+    #[serde(rename = "highlight")]
+    Highlight,
+}
@@ -0,0 +1,127 @@
+use crate::enums::RegistrationMode;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, Executor, PgPool, Postgres};
+use std::str::FromStr;
+
+pub struct InstanceSettings {
+    pub registration_mode: RegistrationMode,
+    /// Only federate with domains that have an `allow` policy.
+    pub allowlist_mode: bool,
+}
+
+pub async fn settings(pool: &PgPool) -> anyhow::Result<InstanceSettings> {
+    let row = query!("SELECT registration_mode, allowlist_mode FROM instance_settings")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(InstanceSettings {
+        registration_mode: RegistrationMode::from_str(&row.registration_mode)?,
+        allowlist_mode: row.allowlist_mode,
+    })
+}
+
+pub async fn set_registration_mode<'e, E>(executor: E, mode: RegistrationMode) -> sqlx::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    query!(
+        "UPDATE instance_settings SET registration_mode=$1",
+        mode.to_string()
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingUser {
+    pub apub_id: String,
+    pub preferred_username: String,
+    pub email: String,
+    pub reason: Option<String>,
+    pub published: DateTime<Utc>,
+}
+
+pub async fn set_allowlist_mode<'e, E>(executor: E, enabled: bool) -> sqlx::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    query!("UPDATE instance_settings SET allowlist_mode=$1", enabled)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Lists accounts waiting for approval, oldest first.
+pub async fn pending_users(pool: &PgPool) -> anyhow::Result<Vec<PendingUser>> {
+    Ok(query!(
+        r#"SELECT apub_id, preferred_username, email AS "email!", reason, published
+           FROM users
+           WHERE NOT approved
+           ORDER BY published"#
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| PendingUser {
+        apub_id: row.apub_id,
+        preferred_username: row.preferred_username,
+        email: row.email,
+        reason: row.reason,
+        published: row.published,
+    })
+    .collect())
+}
+
+pub async fn approve_user<'e, E>(executor: E, apub_id: &str) -> sqlx::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    query!(
+        "UPDATE users SET approved=true WHERE lower(apub_id)=$1 AND NOT approved",
+        apub_id.to_lowercase()
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Rejects a pending account. The username and email become available again.
+pub async fn reject_user<'e, E>(executor: E, apub_id: &str) -> sqlx::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    query!(
+        "DELETE FROM users WHERE lower(apub_id)=$1 AND NOT approved",
+        apub_id.to_lowercase()
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+pub struct Usage {
+    pub users: i64,
+    pub novels: i64,
+    pub chapters: i64,
+}
+
+/// Counts local content for NodeInfo.
+pub async fn usage(pool: &PgPool) -> anyhow::Result<Usage> {
+    let row = query!(
+        r#"SELECT
+           (SELECT count(*) FROM users WHERE private_key IS NOT NULL AND approved) AS "users!",
+           (SELECT count(*) FROM novels WHERE private_key IS NOT NULL) AS "novels!",
+           (SELECT count(*) FROM chapters JOIN novels ON lower(chapters.audience)=lower(novels.apub_id)
+            WHERE novels.private_key IS NOT NULL) AS "chapters!""#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Usage {
+        users: row.users,
+        novels: row.novels,
+        chapters: row.chapters,
+    })
+}
@@ -0,0 +1,130 @@
+use crate::{
+    account::{current_user, UserValidateError},
+    oauth::random_token,
+};
+use actix_session::Session;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{query, query_as, PgPool, Postgres, Transaction};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum InviteError {
+    #[error("Invite: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Invite: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Invite: NotFound")]
+    NotFound,
+    #[error("Invite: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+impl From<UserValidateError> for InviteError {
+    fn from(e: UserValidateError) -> Self {
+        match e {
+            UserValidateError::Unauthorized(e) => InviteError::Unauthorized(e),
+            e => InviteError::InternalServerError(e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Invite {
+    pub code: String,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub expires: Option<DateTime<Utc>>,
+    pub published: DateTime<Utc>,
+}
+
+/// Creates an invite for the signed in user. `max_uses` and `expires_in`
+/// (in hours) are unlimited when `None`.
+pub async fn create_invite(
+    pool: &PgPool,
+    session: &Session,
+    max_uses: Option<i32>,
+    expires_in: Option<i64>,
+) -> Result<Invite, InviteError> {
+    let creator = current_user(pool, session).await?;
+
+    if max_uses.map_or(false, |m| m < 1) || expires_in.map_or(false, |e| e < 1) {
+        return Err(InviteError::BadRequest(
+            "Use limit and expiry must be positive".to_string(),
+        ));
+    }
+    let expires = expires_in.map(|h| Utc::now() + Duration::hours(h));
+
+    query_as!(
+        Invite,
+        r#"INSERT INTO invites
+           (code, creator, max_uses, expires)
+           VALUES ($1, $2, $3, $4)
+           RETURNING code, max_uses, uses, expires, published"#,
+        random_token(),
+        creator,
+        max_uses,
+        expires
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| InviteError::InternalServerError(e.to_string()))
+}
+
+/// Lists the invites created by the signed in user, newest first.
+pub async fn list_invites(pool: &PgPool, session: &Session) -> Result<Vec<Invite>, InviteError> {
+    let creator = current_user(pool, session).await?;
+
+    query_as!(
+        Invite,
+        r#"SELECT code, max_uses, uses, expires, published
+           FROM invites
+           WHERE lower(creator)=$1
+           ORDER BY published DESC"#,
+        creator.to_lowercase()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| InviteError::InternalServerError(e.to_string()))
+}
+
+pub async fn delete_invite(
+    pool: &PgPool,
+    session: &Session,
+    code: &str,
+) -> Result<(), InviteError> {
+    let creator = current_user(pool, session).await?;
+
+    let res = query!(
+        "DELETE FROM invites WHERE code=$1 AND lower(creator)=$2",
+        code,
+        creator.to_lowercase()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| InviteError::InternalServerError(e.to_string()))?;
+
+    if res.rows_affected() == 0 {
+        return Err(InviteError::NotFound);
+    }
+    Ok(())
+}
+
+/// Uses up one use of `code`, returning the invite's creator if it is still
+/// valid.
+pub(crate) async fn redeem(
+    tx: &mut Transaction<'_, Postgres>,
+    code: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    Ok(query!(
+        r#"UPDATE invites SET uses=uses+1
+           WHERE code=$1
+             AND (max_uses IS NULL OR uses < max_uses)
+             AND (expires IS NULL OR expires > now())
+           RETURNING creator"#,
+        code
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .map(|row| row.creator))
+}
@@ -3,9 +3,19 @@ use std::sync::Arc;
 
 pub mod account;
 pub mod activities;
+pub mod admin;
 pub mod api;
+pub mod domain_policy;
 pub mod enums;
+pub mod instance;
+pub mod invite;
+pub mod moderation_log;
+pub mod oauth;
 pub mod objects;
+pub mod report;
+pub mod restriction;
+pub mod throttle;
+pub mod user_filter;
 pub mod util;
 
 pub type DbHandle = Arc<PgPool>;
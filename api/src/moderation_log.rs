@@ -0,0 +1,125 @@
+use crate::enums::ModAction;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{query, Executor, PgPool, Postgres};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct LogEntry {
+    pub id: Uuid,
+    pub actor: String,
+    pub action: ModAction,
+    pub target: String,
+    pub reason: Option<String>,
+    pub published: DateTime<Utc>,
+}
+
+/// Narrows down the log. Every field is optional and `actor` and `target`
+/// match substrings.
+#[derive(Debug, Default, Deserialize)]
+pub struct LogFilter {
+    pub action: Option<ModAction>,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+    /// Only entries older than this, for paging.
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+/// Records a moderation action. Pass the transaction the action runs in, if
+/// any, so that the entry only exists if the action went through.
+pub(crate) async fn record<'e, E>(
+    executor: E,
+    actor: &str,
+    action: ModAction,
+    target: &str,
+    reason: Option<&str>,
+) -> sqlx::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    query!(
+        r#"INSERT INTO moderation_log (actor, action, target, reason)
+           VALUES ($1, $2, $3, $4)"#,
+        actor,
+        action.to_string(),
+        target,
+        reason.map(str::trim).filter(|r| !r.is_empty())
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Lists log entries matching `filter`, newest first. Without a limit every
+/// matching entry is returned.
+pub async fn list(pool: &PgPool, filter: &LogFilter) -> anyhow::Result<Vec<LogEntry>> {
+    query!(
+        r#"SELECT id, actor, action, target, reason, published
+           FROM moderation_log
+           WHERE ($1::text IS NULL OR action=$1)
+             AND ($2::text IS NULL OR strpos(lower(actor), lower($2)) > 0)
+             AND ($3::text IS NULL OR strpos(lower(target), lower($3)) > 0)
+             AND ($4::timestamptz IS NULL OR published < $4)
+           ORDER BY published DESC
+           LIMIT $5"#,
+        filter.action.as_ref().map(ModAction::to_string),
+        filter.actor.as_deref().filter(|a| !a.is_empty()),
+        filter.target.as_deref().filter(|t| !t.is_empty()),
+        filter.before,
+        filter.limit
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(LogEntry {
+            id: row.id,
+            actor: row.actor,
+            action: ModAction::from_str(&row.action)?,
+            target: row.target,
+            reason: row.reason,
+            published: row.published,
+        })
+    })
+    .collect()
+}
+
+/// Quotes `field` if needed, and prefixes it with `'` if it starts like a
+/// formula so spreadsheets show it as text instead of running it.
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{field}")
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Formats `entries` as CSV with a header row.
+pub fn to_csv(entries: &[LogEntry]) -> String {
+    let mut csv = String::from("id,published,actor,action,target,reason\n");
+    for e in entries {
+        let row = [
+            e.id.to_string(),
+            e.published.to_rfc3339_opts(SecondsFormat::Secs, true),
+            e.actor.clone(),
+            e.action.to_string(),
+            e.target.clone(),
+            e.reason.clone().unwrap_or_default(),
+        ];
+        csv.push_str(
+            &row.iter()
+                .map(|f| csv_field(f))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
@@ -0,0 +1,488 @@
+use crate::{
+    account::{current_user, UserValidateError},
+    enums::Scopes,
+};
+use actix_session::Session;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{query, PgPool};
+use std::str::FromStr;
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("OAuth: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("OAuth: Forbidden: {0}")]
+    Forbidden(String),
+    #[error("OAuth: InvalidClient")]
+    InvalidClient,
+    #[error("OAuth: InvalidGrant: {0}")]
+    InvalidGrant(String),
+    #[error("OAuth: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("OAuth: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+impl OAuthError {
+    /// The `error` code from RFC 6749 section 5.2.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Unauthorized(_) | Self::Forbidden(_) => "access_denied",
+            Self::InvalidClient => "invalid_client",
+            Self::InvalidGrant(_) => "invalid_grant",
+            Self::BadRequest(_) => "invalid_request",
+            Self::InternalServerError(_) => "server_error",
+        }
+    }
+}
+
+pub(crate) fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Tokens are only ever stored hashed.
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+pub fn parse_scopes(scopes: &str) -> Result<Vec<Scopes>, OAuthError> {
+    scopes
+        .split_whitespace()
+        .map(|s| {
+            Scopes::from_str(s).map_err(|_| OAuthError::BadRequest(format!("Unknown scope: {s}")))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|s| s.into_iter().unique().collect())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OAuthApp {
+    pub client_id: Uuid,
+    pub client_secret: String,
+    pub name: String,
+    pub website: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+}
+
+pub async fn register_app(
+    pool: &PgPool,
+    name: String,
+    website: Option<String>,
+    redirect_uris: String,
+    scopes: Option<String>,
+) -> Result<OAuthApp, OAuthError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(OAuthError::BadRequest("Missing client name".to_string()));
+    }
+    let redirect_uris = redirect_uris
+        .split_whitespace()
+        .map(|uri| {
+            Url::parse(uri)
+                .map(|_| uri.to_string())
+                .map_err(|e| OAuthError::BadRequest(format!("{uri}: {e}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if redirect_uris.is_empty() {
+        return Err(OAuthError::BadRequest("Missing redirect URI".to_string()));
+    }
+    let scopes = parse_scopes(scopes.as_deref().unwrap_or("read"))?
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect_vec();
+
+    let app = OAuthApp {
+        client_id: Uuid::new_v4(),
+        client_secret: random_token(),
+        name,
+        website,
+        redirect_uris,
+        scopes,
+    };
+
+    query!(
+        r#"INSERT INTO oauth_apps
+           (client_id, client_secret, name, website, redirect_uris, scopes)
+           VALUES ($1, $2, $3, $4, $5, $6)"#,
+        app.client_id,
+        hash_token(&app.client_secret),
+        app.name,
+        app.website,
+        app.redirect_uris.as_slice(),
+        app.scopes.as_slice()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?;
+
+    Ok(app)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthorizeRequest {
+    pub response_type: String,
+    pub client_id: Uuid,
+    pub redirect_uri: String,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    pub code_challenge_method: Option<String>,
+}
+
+/// What the consent screen shows about a pending authorization.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthorizeInfo {
+    pub name: String,
+    pub website: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+pub async fn authorize_info(
+    pool: &PgPool,
+    request: &AuthorizeRequest,
+) -> Result<AuthorizeInfo, OAuthError> {
+    if request.response_type != "code" {
+        return Err(OAuthError::BadRequest(
+            "Only the authorization code flow is supported".to_string(),
+        ));
+    }
+    if request.code_challenge.is_empty() {
+        return Err(OAuthError::BadRequest("PKCE is required".to_string()));
+    }
+    // A missing method means `plain` (RFC 7636 section 4.3), which isn't
+    // supported, so S256 has to be asked for explicitly.
+    match request.code_challenge_method.as_deref() {
+        Some("S256") => (),
+        None => {
+            return Err(OAuthError::BadRequest(
+                "code_challenge_method must be S256".to_string(),
+            ))
+        }
+        Some(m) => {
+            return Err(OAuthError::BadRequest(format!(
+                "Unsupported code challenge method: {m}"
+            )))
+        }
+    };
+
+    let app = query!(
+        "SELECT name, website, redirect_uris, scopes FROM oauth_apps WHERE client_id=$1",
+        request.client_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?
+    .ok_or(OAuthError::InvalidClient)?;
+
+    if !app.redirect_uris.contains(&request.redirect_uri) {
+        return Err(OAuthError::BadRequest(
+            "Unregistered redirect URI".to_string(),
+        ));
+    }
+
+    let scopes = parse_scopes(request.scope.as_deref().unwrap_or("read"))?
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect_vec();
+    if let Some(s) = scopes.iter().find(|s| !app.scopes.contains(s)) {
+        return Err(OAuthError::BadRequest(format!(
+            "Scope not registered for this app: {s}"
+        )));
+    }
+
+    Ok(AuthorizeInfo {
+        name: app.name,
+        website: app.website,
+        scopes,
+    })
+}
+
+/// Issues an authorization code for the signed in user and returns the URI the
+/// user agent should be sent back to.
+pub async fn authorize(
+    pool: &PgPool,
+    session: Session,
+    request: AuthorizeRequest,
+) -> Result<String, OAuthError> {
+    let apub_id = current_user(pool, &session).await.map_err(|e| match e {
+        UserValidateError::Unauthorized(e) => OAuthError::Unauthorized(e),
+        e => OAuthError::InternalServerError(e.to_string()),
+    })?;
+
+    let info = authorize_info(pool, &request).await?;
+    let code = random_token();
+
+    query!(
+        r#"INSERT INTO oauth_codes
+           (code, client_id, apub_id, redirect_uri, scopes, code_challenge, expires)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+        hash_token(&code),
+        request.client_id,
+        apub_id,
+        request.redirect_uri,
+        info.scopes.as_slice(),
+        request.code_challenge,
+        Utc::now() + Duration::minutes(10)
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?;
+
+    let mut redirect = Url::parse(&request.redirect_uri)
+        .map_err(|e| OAuthError::InternalServerError(e.to_string()))?;
+    redirect.query_pairs_mut().append_pair("code", &code);
+    if let Some(state) = request.state {
+        redirect.query_pairs_mut().append_pair("state", &state);
+    }
+    Ok(redirect.to_string())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub code: String,
+    pub redirect_uri: String,
+    pub client_id: Uuid,
+    pub client_secret: Option<String>,
+    pub code_verifier: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub scope: String,
+    pub created_at: i64,
+}
+
+async fn check_client(
+    pool: &PgPool,
+    client_id: Uuid,
+    client_secret: Option<&str>,
+) -> Result<(), OAuthError> {
+    let secret = query!(
+        "SELECT client_secret FROM oauth_apps WHERE client_id=$1",
+        client_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?
+    .ok_or(OAuthError::InvalidClient)?
+    .client_secret;
+
+    // Every app is registered with a secret, so it has to be proven on top
+    // of PKCE.
+    match client_secret {
+        Some(s) if hash_token(s) == secret => Ok(()),
+        _ => Err(OAuthError::InvalidClient),
+    }
+}
+
+pub async fn token(pool: &PgPool, request: TokenRequest) -> Result<TokenResponse, OAuthError> {
+    if request.grant_type != "authorization_code" {
+        return Err(OAuthError::BadRequest(format!(
+            "Unsupported grant type: {}",
+            request.grant_type
+        )));
+    }
+    check_client(pool, request.client_id, request.client_secret.as_deref()).await?;
+
+    let code = query!(
+        r#"DELETE FROM oauth_codes WHERE code=$1
+           RETURNING client_id, apub_id, redirect_uri, scopes, code_challenge, expires"#,
+        hash_token(&request.code)
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?
+    .ok_or_else(|| OAuthError::InvalidGrant("Unknown authorization code".to_string()))?;
+
+    if code.client_id != request.client_id || code.redirect_uri != request.redirect_uri {
+        return Err(OAuthError::InvalidGrant(
+            "Authorization code was issued to another client".to_string(),
+        ));
+    }
+    if code.expires < Utc::now() {
+        return Err(OAuthError::InvalidGrant(
+            "Authorization code expired".to_string(),
+        ));
+    }
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(request.code_verifier.as_bytes()));
+    if challenge != code.code_challenge {
+        return Err(OAuthError::InvalidGrant(
+            "Code verifier does not match".to_string(),
+        ));
+    }
+
+    let token = random_token();
+    let created = query!(
+        r#"INSERT INTO access_tokens (token, apub_id, client_id, scopes)
+           SELECT $1, $2, $3, $4 FROM users
+           WHERE lower(apub_id)=lower($2) AND suspended_at IS NULL
+           RETURNING published"#,
+        hash_token(&token),
+        code.apub_id,
+        code.client_id,
+        code.scopes.as_slice()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?
+    .ok_or_else(|| OAuthError::InvalidGrant("Account is suspended".to_string()))?
+    .published;
+
+    Ok(TokenResponse {
+        access_token: token,
+        token_type: "Bearer".to_string(),
+        scope: code.scopes.join(" "),
+        created_at: created.timestamp(),
+    })
+}
+
+pub async fn revoke(
+    pool: &PgPool,
+    client_id: Uuid,
+    client_secret: Option<String>,
+    token: String,
+) -> Result<(), OAuthError> {
+    check_client(pool, client_id, client_secret.as_deref()).await?;
+
+    // RFC 7009 says unknown tokens are not an error.
+    query!(
+        "DELETE FROM access_tokens WHERE token=$1 AND client_id=$2",
+        hash_token(&token),
+        client_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Resolves a bearer token to the user it acts for, if it grants `scope`.
+/// Tokens of suspended users are treated as invalid.
+pub async fn authenticate(pool: &PgPool, token: &str, scope: Scopes) -> Result<String, OAuthError> {
+    let row = query!(
+        r#"UPDATE access_tokens SET last_used=now()
+           FROM users
+           WHERE token=$1
+             AND lower(users.apub_id)=lower(access_tokens.apub_id)
+             AND users.suspended_at IS NULL
+           RETURNING access_tokens.apub_id, access_tokens.scopes"#,
+        hash_token(token)
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?
+    .ok_or_else(|| OAuthError::Unauthorized("Invalid access token".to_string()))?;
+
+    row.scopes
+        .iter()
+        .filter_map(|s| Scopes::from_str(s).ok())
+        .any(|s| s.allows(&scope))
+        .then_some(row.apub_id)
+        .ok_or_else(|| OAuthError::Forbidden(format!("Token lacks the {scope} scope")))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub app: Option<String>,
+    pub scopes: Vec<String>,
+    pub published: DateTime<Utc>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// Creates a personal access token for `apub_id`. The token is only returned
+/// here and cannot be recovered later.
+pub async fn create_personal_token(
+    pool: &PgPool,
+    apub_id: &str,
+    name: String,
+    scopes: Vec<Scopes>,
+) -> Result<String, OAuthError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(OAuthError::BadRequest("Token name is required".to_string()));
+    }
+    if scopes.is_empty() {
+        return Err(OAuthError::BadRequest(
+            "Select at least one scope".to_string(),
+        ));
+    }
+    let scopes = scopes
+        .into_iter()
+        .unique()
+        .map(|s| s.to_string())
+        .collect_vec();
+
+    let token = random_token();
+    query!(
+        r#"INSERT INTO access_tokens (token, apub_id, name, scopes)
+           VALUES ($1, $2, $3, $4)"#,
+        hash_token(&token),
+        apub_id,
+        name,
+        scopes.as_slice()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?;
+
+    Ok(token)
+}
+
+/// Lists personal tokens and authorized applications of `apub_id`.
+pub async fn list_tokens(pool: &PgPool, apub_id: &str) -> Result<Vec<TokenInfo>, OAuthError> {
+    Ok(query!(
+        r#"SELECT access_tokens.id, access_tokens.name, oauth_apps.name AS "app?",
+             access_tokens.scopes, access_tokens.published, access_tokens.last_used
+           FROM access_tokens
+           LEFT JOIN oauth_apps ON access_tokens.client_id=oauth_apps.client_id
+           WHERE access_tokens.apub_id=$1
+           ORDER BY access_tokens.published DESC"#,
+        apub_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| TokenInfo {
+        id: row.id,
+        name: row.name.or(row.app.clone()).unwrap_or_default(),
+        app: row.app,
+        scopes: row.scopes,
+        published: row.published,
+        last_used: row.last_used,
+    })
+    .collect())
+}
+
+pub async fn revoke_token(pool: &PgPool, apub_id: &str, id: Uuid) -> Result<(), OAuthError> {
+    query!(
+        "DELETE FROM access_tokens WHERE id=$1 AND apub_id=$2",
+        id,
+        apub_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| OAuthError::InternalServerError(e.to_string()))?;
+
+    Ok(())
+}
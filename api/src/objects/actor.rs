@@ -0,0 +1,110 @@
+use crate::{
+    objects::{
+        novel::{DbNovel, Novel},
+        person::{Person, User},
+    },
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    traits::{Actor, Object},
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// A user or a novel. Inboxes that take activities from both, like the
+/// `Announce`s novels send to their followers, verify signatures with this.
+pub enum DbActor {
+    User(User),
+    Novel(DbNovel),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ActorJson {
+    Person(Person),
+    Group(Box<Novel>),
+}
+
+#[async_trait]
+impl Object for DbActor {
+    type DataType = DbHandle;
+    type Kind = ActorJson;
+    type Error = anyhow::Error;
+
+    fn last_refreshed_at(&self) -> Option<NaiveDateTime> {
+        match self {
+            Self::User(user) => user.last_refreshed_at(),
+            Self::Novel(novel) => novel.last_refreshed_at(),
+        }
+    }
+
+    async fn read_from_id(
+        object_id: Url,
+        data: &Data<Self::DataType>,
+    ) -> Result<Option<Self>, Self::Error> {
+        if let Some(user) = User::read_from_id(object_id.clone(), data).await? {
+            return Ok(Some(Self::User(user)));
+        }
+        Ok(DbNovel::read_from_id(object_id, data)
+            .await?
+            .map(Self::Novel))
+    }
+
+    async fn into_json(self, data: &Data<Self::DataType>) -> Result<Self::Kind, Self::Error> {
+        Ok(match self {
+            Self::User(user) => ActorJson::Person(user.into_json(data).await?),
+            Self::Novel(novel) => ActorJson::Group(Box::new(novel.into_json(data).await?)),
+        })
+    }
+
+    async fn verify(
+        json: &Self::Kind,
+        expected_domain: &Url,
+        data: &Data<Self::DataType>,
+    ) -> Result<(), Self::Error> {
+        match json {
+            ActorJson::Person(person) => User::verify(person, expected_domain, data).await,
+            ActorJson::Group(novel) => DbNovel::verify(novel, expected_domain, data).await,
+        }
+    }
+
+    async fn from_json(json: Self::Kind, data: &Data<Self::DataType>) -> Result<Self, Self::Error> {
+        Ok(match json {
+            ActorJson::Person(person) => Self::User(User::from_json(person, data).await?),
+            ActorJson::Group(novel) => Self::Novel(DbNovel::from_json(*novel, data).await?),
+        })
+    }
+}
+
+impl Actor for DbActor {
+    fn id(&self) -> Url {
+        match self {
+            Self::User(user) => user.id(),
+            Self::Novel(novel) => novel.id(),
+        }
+    }
+
+    fn inbox(&self) -> Url {
+        match self {
+            Self::User(user) => user.inbox(),
+            Self::Novel(novel) => novel.inbox(),
+        }
+    }
+
+    fn public_key_pem(&self) -> &str {
+        match self {
+            Self::User(user) => user.public_key_pem(),
+            Self::Novel(novel) => novel.public_key_pem(),
+        }
+    }
+
+    fn private_key_pem(&self) -> Option<String> {
+        match self {
+            Self::User(user) => user.private_key_pem(),
+            Self::Novel(novel) => novel.private_key_pem(),
+        }
+    }
+}
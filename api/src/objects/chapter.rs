@@ -90,14 +90,16 @@ impl Object for Chapter {
         _data: &Data<Self::DataType>,
     ) -> Result<(), Self::Error> {
         verify_domains_match(json.id.inner(), expected_domain)?;
+        // Only the novel's own server may add chapters to it.
+        verify_domains_match(&json.audience, json.id.inner())?;
         Ok(())
     }
 
-    async fn from_json(
-        json: Self::Kind,
-        _data: &Data<Self::DataType>,
-    ) -> Result<Self, Self::Error> {
-        Ok(Self {
+    /// Stores the remote chapter. New chapters are numbered after the ones
+    /// already known for the novel, since `Article` has no sequence number.
+    /// Chapters of local novels are never written from remote objects.
+    async fn from_json(json: Self::Kind, data: &Data<Self::DataType>) -> Result<Self, Self::Error> {
+        let chapter = Self {
             apub_id: json.id.into_inner().into(),
             audience: json.audience.into(),
             title: json.name,
@@ -110,7 +112,39 @@ impl Object for Chapter {
                 Some(u) => Some(u.parse()?),
             },
             last_refresh: Local::now().naive_local(),
-        })
+        };
+
+        query!(
+            r#"INSERT INTO chapters
+               (apub_id, audience, sequence, title, summary, sensitive, content,
+                 published, updated, last_refresh)
+               SELECT $1, $2,
+                 (SELECT coalesce(max(sequence) + 1, 0) FROM chapters
+                  WHERE lower(audience)=lower($2)),
+                 $3, $4, $5, $6, $7, $8, $9
+               WHERE NOT EXISTS(SELECT 1 FROM novels
+                                WHERE lower(apub_id)=lower($2)
+                                  AND private_key IS NOT NULL)
+               ON CONFLICT (apub_id) DO UPDATE SET
+                 title=$3, summary=$4, sensitive=$5, content=$6, updated=$8,
+                 last_refresh=$9
+               WHERE NOT EXISTS(SELECT 1 FROM novels
+                                WHERE lower(apub_id)=lower(chapters.audience)
+                                  AND private_key IS NOT NULL)"#,
+            chapter.apub_id,
+            chapter.audience,
+            chapter.title,
+            chapter.summary,
+            chapter.sensitive,
+            chapter.content,
+            chapter.published,
+            chapter.updated,
+            chapter.last_refresh
+        )
+        .execute(data.app_data().as_ref())
+        .await?;
+
+        Ok(chapter)
     }
 }
 
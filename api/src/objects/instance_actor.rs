@@ -0,0 +1,142 @@
+use crate::{util::is_local_url, DbHandle};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    http_signatures::generate_actor_keypair,
+    kinds::actor::ApplicationType,
+    protocol::{public_key::PublicKey, verification::verify_domains_match},
+    traits::{Actor, Object},
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::query;
+use url::Url;
+
+/// The actor of the instance itself. It sends the activities that shouldn't
+/// come from any one user, like forwarded reports.
+#[derive(Clone, Debug)]
+pub struct InstanceActor {
+    pub apub_id: String,
+    pub inbox: String,
+    pub public_key: String,
+    private_key: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Application {
+    id: ObjectId<InstanceActor>,
+    #[serde(rename = "type")]
+    kind: ApplicationType,
+    preferred_username: String,
+    inbox: Url,
+    public_key: PublicKey,
+}
+
+impl InstanceActor {
+    /// Loads the instance actor, creating its keys the first time.
+    pub async fn read(scheme: &str, data: &Data<DbHandle>) -> anyhow::Result<Self> {
+        let keys = query!("SELECT public_key, private_key FROM instance_settings")
+            .fetch_one(data.app_data().as_ref())
+            .await?;
+        let (public_key, private_key) = match (keys.public_key, keys.private_key) {
+            (Some(public_key), Some(private_key)) => (public_key, private_key),
+            _ => {
+                let keypair = generate_actor_keypair()?;
+                // Whichever request gets here first creates the keys.
+                query!(
+                    r#"UPDATE instance_settings SET public_key=$1, private_key=$2
+                       WHERE public_key IS NULL OR private_key IS NULL"#,
+                    keypair.public_key,
+                    keypair.private_key
+                )
+                .execute(data.app_data().as_ref())
+                .await?;
+                let keys = query!(
+                    r#"SELECT public_key AS "public_key!", private_key AS "private_key!"
+                       FROM instance_settings"#
+                )
+                .fetch_one(data.app_data().as_ref())
+                .await?;
+                (keys.public_key, keys.private_key)
+            }
+        };
+
+        let apub_id = format!("{scheme}://{}/actor", data.domain());
+        Ok(Self {
+            inbox: format!("{apub_id}/inbox"),
+            apub_id,
+            public_key,
+            private_key,
+        })
+    }
+}
+
+#[async_trait]
+impl Object for InstanceActor {
+    type DataType = DbHandle;
+    type Kind = Application;
+    type Error = anyhow::Error;
+
+    fn last_refreshed_at(&self) -> Option<NaiveDateTime> {
+        None
+    }
+
+    async fn read_from_id(
+        object_id: Url,
+        data: &Data<Self::DataType>,
+    ) -> Result<Option<Self>, Self::Error> {
+        if object_id.path() != "/actor" || !is_local_url(&object_id, data.domain()) {
+            return Ok(None);
+        }
+        Ok(Some(Self::read(object_id.scheme(), data).await?))
+    }
+
+    async fn into_json(self, _data: &Data<Self::DataType>) -> Result<Self::Kind, Self::Error> {
+        Ok(Self::Kind {
+            id: self.apub_id.parse::<Url>()?.into(),
+            kind: Default::default(),
+            preferred_username: "wordforge".to_string(),
+            inbox: self.inbox.parse()?,
+            public_key: self.public_key(),
+        })
+    }
+
+    async fn verify(
+        json: &Self::Kind,
+        expected_domain: &Url,
+        _data: &Data<Self::DataType>,
+    ) -> Result<(), Self::Error> {
+        verify_domains_match(json.id.inner(), expected_domain)?;
+        Ok(())
+    }
+
+    /// Only the local instance actor is known. Other instances' actors are
+    /// never stored.
+    async fn from_json(
+        _json: Self::Kind,
+        _data: &Data<Self::DataType>,
+    ) -> Result<Self, Self::Error> {
+        Err(anyhow!("Remote instance actors aren't stored"))
+    }
+}
+
+impl Actor for InstanceActor {
+    fn id(&self) -> Url {
+        self.apub_id.parse().unwrap()
+    }
+
+    fn inbox(&self) -> Url {
+        self.inbox.parse().unwrap()
+    }
+
+    fn public_key_pem(&self) -> &str {
+        &self.public_key
+    }
+
+    fn private_key_pem(&self) -> Option<String> {
+        Some(self.private_key.clone())
+    }
+}
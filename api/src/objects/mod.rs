@@ -1,4 +1,7 @@
+pub mod actor;
 pub mod chapter;
+pub mod instance_actor;
 pub mod novel;
 pub mod person;
+pub mod shelf;
 pub mod novel_list;
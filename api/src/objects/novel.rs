@@ -1,7 +1,8 @@
 use crate::{
     activities,
     enums::{Genres, Roles},
-    util::USERNAME_RE,
+    restriction,
+    util::{parse_tags, USERNAME_RE},
     DbHandle,
 };
 use activitypub_federation::{
@@ -27,6 +28,10 @@ use validator::Validate;
 #[enum_delegate::implement(ActivityHandler)]
 pub enum NovelAcceptedActivities {
     Add(activities::add::Add),
+    Move(activities::move_account::Move),
+    Flag(activities::flag::Flag),
+    Follow(activities::follow::Follow),
+    Undo(activities::undo::Undo),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -53,12 +58,14 @@ pub struct DbNovel {
     private_key: Option<String>,
     pub published: DateTime<Utc>,
     pub last_refresh: NaiveDateTime,
+    /// Hidden by a moderator from local listings and pages.
+    pub hidden: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct Novel {
-    id: ObjectId<DbNovel>,
+    pub id: ObjectId<DbNovel>,
     #[serde(rename = "type")]
     kind: GroupType,
     #[validate(regex(path = "USERNAME_RE", message = "Invalid username"))]
@@ -68,7 +75,11 @@ pub struct Novel {
     pub authors: Vec<Author>,
     attributed_to: Vec<Url>,
     pub genre: Genres,
+    #[serde(default)]
     pub tags: Vec<String>,
+    /// The tags as `Hashtag`s, for software that doesn't know about `tags`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag: Vec<Tag>,
     pub language: String,
     pub sensitive: bool,
     inbox: Url,
@@ -77,13 +88,26 @@ pub struct Novel {
     pub published: String,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum Tag {
+    Hashtag {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        href: Option<Url>,
+        name: String,
+    },
+    /// Mentions, emoji and anything else that isn't a tag of the novel.
+    #[serde(other)]
+    Other,
+}
+
 impl DbNovel {
     pub async fn read_from_uuid(
         uuid: Uuid,
         data: &Data<DbHandle>,
     ) -> Result<Option<Self>, anyhow::Error> {
         let apub_id = match query!(
-            "SELECT apub_id FROM novels WHERE preferred_username=$1",
+            "SELECT apub_id FROM novels WHERE preferred_username=$1 AND private_key IS NOT NULL",
             uuid
         )
         .fetch_optional(data.app_data().as_ref())
@@ -127,7 +151,7 @@ impl Object for DbNovel {
         let novel = query!(
             r#"SELECT apub_id, preferred_username, title, summary, genre, tags,
                language, sensitive, inbox, outbox, public_key, private_key,
-               published, last_refresh
+               published, last_refresh, hidden
                FROM novels WHERE lower(apub_id)=$1"#,
             object_id.to_string().to_lowercase()
         )
@@ -149,14 +173,27 @@ impl Object for DbNovel {
             private_key: row.private_key,
             published: row.published,
             last_refresh: row.last_refresh,
+            hidden: row.hidden,
         });
 
         Ok(novel)
     }
 
-    async fn into_json(self, _data: &Data<Self::DataType>) -> Result<Self::Kind, Self::Error> {
+    async fn into_json(self, data: &Data<Self::DataType>) -> Result<Self::Kind, Self::Error> {
+        let id: Url = self.apub_id.parse()?;
+        let tag_base: Url = format!("{}://{}/tags/", id.scheme(), data.domain()).parse()?;
+        let tag = self
+            .tags
+            .iter()
+            .map(|t| {
+                Ok(Tag::Hashtag {
+                    href: Some(tag_base.join(&t.to_lowercase())?),
+                    name: format!("#{t}"),
+                })
+            })
+            .collect::<Result<_, url::ParseError>>()?;
         Ok(Self::Kind {
-            id: self.apub_id.parse()?,
+            id: id.into(),
             kind: Default::default(),
             preferred_username: self.preferred_username.to_string().to_lowercase(),
             name: self.title.clone(),
@@ -169,6 +206,7 @@ impl Object for DbNovel {
                 .collect(),
             genre: self.genre.clone(),
             tags: self.tags.clone(),
+            tag,
             language: self.language.to_639_1().unwrap().to_string(),
             sensitive: self.sensitive,
             inbox: self.inbox.parse()?,
@@ -187,28 +225,110 @@ impl Object for DbNovel {
         Ok(())
     }
 
-    async fn from_json(
-        json: Self::Kind,
-        _data: &Data<Self::DataType>,
-    ) -> Result<Self, Self::Error> {
-        Ok(Self {
+    /// Stores the remote novel and its authors. A novel hidden by a moderator
+    /// stays hidden when it is refreshed. Only authors on the novel's own
+    /// server are kept, so that a remote novel can't claim anyone else.
+    async fn from_json(json: Self::Kind, data: &Data<Self::DataType>) -> Result<Self, Self::Error> {
+        let novel_domain = json.id.inner().domain().map(str::to_lowercase);
+        let json_authors = json
+            .authors
+            .into_iter()
+            .filter(|a| {
+                Url::parse(&a.apub_id)
+                    .ok()
+                    .and_then(|u| u.domain().map(str::to_lowercase))
+                    == novel_domain
+            })
+            .collect::<Vec<_>>();
+        let authors = json_authors
+            .iter()
+            .map(|a| a.apub_id.clone())
+            .collect::<Vec<_>>();
+        let forced = restriction::any_force_sensitive(data.app_data(), &authors).await?;
+        let mut novel = Self {
             apub_id: json.id.into_inner().into(),
             preferred_username: json.preferred_username.parse()?,
             title: json.name,
             summary: json.summary,
-            authors: json.authors,
+            authors: json_authors,
             genre: json.genre,
-            tags: json.tags,
+            tags: parse_tags(
+                &json
+                    .tags
+                    .iter()
+                    .map(String::as_str)
+                    .chain(json.tag.iter().filter_map(|t| match t {
+                        Tag::Hashtag { name, .. } => Some(name.as_str()),
+                        Tag::Other => None,
+                    }))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
             language: Language::from_639_1(json.language.as_str())
                 .ok_or_else(|| anyhow!("Unknown language"))?,
-            sensitive: json.sensitive,
+            sensitive: json.sensitive || forced,
             inbox: json.inbox.into(),
             outbox: json.outbox.into(),
             public_key: json.public_key.public_key_pem,
             private_key: None,
             published: json.published.parse()?,
             last_refresh: Local::now().naive_local(),
-        })
+            hidden: false,
+        };
+
+        let mut tx = data.app_data().begin().await?;
+        let stored = query!(
+            r#"INSERT INTO novels
+               (apub_id, preferred_username, title, summary, genre, tags, language,
+                 sensitive, inbox, outbox, public_key, published, last_refresh)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+               ON CONFLICT (apub_id) DO UPDATE SET
+                 preferred_username=$2, title=$3, summary=$4, genre=$5, tags=$6,
+                 language=$7, sensitive=$8, inbox=$9, outbox=$10, public_key=$11,
+                 last_refresh=$13
+               WHERE novels.private_key IS NULL
+               RETURNING hidden"#,
+            novel.apub_id,
+            novel.preferred_username,
+            novel.title,
+            novel.summary,
+            novel.genre.to_string(),
+            &novel.tags,
+            novel.language.to_639_1(),
+            novel.sensitive,
+            novel.inbox,
+            novel.outbox,
+            novel.public_key,
+            novel.published,
+            novel.last_refresh
+        )
+        .fetch_optional(&mut tx)
+        .await?;
+
+        // Nothing is returned when the id belongs to a local novel, which is
+        // never overwritten.
+        if let Some(row) = stored {
+            novel.hidden = row.hidden;
+            query!(
+                "DELETE FROM author_roles WHERE lower(id)=$1",
+                novel.apub_id.to_lowercase()
+            )
+            .execute(&mut tx)
+            .await?;
+            for author in &novel.authors {
+                query!(
+                    "INSERT INTO author_roles VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+                    novel.apub_id,
+                    author.apub_id,
+                    author.role.to_string()
+                )
+                .execute(&mut tx)
+                .await?;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(novel)
     }
 }
 
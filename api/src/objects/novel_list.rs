@@ -36,7 +36,8 @@ impl Collection for NovelList {
                 author_roles, novels
             WHERE
                 lower(author)=$1 AND
-                author_roles.id = novels.apub_id
+                author_roles.id = novels.apub_id AND
+                NOT novels.hidden
             ORDER BY published DESC
         "#,
             owner.apub_id.to_string().to_lowercase()
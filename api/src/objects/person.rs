@@ -1,18 +1,34 @@
-use crate::{util::USERNAME_RE, DbHandle};
+use super::shelf::shelf_id;
+use crate::{activities, util::USERNAME_RE, DbHandle};
 use activitypub_federation::{
     config::Data,
     fetch::object_id::ObjectId,
     kinds::actor::PersonType,
     protocol::{public_key::PublicKey, verification::verify_domains_match},
-    traits::{Actor, Object},
+    traits::{ActivityHandler, Actor, Object},
 };
 use async_trait::async_trait;
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
-use sqlx::{query_as, PgPool};
+use sqlx::{query, query_as, PgPool};
 use url::Url;
 use validator::Validate;
 
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+#[enum_delegate::implement(ActivityHandler)]
+pub enum UserAcceptedActivities {
+    Move(activities::move_account::Move),
+    Flag(activities::flag::Flag),
+    Follow(activities::follow::Follow),
+    Accept(activities::follow::Accept),
+    Reject(activities::follow::Reject),
+    Block(activities::block::Block),
+    Undo(activities::undo::Undo),
+    Create(activities::create::Create),
+    Announce(activities::announce::Announce),
+}
+
 #[derive(Serialize, Deserialize, Validate)]
 pub struct User {
     pub apub_id: String,
@@ -27,6 +43,8 @@ pub struct User {
     private_key: Option<String>,
     pub published: DateTime<Utc>,
     pub last_refresh: NaiveDateTime,
+    pub also_known_as: Vec<String>,
+    pub moved_to: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -42,6 +60,13 @@ pub struct Person {
     outbox: Url,
     public_key: PublicKey,
     published: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    also_known_as: Vec<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    moved_to: Option<Url>,
+    /// Public reading lists.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    streams: Vec<Url>,
 }
 
 impl User {
@@ -52,8 +77,9 @@ impl User {
         query_as!(
             Self,
             r#"SELECT apub_id, preferred_username, name, summary, inbox, outbox,
-            public_key, null as private_key, published, last_refresh
-            FROM users WHERE preferred_username=$1"#,
+            public_key, null as private_key, published, last_refresh,
+            also_known_as, moved_to
+            FROM users WHERE preferred_username=$1 AND private_key IS NOT NULL"#,
             username.to_lowercase()
         )
         .fetch_optional(data)
@@ -78,8 +104,9 @@ impl Object for User {
         query_as!(
             Self,
             r#"SELECT apub_id, preferred_username, name, summary, inbox, outbox,
-            public_key, private_key, published, last_refresh
-            FROM users WHERE apub_id=$1"#,
+            public_key, private_key, published, last_refresh,
+            also_known_as, moved_to
+            FROM users WHERE lower(apub_id)=$1"#,
             object_id.to_string().to_lowercase()
         )
         .fetch_optional(data.app_data().as_ref())
@@ -87,7 +114,20 @@ impl Object for User {
         .map_err(Self::Error::new)
     }
 
-    async fn into_json(self, _data: &Data<Self::DataType>) -> Result<Self::Kind, Self::Error> {
+    async fn into_json(self, data: &Data<Self::DataType>) -> Result<Self::Kind, Self::Error> {
+        let streams = if self.private_key.is_some() {
+            query!(
+                "SELECT id FROM shelves WHERE lower(owner)=$1 AND public ORDER BY published",
+                self.apub_id.to_lowercase()
+            )
+            .fetch_all(data.app_data().as_ref())
+            .await?
+            .into_iter()
+            .map(|row| shelf_id(&self, row.id))
+            .collect::<Result<_, _>>()?
+        } else {
+            vec![]
+        };
         Ok(Self::Kind {
             id: self.apub_id.parse()?,
             kind: Default::default(),
@@ -98,6 +138,13 @@ impl Object for User {
             outbox: self.outbox.parse()?,
             public_key: self.public_key(),
             published: self.published.to_rfc3339_opts(SecondsFormat::Millis, true),
+            also_known_as: self
+                .also_known_as
+                .iter()
+                .filter_map(|a| a.parse().ok())
+                .collect(),
+            moved_to: self.moved_to.map(|m| m.parse()).transpose()?,
+            streams,
         })
     }
 
@@ -110,11 +157,10 @@ impl Object for User {
         Ok(())
     }
 
-    async fn from_json(
-        json: Self::Kind,
-        _data: &Data<Self::DataType>,
-    ) -> Result<Self, Self::Error> {
-        Ok(Self {
+    /// Stores the remote user so that it can be listed, searched and
+    /// moderated like local ones.
+    async fn from_json(json: Self::Kind, data: &Data<Self::DataType>) -> Result<Self, Self::Error> {
+        let user = Self {
             apub_id: json.id.into_inner().into(),
             preferred_username: json.preferred_username,
             name: json.name,
@@ -125,7 +171,35 @@ impl Object for User {
             private_key: None,
             published: json.published.parse()?,
             last_refresh: Local::now().naive_local(),
-        })
+            also_known_as: json.also_known_as.into_iter().map(|a| a.into()).collect(),
+            moved_to: json.moved_to.map(|m| m.into()),
+        };
+
+        query!(
+            r#"INSERT INTO users
+               (apub_id, preferred_username, name, summary, inbox, outbox, public_key,
+                published, last_refresh, also_known_as, moved_to)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               ON CONFLICT (apub_id) DO UPDATE SET
+                 preferred_username=$2, name=$3, summary=$4, inbox=$5, outbox=$6,
+                 public_key=$7, last_refresh=$9, also_known_as=$10, moved_to=$11
+               WHERE users.private_key IS NULL"#,
+            user.apub_id,
+            user.preferred_username,
+            user.name,
+            user.summary,
+            user.inbox,
+            user.outbox,
+            user.public_key,
+            user.published,
+            user.last_refresh,
+            &user.also_known_as,
+            user.moved_to
+        )
+        .execute(data.app_data().as_ref())
+        .await?;
+
+        Ok(user)
     }
 }
 
@@ -0,0 +1,69 @@
+use super::person::User;
+use crate::DbHandle;
+use activitypub_federation::{config::Data, kinds::collection::OrderedCollectionType};
+use serde::{Deserialize, Serialize};
+use sqlx::query;
+use url::Url;
+use uuid::Uuid;
+
+/// A public reading list of a user, listed in the `streams` of their
+/// `Person`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shelf {
+    id: Url,
+    #[serde(rename = "type")]
+    kind: OrderedCollectionType,
+    name: String,
+    attributed_to: Url,
+    total_items: usize,
+    pub ordered_items: Vec<String>,
+}
+
+/// The id of a shelf of the local user `owner`.
+pub fn shelf_id(owner: &User, id: Uuid) -> Result<Url, url::ParseError> {
+    Url::parse(&format!("{}/shelves/{id}", owner.apub_id))
+}
+
+impl Shelf {
+    /// Reads a public shelf of the local user `owner`, newest additions first.
+    pub async fn read_local(
+        owner: &User,
+        id: Uuid,
+        data: &Data<DbHandle>,
+    ) -> anyhow::Result<Option<Self>> {
+        let shelf = match query!(
+            "SELECT name FROM shelves WHERE id=$1 AND lower(owner)=$2 AND public",
+            id,
+            owner.apub_id.to_lowercase()
+        )
+        .fetch_optional(data.app_data().as_ref())
+        .await?
+        {
+            Some(shelf) => shelf,
+            None => return Ok(None),
+        };
+
+        let novels: Vec<String> = query!(
+            r#"SELECT n.apub_id FROM shelf_items i
+               JOIN novels n ON lower(n.apub_id)=lower(i.novel)
+               WHERE i.shelf=$1 AND NOT n.hidden AND NOT authors_hidden(n.apub_id)
+               ORDER BY i.published DESC"#,
+            id
+        )
+        .fetch_all(data.app_data().as_ref())
+        .await?
+        .into_iter()
+        .map(|row| row.apub_id)
+        .collect();
+
+        Ok(Some(Self {
+            id: shelf_id(owner, id)?,
+            kind: Default::default(),
+            name: shelf.name,
+            attributed_to: owner.apub_id.parse()?,
+            total_items: novels.len(),
+            ordered_items: novels,
+        }))
+    }
+}
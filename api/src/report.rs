@@ -0,0 +1,319 @@
+use crate::{
+    account::{require_role, UserValidateError},
+    activities::flag::Flag,
+    enums::{InstanceRole, ModAction, ReportStatus, ReportTarget},
+    moderation_log,
+    objects::{chapter::Chapter, novel::DbNovel, person::User},
+    throttle,
+    util::{is_local_url, RedisHandle},
+    DbHandle,
+};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId, traits::Actor};
+use actix_session::Session;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, PgPool};
+use std::str::FromStr;
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("Report: Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Report: Forbidden: {0}")]
+    Forbidden(String),
+    #[error("Report: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Report: NotFound")]
+    NotFound,
+    #[error("Report: TooManyRequests: {0}")]
+    TooManyRequests(String),
+    #[error("Report: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+impl From<UserValidateError> for ReportError {
+    fn from(e: UserValidateError) -> Self {
+        match e {
+            UserValidateError::Unauthorized(e) => ReportError::Unauthorized(e),
+            UserValidateError::Forbidden(e) => ReportError::Forbidden(e),
+            e => ReportError::InternalServerError(e.to_string()),
+        }
+    }
+}
+
+/// Longest reason a report can have, in characters.
+pub const MAX_REASON_LENGTH: usize = 1000;
+/// Reports the actors of one remote domain can file within a day. Further
+/// `Flag`s are dropped.
+const REMOTE_DAILY_LIMIT: i64 = 20;
+
+pub struct NewReport {
+    pub target: String,
+    pub kind: ReportTarget,
+    pub reason: String,
+    /// Also send a `Flag` to the instance the target lives on.
+    pub forward: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub id: Uuid,
+    pub reporter: String,
+    pub target: String,
+    pub target_kind: ReportTarget,
+    pub reason: String,
+    pub remote: bool,
+    pub forwarded: bool,
+    pub status: ReportStatus,
+    pub note: Option<String>,
+    pub resolved_by: Option<String>,
+    pub published: DateTime<Utc>,
+    pub resolved: Option<DateTime<Utc>>,
+}
+
+/// Finds the inbox of the actor responsible for `target`, or `None` when the
+/// target is local.
+async fn origin_inbox(
+    target: Url,
+    kind: &ReportTarget,
+    data: &Data<DbHandle>,
+) -> anyhow::Result<Option<Url>> {
+    let novel = match kind {
+        ReportTarget::User => {
+            let user = ObjectId::<User>::from(target).dereference(data).await?;
+            return Ok(user.private_key_pem().is_none().then(|| user.inbox()));
+        }
+        ReportTarget::Novel => ObjectId::<DbNovel>::from(target),
+        ReportTarget::Chapter => {
+            let chapter = ObjectId::<Chapter>::from(target).dereference(data).await?;
+            ObjectId::<DbNovel>::from(chapter.audience.parse::<Url>()?)
+        }
+    };
+    let novel = novel.dereference(data).await?;
+    Ok(novel.private_key_pem().is_none().then(|| novel.inbox()))
+}
+
+/// Files a report about `info.target` as `reporter`. Reports about remote
+/// content are forwarded to its instance when `info.forward` is set.
+///
+/// Looking up a remote target counts as a remote lookup of `reporter`, see
+/// [`throttle::resolve`].
+pub async fn create_report(
+    data: &Data<DbHandle>,
+    redis: &RedisHandle,
+    reporter: &str,
+    scheme: &str,
+    info: NewReport,
+) -> Result<Uuid, ReportError> {
+    let target: Url = info
+        .target
+        .parse()
+        .map_err(|e: url::ParseError| ReportError::BadRequest(e.to_string()))?;
+    let reason = info.reason.trim().to_string();
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(ReportError::BadRequest(format!(
+            "Reason must be at most {MAX_REASON_LENGTH} characters"
+        )));
+    }
+
+    if !is_local_url(&target, data.domain())
+        && !throttle::resolve(redis, reporter)
+            .await
+            .map_err(ReportError::InternalServerError)?
+    {
+        return Err(ReportError::TooManyRequests(
+            "Too many lookups on other servers, try again later".to_string(),
+        ));
+    }
+
+    let inbox = origin_inbox(target.clone(), &info.kind, data)
+        .await
+        .map_err(|_| ReportError::NotFound)?;
+    let forward = info.forward && inbox.is_some();
+
+    let id = query!(
+        r#"INSERT INTO reports (reporter, target, target_kind, reason, forwarded)
+           VALUES ($1, $2, $3, $4, $5)
+           RETURNING id"#,
+        reporter,
+        target.to_string(),
+        info.kind.to_string(),
+        reason,
+        forward
+    )
+    .fetch_one(data.app_data().as_ref())
+    .await
+    .map_err(|e| ReportError::InternalServerError(e.to_string()))?
+    .id;
+
+    if let (true, Some(inbox)) = (forward, inbox) {
+        // The report is already in our own queue, so a failed delivery is
+        // only logged. It also shows up in the admin dashboard.
+        if let Err(e) = Flag::send(target, reason, inbox, scheme, data).await {
+            log::warn!("Failed to forward report {id}: {e}");
+        }
+    }
+
+    Ok(id)
+}
+
+/// Queues a report received in a `Flag` if `target` is local content. The
+/// reason is cut to [`MAX_REASON_LENGTH`]. Nothing is queued if an actor on
+/// the domain of `reporter` already has an open report about `target`, or if
+/// actors on that domain filed [`REMOTE_DAILY_LIMIT`] reports in the last
+/// day.
+pub(crate) async fn receive_remote(
+    pool: &PgPool,
+    reporter: &str,
+    target: &str,
+    reason: &str,
+) -> anyhow::Result<()> {
+    let recent = query!(
+        r#"SELECT
+             count(*) FILTER (WHERE published > now() - interval '1 day') AS "recent!",
+             count(*) FILTER (WHERE lower(target)=$2 AND status=$3) AS "open!"
+           FROM reports
+           WHERE remote
+             AND substring(lower(reporter) from '^[a-z]+://([^/:]+)')
+                 = substring($1 from '^[a-z]+://([^/:]+)')"#,
+        reporter.to_lowercase(),
+        target.to_lowercase(),
+        ReportStatus::Open.to_string()
+    )
+    .fetch_one(pool)
+    .await?;
+    if recent.open > 0 {
+        return Ok(());
+    }
+    if recent.recent >= REMOTE_DAILY_LIMIT {
+        log::warn!("Dropped a report from {reporter}, whose domain sent too many");
+        return Ok(());
+    }
+
+    let kind = query!(
+        r#"SELECT CASE
+             WHEN EXISTS(SELECT 1 FROM users
+                         WHERE lower(apub_id)=$1 AND private_key IS NOT NULL) THEN 'user'
+             WHEN EXISTS(SELECT 1 FROM novels
+                         WHERE lower(apub_id)=$1 AND private_key IS NOT NULL) THEN 'novel'
+             WHEN EXISTS(SELECT 1 FROM chapters c
+                         JOIN novels n ON lower(n.apub_id)=lower(c.audience)
+                         WHERE lower(c.apub_id)=$1 AND n.private_key IS NOT NULL) THEN 'chapter'
+           END AS kind"#,
+        target.to_lowercase()
+    )
+    .fetch_one(pool)
+    .await?
+    .kind;
+
+    if let Some(kind) = kind {
+        query!(
+            r#"INSERT INTO reports (reporter, target, target_kind, reason, remote)
+               VALUES ($1, $2, $3, $4, true)"#,
+            reporter,
+            target,
+            kind,
+            reason.chars().take(MAX_REASON_LENGTH).collect::<String>()
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Lists reports with `status` for moderators, oldest first.
+pub async fn list_reports(
+    pool: &PgPool,
+    session: &Session,
+    status: ReportStatus,
+) -> Result<Vec<Report>, ReportError> {
+    require_role(pool, session, InstanceRole::Moderator).await?;
+
+    query!(
+        r#"SELECT id, reporter, target, target_kind, reason, remote, forwarded, status,
+             note, resolved_by, published, resolved
+           FROM reports
+           WHERE status=$1
+           ORDER BY published"#,
+        status.to_string()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ReportError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        Ok(Report {
+            id: row.id,
+            reporter: row.reporter,
+            target: row.target,
+            target_kind: ReportTarget::from_str(&row.target_kind)
+                .map_err(|e| ReportError::InternalServerError(e.to_string()))?,
+            reason: row.reason,
+            remote: row.remote,
+            forwarded: row.forwarded,
+            status: ReportStatus::from_str(&row.status)
+                .map_err(|e| ReportError::InternalServerError(e.to_string()))?,
+            note: row.note,
+            resolved_by: row.resolved_by,
+            published: row.published,
+            resolved: row.resolved,
+        })
+    })
+    .collect()
+}
+
+/// Closes an open report as resolved or dismissed with an optional note for
+/// other moderators.
+pub async fn close_report(
+    pool: &PgPool,
+    session: &Session,
+    id: Uuid,
+    status: ReportStatus,
+    note: Option<String>,
+) -> Result<(), ReportError> {
+    let (moderator, _) = require_role(pool, session, InstanceRole::Moderator).await?;
+
+    if status == ReportStatus::Open {
+        return Err(ReportError::BadRequest(
+            "Reports can only be resolved or dismissed".to_string(),
+        ));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ReportError::InternalServerError(e.to_string()))?;
+
+    let note = note.filter(|n| !n.trim().is_empty());
+    let target = query!(
+        r#"UPDATE reports
+           SET status=$2, note=$3, resolved_by=$4, resolved=now()
+           WHERE id=$1 AND status='open'
+           RETURNING target"#,
+        id,
+        status.to_string(),
+        note,
+        moderator
+    )
+    .fetch_optional(&mut tx)
+    .await
+    .map_err(|e| ReportError::InternalServerError(e.to_string()))?
+    .ok_or(ReportError::NotFound)?
+    .target;
+
+    let action = if status == ReportStatus::Resolved {
+        ModAction::ResolveReport
+    } else {
+        ModAction::DismissReport
+    };
+    moderation_log::record(&mut tx, &moderator, action, &target, note.as_deref())
+        .await
+        .map_err(|e| ReportError::InternalServerError(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ReportError::InternalServerError(e.to_string()))
+}
@@ -0,0 +1,85 @@
+use crate::enums::ActorRestriction;
+use serde::Serialize;
+use sqlx::{query, Executor, PgPool, Postgres};
+
+/// Moderation restrictions in effect for an actor. Suspension of local users
+/// is stored with the account in `users.suspended_at` so that it also blocks
+/// sign in. Everything else lives in `actor_restrictions`.
+///
+/// Silenced actors, and actors on silenced domains, are left out of public
+/// listings by the `actor_hidden` and `authors_hidden` SQL functions.
+#[derive(Debug, Default, Serialize)]
+pub struct Restrictions {
+    pub suspended: bool,
+    pub silenced: bool,
+    pub force_sensitive: bool,
+}
+
+pub async fn for_actor(pool: &PgPool, apub_id: &str) -> anyhow::Result<Restrictions> {
+    let row = query!(
+        r#"SELECT
+             EXISTS(SELECT 1 FROM users
+                    WHERE lower(apub_id)=$1 AND suspended_at IS NOT NULL)
+               OR coalesce(r.suspended, false) AS "suspended!",
+             coalesce(r.silenced, false) AS "silenced!",
+             coalesce(r.force_sensitive, false) AS "force_sensitive!"
+           FROM (SELECT 1) AS one
+           LEFT JOIN actor_restrictions r ON r.apub_id=$1"#,
+        apub_id.to_lowercase()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Restrictions {
+        suspended: row.suspended,
+        silenced: row.silenced,
+        force_sensitive: row.force_sensitive,
+    })
+}
+
+pub async fn is_suspended(pool: &PgPool, apub_id: &str) -> anyhow::Result<bool> {
+    Ok(for_actor(pool, apub_id).await?.suspended)
+}
+
+/// Whether novels by any of `authors` have to be marked as sensitive.
+pub async fn any_force_sensitive(pool: &PgPool, authors: &[String]) -> anyhow::Result<bool> {
+    Ok(query!(
+        r#"SELECT EXISTS(
+             SELECT 1 FROM actor_restrictions
+             WHERE force_sensitive AND apub_id=ANY($1)
+           ) AS "forced!""#,
+        &authors.iter().map(|a| a.to_lowercase()).collect::<Vec<_>>()
+    )
+    .fetch_one(pool)
+    .await?
+    .forced)
+}
+
+/// Turns `restriction` on or off for `apub_id`, leaving the others as they
+/// are.
+pub(crate) async fn set<'e, E>(
+    executor: E,
+    apub_id: &str,
+    restriction: &ActorRestriction,
+    enabled: bool,
+) -> sqlx::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    query!(
+        r#"INSERT INTO actor_restrictions (apub_id, suspended, silenced, force_sensitive)
+           VALUES ($1, $2 AND $3='suspend', $2 AND $3='silence', $2 AND $3='force_sensitive')
+           ON CONFLICT (apub_id) DO UPDATE SET
+             suspended = CASE WHEN $3='suspend' THEN $2 ELSE actor_restrictions.suspended END,
+             silenced = CASE WHEN $3='silence' THEN $2 ELSE actor_restrictions.silenced END,
+             force_sensitive = CASE WHEN $3='force_sensitive' THEN $2
+                                    ELSE actor_restrictions.force_sensitive END,
+             updated = now()"#,
+        apub_id.to_lowercase(),
+        enabled,
+        restriction.to_string()
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
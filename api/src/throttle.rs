@@ -0,0 +1,140 @@
+use crate::util::RedisHandle;
+use actix_redis::{resp_array, Command, RespValue};
+use std::time::Duration;
+
+/// Counters are forgotten this many seconds after they start.
+const WINDOW: i64 = 15 * 60;
+/// Failed attempts on one account before each new attempt is delayed.
+const DELAY_AFTER: i64 = 3;
+/// Longest delay between attempts, in seconds.
+const MAX_DELAY: i64 = 16;
+/// Failed attempts on one account before it is locked.
+const LOCKOUT_AFTER: i64 = 10;
+/// How long a locked account stays locked, in seconds.
+pub const LOCKOUT: i64 = 30 * 60;
+/// Attempts from one IP address across all accounts, not counting
+/// successful ones, before it is refused until the window expires.
+const IP_LIMIT: i64 = 50;
+/// Remote lookups one user may start within the window.
+const RESOLVE_LIMIT: i64 = 30;
+
+pub enum Throttle {
+    /// The attempt may proceed after waiting this long.
+    Allowed(Duration),
+    /// The attempt is refused for this many seconds.
+    Locked(i64),
+}
+
+fn account_key(email: &str) -> String {
+    format!("login:failures:account:{}", email.to_lowercase())
+}
+
+fn lock_key(email: &str) -> String {
+    format!("login:lock:{}", email.to_lowercase())
+}
+
+fn ip_key(ip: &str) -> String {
+    format!("login:failures:ip:{ip}")
+}
+
+fn resolve_key(user: &str) -> String {
+    format!("resolve:{}", user.to_lowercase())
+}
+
+async fn command(redis: &RedisHandle, cmd: RespValue) -> Result<RespValue, String> {
+    match redis.send(Command(cmd)).await {
+        Err(e) => Err(e.to_string()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Ok(Ok(RespValue::Error(e))) => Err(e),
+        Ok(Ok(v)) => Ok(v),
+    }
+}
+
+async fn integer(redis: &RedisHandle, cmd: RespValue) -> Result<i64, String> {
+    match command(redis, cmd).await? {
+        RespValue::Nil => Ok(0),
+        RespValue::Integer(i) => Ok(i),
+        RespValue::BulkString(v) => String::from_utf8_lossy(&v)
+            .parse()
+            .map_err(|e: std::num::ParseIntError| e.to_string()),
+        v => Err(format!("Unexpected redis response: {v:?}")),
+    }
+}
+
+/// Increments a counter and starts its expiry window on the first increment,
+/// in one step so that a counter can't be left without one.
+const INCREMENT: &str = "local n = redis.call('INCR', KEYS[1]) \
+     if n == 1 then redis.call('EXPIRE', KEYS[1], ARGV[1]) end \
+     return n";
+
+async fn increment(redis: &RedisHandle, key: &str) -> Result<i64, String> {
+    integer(
+        redis,
+        resp_array!["EVAL", INCREMENT, "1", key, WINDOW.to_string()],
+    )
+    .await
+}
+
+/// Checks whether a login attempt for `email` from `ip` may proceed, and
+/// counts it. Attempts are counted before the password is checked, so that
+/// parallel guesses can't get past the limits; [`success`] takes a
+/// successful one back off.
+pub async fn check(redis: &RedisHandle, email: &str, ip: Option<&str>) -> Result<Throttle, String> {
+    let locked = integer(redis, resp_array!["TTL", lock_key(email)]).await?;
+    if locked > 0 {
+        return Ok(Throttle::Locked(locked));
+    }
+
+    if let Some(ip) = ip {
+        if increment(redis, &ip_key(ip)).await? > IP_LIMIT {
+            let ttl = integer(redis, resp_array!["TTL", ip_key(ip)]).await?;
+            return Ok(Throttle::Locked(ttl.max(1)));
+        }
+    }
+
+    // Attempts past the lockout limit are refused even while the one that
+    // reached it is still being checked.
+    let attempt = increment(redis, &account_key(email)).await?;
+    if attempt > LOCKOUT_AFTER {
+        return Ok(Throttle::Locked(LOCKOUT));
+    }
+    let failures = attempt - 1;
+    let delay = if failures >= DELAY_AFTER {
+        (1 << (failures - DELAY_AFTER).min(4)).min(MAX_DELAY)
+    } else {
+        0
+    };
+    Ok(Throttle::Allowed(Duration::from_secs(delay as u64)))
+}
+
+/// Records that an attempt let through by [`check`] failed. Returns `true`
+/// if it locked the account, which happens only once per lockout.
+pub async fn failure(redis: &RedisHandle, email: &str) -> Result<bool, String> {
+    if integer(redis, resp_array!["GET", account_key(email)]).await? < LOCKOUT_AFTER {
+        return Ok(false);
+    }
+
+    let set = command(
+        redis,
+        resp_array!["SET", lock_key(email), "1", "EX", LOCKOUT.to_string(), "NX"],
+    )
+    .await?;
+    command(redis, resp_array!["DEL", account_key(email)]).await?;
+    Ok(!matches!(set, RespValue::Nil))
+}
+
+/// Clears the failure count of `email` after a successful login, and takes
+/// the attempt back off the count of `ip`.
+pub async fn success(redis: &RedisHandle, email: &str, ip: Option<&str>) -> Result<(), String> {
+    command(redis, resp_array!["DEL", account_key(email)]).await?;
+    if let Some(ip) = ip {
+        command(redis, resp_array!["DECR", ip_key(ip)]).await?;
+    }
+    Ok(())
+}
+
+/// Records a lookup of a remote object started by `user`. Returns `false`
+/// once they started too many within the window.
+pub async fn resolve(redis: &RedisHandle, user: &str) -> Result<bool, String> {
+    Ok(increment(redis, &resolve_key(user)).await? <= RESOLVE_LIMIT)
+}
@@ -0,0 +1,270 @@
+use crate::{
+    activities::{
+        block::Block,
+        undo::{Undo, UndoObject},
+    },
+    domain_policy::{self, normalize_domain},
+    enums::FilterKind,
+    objects::person::User,
+    DbHandle,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    traits::{Actor, Object},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, PgPool};
+use std::str::FromStr;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Debug, Error)]
+pub enum FilterError {
+    #[error("Filter: BadRequest: {0}")]
+    BadRequest(String),
+    #[error("Filter: NotFound")]
+    NotFound,
+    #[error("Filter: InternalServerError: {0}")]
+    InternalServerError(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserFilter {
+    /// An actor id, or a domain when `domain` is set.
+    pub target: String,
+    pub kind: FilterKind,
+    pub domain: bool,
+    pub published: DateTime<Utc>,
+}
+
+/// Finds the user behind a local username, a handle like `user@example.com`
+/// or an actor URL.
+async fn resolve_user(target: &str, data: &Data<DbHandle>) -> Result<User, FilterError> {
+    let target = target.trim().trim_start_matches('@');
+    let user = match target.parse::<Url>() {
+        Ok(url) => ObjectId::<User>::from(url).dereference(data).await.ok(),
+        Err(_) if target.contains('@') => domain_policy::resolve_actor::<User>(target, data)
+            .await
+            .ok(),
+        Err(_) => User::read_from_username(target, data.app_data())
+            .await
+            .map_err(|e| FilterError::InternalServerError(e.to_string()))?,
+    };
+    user.ok_or(FilterError::NotFound)
+}
+
+async fn local_user(owner: &str, data: &Data<DbHandle>) -> Result<User, FilterError> {
+    User::read_from_id(
+        owner
+            .parse()
+            .map_err(|e: url::ParseError| FilterError::InternalServerError(e.to_string()))?,
+        data,
+    )
+    .await
+    .map_err(|e| FilterError::InternalServerError(e.to_string()))?
+    .ok_or_else(|| FilterError::InternalServerError("Local user not found".to_string()))
+}
+
+/// Blocks or mutes `target` for `owner`. `target` is a domain when `domain`
+/// is set and a user otherwise.
+///
+/// Blocking drops the follows between the two in both directions. Blocks of
+/// remote users are federated with a `Block` activity so that their instance
+/// can hide `owner`'s content from them too.
+pub async fn add_filter(
+    data: &Data<DbHandle>,
+    owner: &str,
+    kind: FilterKind,
+    target: &str,
+    domain: bool,
+) -> Result<(), FilterError> {
+    let owner = owner.to_lowercase();
+    let (target, user) = if domain {
+        let target = normalize_domain(target)
+            .ok_or_else(|| FilterError::BadRequest("Invalid domain".to_string()))?;
+        if target == data.domain().split(':').next().unwrap_or(data.domain()) {
+            return Err(FilterError::BadRequest(
+                "You can't block or mute your own instance".to_string(),
+            ));
+        }
+        (target, None)
+    } else {
+        let user = resolve_user(target, data).await?;
+        (user.apub_id.to_lowercase(), Some(user))
+    };
+    if target == owner {
+        return Err(FilterError::BadRequest(
+            "You can't block or mute yourself".to_string(),
+        ));
+    }
+
+    let mut tx = data
+        .app_data()
+        .begin()
+        .await
+        .map_err(|e| FilterError::InternalServerError(e.to_string()))?;
+
+    let inserted = query!(
+        r#"INSERT INTO user_filters (owner, target, kind, domain)
+           VALUES ($1, $2, $3, $4)
+           ON CONFLICT DO NOTHING"#,
+        owner,
+        target,
+        kind.to_string(),
+        domain
+    )
+    .execute(&mut tx)
+    .await
+    .map_err(|e| FilterError::InternalServerError(e.to_string()))?
+    .rows_affected()
+        > 0;
+
+    if kind == FilterKind::Block {
+        query!(
+            r#"DELETE FROM follows
+               WHERE (lower(follower)=$1 AND (lower(following)=$2
+                      OR ($3 AND substring(lower(following) from '^[a-z]+://([^/:]+)') = $2)
+                      OR ($3 AND right(substring(lower(following) from '^[a-z]+://([^/:]+)'),
+                                       length($2) + 1) = '.' || $2)))
+                  OR (lower(following)=$1 AND (lower(follower)=$2
+                      OR ($3 AND substring(lower(follower) from '^[a-z]+://([^/:]+)') = $2)
+                      OR ($3 AND right(substring(lower(follower) from '^[a-z]+://([^/:]+)'),
+                                       length($2) + 1) = '.' || $2)))"#,
+            owner,
+            target,
+            domain
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| FilterError::InternalServerError(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| FilterError::InternalServerError(e.to_string()))?;
+
+    if let (true, FilterKind::Block, Some(user)) = (inserted, kind, user) {
+        if user.private_key_pem().is_none() {
+            let blocker = local_user(&owner, data).await?;
+            // The block already applies locally, so a failed delivery is only
+            // logged. It also shows up in the admin dashboard.
+            match Block::send(&blocker, &user, data).await {
+                Ok(id) => {
+                    query!(
+                        r#"UPDATE user_filters SET activity_id=$3
+                           WHERE owner=$1 AND target=$2 AND kind='block'"#,
+                        owner,
+                        target,
+                        id.to_string()
+                    )
+                    .execute(data.app_data().as_ref())
+                    .await
+                    .map_err(|e| FilterError::InternalServerError(e.to_string()))?;
+                }
+                Err(e) => log::warn!("Failed to send block of {target}: {e}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lifts a block or mute. Lifting a federated block sends an `Undo`.
+pub async fn remove_filter(
+    data: &Data<DbHandle>,
+    owner: &str,
+    kind: FilterKind,
+    target: &str,
+) -> Result<(), FilterError> {
+    let owner = owner.to_lowercase();
+    let removed = query!(
+        r#"DELETE FROM user_filters
+           WHERE owner=$1 AND target=$2 AND kind=$3
+           RETURNING activity_id"#,
+        owner,
+        target.trim().to_lowercase(),
+        kind.to_string()
+    )
+    .fetch_optional(data.app_data().as_ref())
+    .await
+    .map_err(|e| FilterError::InternalServerError(e.to_string()))?
+    .ok_or(FilterError::NotFound)?;
+
+    if let Some(id) = removed.activity_id {
+        let blocker = local_user(&owner, data).await?;
+        let undo = async {
+            let id: Url = id.parse()?;
+            let user = ObjectId::<User>::from(target.trim().parse::<Url>()?)
+                .dereference(data)
+                .await?;
+            let block = Block::new(&blocker, &user, id)?;
+            Undo::send(&blocker, UndoObject::Block(block), user.inbox(), data).await
+        };
+        if let Err(e) = undo.await {
+            log::warn!("Failed to send undo of block of {target}: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists the blocks or mutes of `owner`, newest first.
+pub async fn list_filters(
+    pool: &PgPool,
+    owner: &str,
+    kind: FilterKind,
+) -> Result<Vec<UserFilter>, FilterError> {
+    query!(
+        r#"SELECT target, kind, domain, published FROM user_filters
+           WHERE owner=$1 AND kind=$2
+           ORDER BY published DESC"#,
+        owner.to_lowercase(),
+        kind.to_string()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FilterError::InternalServerError(e.to_string()))?
+    .into_iter()
+    .map(|row| {
+        Ok(UserFilter {
+            target: row.target,
+            kind: FilterKind::from_str(&row.kind)
+                .map_err(|e| FilterError::InternalServerError(e.to_string()))?,
+            domain: row.domain,
+            published: row.published,
+        })
+    })
+    .collect()
+}
+
+async fn matches(
+    pool: &PgPool,
+    owner: &str,
+    actor: &Url,
+    kinds: &[FilterKind],
+) -> anyhow::Result<bool> {
+    Ok(query!(
+        r#"SELECT EXISTS(
+             SELECT 1 FROM user_filters
+             WHERE owner=$1 AND kind=ANY($2)
+               AND ((NOT domain AND target=$3)
+                    OR (domain AND ($4=target OR right($4, length(target) + 1)='.' || target)))
+           ) AS "matches!""#,
+        owner.to_lowercase(),
+        &kinds.iter().map(FilterKind::to_string).collect::<Vec<_>>(),
+        actor.to_string().to_lowercase(),
+        actor.host_str().unwrap_or_default().to_lowercase()
+    )
+    .fetch_one(pool)
+    .await?
+    .matches)
+}
+
+/// Whether `owner` blocked `actor` or its domain. Blocked actors can't
+/// follow `owner` or their novels, and can't be restored as co-authors of
+/// them.
+pub async fn is_blocked(pool: &PgPool, owner: &str, actor: &Url) -> anyhow::Result<bool> {
+    matches(pool, owner, actor, &[FilterKind::Block]).await
+}
@@ -1,5 +1,12 @@
+use actix::Addr;
+use actix_redis::RedisActor;
+use argon2::{Algorithm, Argon2, Params, Version};
+use isolang::Language;
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::net::IpAddr;
+use url::Url;
 
 lazy_static! {
     pub static ref USERNAME_RE: Regex =
@@ -10,4 +17,70 @@ lazy_static! {
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub scheme: Box<str>,
+    /// Answer failed logins with a generic error instead of saying whether
+    /// the email or the password was wrong.
+    pub uniform_login_errors: bool,
+    /// Argon2 cost parameters for new password hashes.
+    pub argon2_params: Params,
+    /// Reverse proxies whose `Forwarded` and `X-Forwarded-For` headers are
+    /// trusted for the client's IP address.
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl AppState {
+    /// Password hasher using the instance's Argon2 parameters.
+    pub fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(
+            Algorithm::Argon2id,
+            Version::V0x13,
+            self.argon2_params.clone(),
+        )
+    }
+}
+
+pub type RedisHandle = Addr<RedisActor>;
+
+/// The name an actor goes by in local paths like `/user/{name}`. Remote
+/// actors get `name@host`, with the port if there is one.
+pub fn local_handle(name: &str, apub_id: &str, local: bool) -> Result<String, url::ParseError> {
+    if local {
+        return Ok(name.to_string());
+    }
+    let url: Url = apub_id.parse()?;
+    let host = url.host_str().unwrap_or_default();
+    Ok(match url.port() {
+        Some(port) => format!("{name}@{host}:{port}"),
+        None => format!("{name}@{host}"),
+    })
+}
+
+/// Whether `url` is on this instance, whose domain is `domain` with the port
+/// if there is one.
+pub fn is_local_url(url: &Url, domain: &str) -> bool {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    }
+    .eq_ignore_ascii_case(domain)
+}
+
+/// Picks the tags out of `text`, sorted and without case-insensitive
+/// duplicates.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    TAG_RE
+        .find_iter(text)
+        .map(|t| t.as_str().to_string())
+        .unique_by(|t| t.to_lowercase())
+        .sorted()
+        .collect_vec()
+}
+
+/// The ISO 639-1 code of a language given by code or English name.
+pub fn language_code(language: &str) -> Option<String> {
+    let language = language.trim();
+    Language::from_639_1(&language.to_lowercase())
+        .or_else(|| Language::from_name(language))
+        .and_then(|l| l.to_639_1())
+        .map(str::to_string)
 }
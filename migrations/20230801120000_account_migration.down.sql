@@ -0,0 +1,5 @@
+drop table follows;
+
+alter table users
+drop column also_known_as,
+drop column moved_to;
@@ -0,0 +1,11 @@
+alter table users
+add column also_known_as text[] not null default '{}',
+add column moved_to text default null;
+
+create table
+  follows (
+    follower text not null,
+    following text not null,
+    published timestamptz not null default now(),
+    primary key (follower, following)
+  );
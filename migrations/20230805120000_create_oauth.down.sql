@@ -0,0 +1,5 @@
+drop table oauth_apps;
+
+drop table oauth_codes;
+
+drop table access_tokens;
@@ -0,0 +1,31 @@
+create table
+  oauth_apps (
+    client_id uuid primary key,
+    client_secret text not null,
+    name text not null,
+    website text,
+    redirect_uris text[] not null,
+    scopes text[] not null,
+    published timestamptz not null default now()
+  );
+
+create table
+  oauth_codes (
+    code text primary key,
+    client_id uuid not null,
+    apub_id text not null,
+    redirect_uri text not null,
+    scopes text[] not null,
+    code_challenge text not null,
+    expires timestamptz not null
+  );
+
+create table
+  access_tokens (
+    token text primary key,
+    apub_id text not null,
+    client_id uuid,
+    scopes text[] not null,
+    published timestamptz not null default now(),
+    last_used timestamptz default null
+  );
@@ -0,0 +1,7 @@
+delete from access_tokens
+where
+  client_id is null;
+
+alter table access_tokens
+drop column id,
+drop column name;
@@ -0,0 +1,3 @@
+alter table access_tokens
+add column id uuid unique not null default gen_random_uuid(),
+add column name text default null;
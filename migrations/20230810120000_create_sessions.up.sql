@@ -0,0 +1,13 @@
+create table
+  sessions (
+    id uuid primary key default gen_random_uuid(),
+    apub_id text not null,
+    client_app text not null,
+    client_website text,
+    user_agent text,
+    ip text,
+    published timestamptz not null default now(),
+    last_seen timestamptz not null default now()
+  );
+
+create index sessions_apub_id on sessions (lower(apub_id));
@@ -0,0 +1,8 @@
+alter table users
+drop column approved,
+drop column reason,
+drop column invited_by;
+
+drop table invites;
+
+drop table instance_settings;
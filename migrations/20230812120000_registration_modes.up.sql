@@ -0,0 +1,24 @@
+create table
+  instance_settings (
+    id boolean primary key default true check (id),
+    registration_mode text not null default 'open'
+  );
+
+insert into
+  instance_settings default
+values;
+
+create table
+  invites (
+    code text primary key,
+    creator text not null,
+    max_uses integer default null,
+    uses integer not null default 0,
+    expires timestamptz default null,
+    published timestamptz not null default now()
+  );
+
+alter table users
+add column approved boolean not null default true,
+add column reason text default null,
+add column invited_by text default null;
@@ -0,0 +1,19 @@
+alter table users
+add column role text not null default 'user';
+
+update users
+set
+  role = 'owner'
+where
+  apub_id = (
+    select
+      apub_id
+    from
+      users
+    where
+      private_key is not null
+    order by
+      published
+    limit
+      1
+  );
@@ -0,0 +1,7 @@
+drop table deliveries;
+
+alter table novels
+drop column hidden;
+
+alter table users
+drop column suspended_at;
@@ -0,0 +1,19 @@
+alter table users
+add column suspended_at timestamptz default null;
+
+alter table novels
+add column hidden boolean not null default false;
+
+create table
+  deliveries (
+    id uuid primary key default gen_random_uuid(),
+    activity_id text not null,
+    kind text not null,
+    actor text not null,
+    inbox text not null,
+    status text not null default 'queued',
+    error text,
+    published timestamptz not null default now()
+  );
+
+create index deliveries_published on deliveries (published);
@@ -0,0 +1,17 @@
+create table
+  reports (
+    id uuid primary key default gen_random_uuid(),
+    reporter text not null,
+    target text not null,
+    target_kind text not null,
+    reason text not null default '',
+    remote boolean not null default false,
+    forwarded boolean not null default false,
+    status text not null default 'open',
+    note text default null,
+    resolved_by text default null,
+    published timestamptz not null default now(),
+    resolved timestamptz default null
+  );
+
+create index reports_status on reports (status, published);
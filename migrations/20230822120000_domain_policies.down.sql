@@ -0,0 +1,4 @@
+alter table instance_settings
+drop column allowlist_mode;
+
+drop table domain_policies;
@@ -0,0 +1,10 @@
+create table
+  domain_policies (
+    domain text primary key,
+    policy text not null,
+    reason text default null,
+    published timestamptz not null default now()
+  );
+
+alter table instance_settings
+add column allowlist_mode boolean not null default false;
@@ -0,0 +1,12 @@
+create table
+  user_filters (
+    owner text not null,
+    target text not null,
+    kind text not null,
+    domain boolean not null default false,
+    activity_id text default null,
+    published timestamptz not null default now(),
+    primary key (owner, target, kind)
+  );
+
+create index user_filters_target on user_filters (target);
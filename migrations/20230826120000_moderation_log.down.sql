@@ -0,0 +1 @@
+drop table moderation_log;
@@ -0,0 +1,11 @@
+create table
+  moderation_log (
+    id uuid primary key default gen_random_uuid(),
+    actor text not null,
+    action text not null,
+    target text not null,
+    reason text default null,
+    published timestamptz not null default now()
+  );
+
+create index moderation_log_published on moderation_log (published);
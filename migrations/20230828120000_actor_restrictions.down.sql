@@ -0,0 +1 @@
+drop table actor_restrictions;
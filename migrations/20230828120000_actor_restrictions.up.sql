@@ -0,0 +1,8 @@
+create table
+  actor_restrictions (
+    apub_id text primary key,
+    suspended boolean not null default false,
+    silenced boolean not null default false,
+    force_sensitive boolean not null default false,
+    updated timestamptz not null default now()
+  );
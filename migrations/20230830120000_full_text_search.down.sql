@@ -0,0 +1,18 @@
+drop trigger novels_language on novels;
+
+drop function novels_language_update;
+
+drop trigger chapters_search on chapters;
+
+drop function chapters_search_update;
+
+alter table chapters
+drop column search;
+
+alter table users
+drop column search;
+
+alter table novels
+drop column search;
+
+drop function lang_regconfig;
@@ -0,0 +1,100 @@
+-- Maps the ISO 639-1 code stored with a novel to a text search configuration.
+-- Languages without a stemmer fall back to 'simple'.
+create function lang_regconfig (lang text) returns regconfig as $$
+  select case lang
+    when 'ar' then 'arabic'
+    when 'ca' then 'catalan'
+    when 'da' then 'danish'
+    when 'de' then 'german'
+    when 'el' then 'greek'
+    when 'en' then 'english'
+    when 'es' then 'spanish'
+    when 'eu' then 'basque'
+    when 'fi' then 'finnish'
+    when 'fr' then 'french'
+    when 'ga' then 'irish'
+    when 'hi' then 'hindi'
+    when 'hu' then 'hungarian'
+    when 'hy' then 'armenian'
+    when 'id' then 'indonesian'
+    when 'it' then 'italian'
+    when 'lt' then 'lithuanian'
+    when 'ne' then 'nepali'
+    when 'nl' then 'dutch'
+    when 'no' then 'norwegian'
+    when 'nb' then 'norwegian'
+    when 'pt' then 'portuguese'
+    when 'ro' then 'romanian'
+    when 'ru' then 'russian'
+    when 'sr' then 'serbian'
+    when 'sv' then 'swedish'
+    when 'ta' then 'tamil'
+    when 'tr' then 'turkish'
+    when 'yi' then 'yiddish'
+    else 'simple'
+  end::regconfig
+$$ language sql immutable;
+
+alter table novels
+add column search tsvector generated always as (
+  setweight(to_tsvector(lang_regconfig (language), title), 'A') ||
+  setweight(to_tsvector('simple', array_to_string(tags, ' ')), 'A') ||
+  setweight(to_tsvector(lang_regconfig (language), summary), 'B')
+) stored;
+
+create index novels_search on novels using gin (search);
+
+alter table users
+add column search tsvector generated always as (
+  setweight(to_tsvector('simple', preferred_username), 'A') ||
+  setweight(to_tsvector('simple', name), 'A') ||
+  setweight(to_tsvector('simple', summary), 'C')
+) stored;
+
+create index users_search on users using gin (search);
+
+-- Chapters take their language from the novel, so the vector is kept up to
+-- date by a trigger instead of a generated column.
+alter table chapters
+add column search tsvector not null default '';
+
+create function chapters_search_update () returns trigger as $$
+declare
+  config regconfig;
+begin
+  select lang_regconfig (language) into config
+  from novels where lower(apub_id) = lower(new.audience);
+  config := coalesce(config, 'simple');
+  new.search :=
+    setweight(to_tsvector(config, new.title), 'A') ||
+    setweight(to_tsvector(config, new.summary), 'B') ||
+    setweight(to_tsvector(config, new.content), 'C');
+  return new;
+end
+$$ language plpgsql;
+
+create trigger chapters_search before insert
+or
+update of title,
+summary,
+content,
+audience on chapters for each row
+execute function chapters_search_update ();
+
+-- Re-stem the chapters of a novel when its language changes.
+create function novels_language_update () returns trigger as $$
+begin
+  update chapters set title = title
+  where lower(audience) = lower(new.apub_id);
+  return new;
+end
+$$ language plpgsql;
+
+create trigger novels_language after
+update of language on novels for each row
+when (old.language is distinct from new.language)
+execute function novels_language_update ();
+
+update chapters set title = title;
+
+create index chapters_search on chapters using gin (search);
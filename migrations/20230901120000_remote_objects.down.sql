@@ -0,0 +1,38 @@
+delete from chapters
+where
+  audience in (
+    select
+      apub_id
+    from
+      novels
+    where
+      private_key is null
+  );
+
+delete from author_roles
+where
+  id in (
+    select
+      apub_id
+    from
+      novels
+    where
+      private_key is null
+  );
+
+delete from novels
+where
+  private_key is null;
+
+delete from users
+where
+  private_key is null;
+
+drop index users_local_username;
+
+alter table users
+add constraint users_preferred_username_key unique (preferred_username),
+alter column email
+set not null,
+alter column password
+set not null;
@@ -0,0 +1,13 @@
+-- Remote users are stored alongside local ones. They have no credentials and
+-- their usernames only need to be unique per instance, which the apub_id
+-- already covers.
+alter table users
+alter column email
+drop not null,
+alter column password
+drop not null,
+drop constraint users_preferred_username_key;
+
+create unique index users_local_username on users (preferred_username)
+where
+  private_key is not null;
@@ -0,0 +1,7 @@
+drop function authors_hidden;
+
+drop function actor_hidden;
+
+drop index novels_lower_tags;
+
+drop function lower_tags;
@@ -0,0 +1,34 @@
+-- Tags are matched case-insensitively, so they are indexed lowercased.
+create function lower_tags (tags text[]) returns text[] as $$
+  select array(select lower(t) from unnest(tags) t)
+$$ language sql immutable;
+
+create index novels_lower_tags on novels using gin (lower_tags (tags));
+
+-- Whether an actor is suspended or silenced, directly or through its domain,
+-- and should be left out of public listings.
+create function actor_hidden (actor text) returns boolean as $$
+  select
+    exists(
+      select 1 from users
+      where lower(apub_id) = lower(actor) and suspended_at is not null
+    )
+    or exists(
+      select 1 from actor_restrictions
+      where apub_id = lower(actor) and (suspended or silenced)
+    )
+    or exists(
+      select 1 from domain_policies
+      where policy = 'silence'
+        and (substring(lower(actor) from '^[a-z]+://([^/:]+)') = domain
+             or substring(lower(actor) from '^[a-z]+://([^/:]+)') like '%.' || domain)
+    )
+$$ language sql stable;
+
+-- Whether any author of a novel is hidden from public listings.
+create function authors_hidden (novel text) returns boolean as $$
+  select exists(
+    select 1 from author_roles
+    where lower(id) = lower(novel) and actor_hidden (author)
+  )
+$$ language sql stable;
@@ -0,0 +1,5 @@
+drop index novels_genre_language;
+
+drop index follows_following;
+
+drop index chapters_audience;
@@ -0,0 +1,5 @@
+create index chapters_audience on chapters (lower(audience));
+
+create index follows_following on follows (lower(following));
+
+create index novels_genre_language on novels (genre, language);
@@ -0,0 +1,14 @@
+drop function authors_muted_by;
+
+drop function muted_by;
+
+drop index novels_published;
+
+drop index chapters_published;
+
+drop table announces;
+
+drop index follows_follower;
+
+alter table follows
+drop column activity_id;
@@ -0,0 +1,41 @@
+-- Follows of remote actors are sent as activities, so that they can be
+-- withdrawn with an `Undo` of the same activity.
+alter table follows
+add column activity_id text default null;
+
+create index follows_follower on follows (lower(follower));
+
+-- Boosts of novels and chapters by the actors local users follow.
+create table
+  announces (
+    id text primary key,
+    actor text not null,
+    object text not null,
+    published timestamptz not null default now()
+  );
+
+create index announces_actor on announces (lower(actor));
+
+create index chapters_published on chapters (published);
+
+create index novels_published on novels (published);
+
+-- Whether a local user blocked or muted an actor or its domain, and doesn't
+-- want to see it in their timelines.
+create function muted_by (owner text, actor text) returns boolean as $$
+  select exists(
+    select 1 from user_filters
+    where user_filters.owner = lower($1) and kind in ('block', 'mute')
+      and ((not domain and target = lower(actor))
+           or (domain and (substring(lower(actor) from '^[a-z]+://([^/:]+)') = target
+                           or substring(lower(actor) from '^[a-z]+://([^/:]+)') like '%.' || target)))
+  )
+$$ language sql stable;
+
+-- Whether a local user blocked or muted any author of a novel.
+create function authors_muted_by (owner text, novel text) returns boolean as $$
+  select exists(
+    select 1 from author_roles
+    where lower(id) = lower(novel) and muted_by (owner, author)
+  )
+$$ language sql stable;
@@ -0,0 +1,7 @@
+drop function trending_novels;
+
+drop index announces_published;
+
+drop index follows_published;
+
+drop table trending_reviews;
@@ -0,0 +1,50 @@
+-- Novels and tags only trend publicly once a moderator approved them.
+create table
+  trending_reviews (
+    kind text not null,
+    -- Lowercased novel apub_id or tag.
+    target text not null,
+    approved boolean not null,
+    reviewer text not null,
+    published timestamptz not null default now(),
+    primary key (kind, target)
+  );
+
+create index follows_published on follows (published);
+
+create index announces_published on announces (published);
+
+-- How much novels are trending: follows of a novel and boosts of it or its
+-- chapters over the last week, each counting half as much every two days.
+-- Boosts count once per actor and novel, and only the `cap` best scoring
+-- novels of each author are kept. Hidden and sensitive novels and those of
+-- hidden authors are left out.
+create function trending_novels (cap bigint) returns table (novel text, score double precision) as $$
+  with signals as (
+      select lower(following) as novel, 1.0 as weight, published
+      from follows
+      where published > now() - interval '7 days'
+    union all
+      select lower(coalesce(c.audience, a.object)), 2.0, max(a.published)
+      from announces a
+      left join chapters c on lower(c.apub_id) = lower(a.object)
+      where a.published > now() - interval '7 days'
+      group by lower(coalesce(c.audience, a.object)), lower(a.actor)
+  ),
+  scores as (
+    select lower(n.apub_id) as novel,
+      sum(s.weight * power(0.5, extract(epoch from now() - s.published) / 172800))::double precision as score
+    from signals s
+    join novels n on lower(n.apub_id) = s.novel
+    where not n.hidden and not n.sensitive and not authors_hidden(n.apub_id)
+    group by lower(n.apub_id)
+  ),
+  ranks as (
+    select s.novel,
+      row_number() over (partition by lower(a.author) order by s.score desc, s.novel) as rank
+    from scores s
+    join author_roles a on lower(a.id) = s.novel
+  )
+  select s.novel, s.score from scores s
+  where not exists(select 1 from ranks r where r.novel = s.novel and r.rank > cap)
+$$ language sql stable;
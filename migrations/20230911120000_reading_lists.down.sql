@@ -0,0 +1,3 @@
+drop table shelf_items;
+
+drop table shelves;
@@ -0,0 +1,27 @@
+-- Reading lists of local users. Every user has one shelf of each built-in
+-- kind, created when first needed, and any number of custom ones.
+create table
+  shelves (
+    id uuid primary key default gen_random_uuid(),
+    owner text not null,
+    kind text not null,
+    name text not null,
+    public boolean not null default false,
+    published timestamptz not null default now()
+  );
+
+create unique index shelves_owner_kind on shelves (lower(owner), kind)
+where
+  kind <> 'custom';
+
+create unique index shelves_owner_name on shelves (lower(owner), lower(name));
+
+create table
+  shelf_items (
+    shelf uuid not null references shelves (id) on delete cascade,
+    novel text not null,
+    published timestamptz not null default now(),
+    primary key (shelf, novel)
+  );
+
+create index shelf_items_novel on shelf_items (lower(novel));
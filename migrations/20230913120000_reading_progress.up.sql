@@ -0,0 +1,13 @@
+-- How far local users got in each novel, both stored lowercased. Chapters
+-- before `sequence` are read, and so is the one at `sequence` once
+-- `position` reaches 1.
+create table
+  reading_progress (
+    reader text not null,
+    novel text not null,
+    sequence int not null,
+    -- How far the reader scrolled into the chapter at `sequence`.
+    position real not null default 0 check (position between 0 and 1),
+    updated timestamptz not null default now(),
+    primary key (reader, novel)
+  );
@@ -0,0 +1,20 @@
+-- Bookmarks and highlights of local users in chapters. Both point at a
+-- paragraph of the rendered chapter by its index, and keep the text they
+-- point at to find the paragraph again after the chapter was edited.
+create table
+  bookmarks (
+    id uuid primary key default gen_random_uuid(),
+    owner text not null,
+    chapter text not null,
+    kind text not null,
+    paragraph int not null,
+    -- The highlighted passage, or the start of the bookmarked paragraph.
+    quote text not null,
+    -- Only shown to the owner.
+    note text not null default '',
+    published timestamptz not null default now()
+  );
+
+create index bookmarks_owner on bookmarks (lower(owner), published);
+
+create index bookmarks_chapter on bookmarks (lower(chapter));
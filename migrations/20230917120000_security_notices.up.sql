@@ -0,0 +1,9 @@
+create table
+  security_notices (
+    id uuid primary key default gen_random_uuid(),
+    apub_id text not null,
+    message text not null,
+    published timestamptz not null default now()
+  );
+
+create index security_notices_apub_id on security_notices (lower(apub_id));
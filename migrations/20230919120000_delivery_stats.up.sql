@@ -0,0 +1,24 @@
+-- Deliveries are retried in the background by the federation library, which
+-- doesn't report how they end. Only what is known when an activity is handed
+-- to the queue is kept, for a week.
+drop table deliveries;
+
+create table
+  delivery_counts (
+    hour timestamptz primary key,
+    queued integer not null default 0,
+    failed integer not null default 0
+  );
+
+create table
+  delivery_failures (
+    id uuid primary key default gen_random_uuid(),
+    activity_id text not null,
+    kind text not null,
+    actor text not null,
+    inboxes integer not null,
+    error text not null,
+    published timestamptz not null default now()
+  );
+
+create index delivery_failures_published on delivery_failures (published);
@@ -0,0 +1,3 @@
+alter table instance_settings
+drop column private_key,
+drop column public_key;
@@ -0,0 +1,4 @@
+-- Keys of the instance actor, created when it is first used.
+alter table instance_settings
+add column public_key text default null,
+add column private_key text default null;
@@ -0,0 +1,12 @@
+-- Co-authors of imported novels. Only the importing user is attached to the
+-- new novel, the others are kept here so they can be invited again.
+create table
+  pending_authors (
+    novel text not null,
+    author text not null,
+    role text not null,
+    published timestamptz not null default now(),
+    primary key (novel, author)
+  );
+
+create index pending_authors_author on pending_authors (lower(author));
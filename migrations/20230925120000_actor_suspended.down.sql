@@ -0,0 +1,3 @@
+drop function authors_suspended;
+
+drop function actor_suspended;
@@ -0,0 +1,26 @@
+-- Whether an actor is suspended. Unlike `actor_hidden` this ignores
+-- silencing, which only keeps actors out of public listings.
+create function actor_suspended (actor text) returns boolean as $$
+  select
+    exists(
+      select 1 from users
+      where lower(apub_id) = lower(actor) and suspended_at is not null
+    )
+    or exists(
+      select 1 from actor_restrictions
+      where apub_id = lower(actor) and suspended
+    )
+$$ language sql stable;
+
+-- Whether every author of a novel is suspended, which takes the novel down.
+create function authors_suspended (novel text) returns boolean as $$
+  select
+    exists(
+      select 1 from author_roles
+      where lower(id) = lower(novel)
+    )
+    and not exists(
+      select 1 from author_roles
+      where lower(id) = lower(novel) and not actor_suspended (author)
+    )
+$$ language sql stable;
@@ -0,0 +1,17 @@
+create or replace function actor_hidden (actor text) returns boolean as $$
+  select
+    exists(
+      select 1 from users
+      where lower(apub_id) = lower(actor) and suspended_at is not null
+    )
+    or exists(
+      select 1 from actor_restrictions
+      where apub_id = lower(actor) and (suspended or silenced)
+    )
+    or exists(
+      select 1 from domain_policies
+      where policy = 'silence'
+        and (substring(lower(actor) from '^[a-z]+://([^/:]+)') = domain
+             or substring(lower(actor) from '^[a-z]+://([^/:]+)') like '%.' || domain)
+    )
+$$ language sql stable;
@@ -0,0 +1,20 @@
+-- Match subdomains by suffix instead of with `like`, where `_` and `%` in a
+-- domain would act as wildcards.
+create or replace function actor_hidden (actor text) returns boolean as $$
+  select
+    exists(
+      select 1 from users
+      where lower(apub_id) = lower(actor) and suspended_at is not null
+    )
+    or exists(
+      select 1 from actor_restrictions
+      where apub_id = lower(actor) and (suspended or silenced)
+    )
+    or exists(
+      select 1 from domain_policies
+      where policy = 'silence'
+        and (substring(lower(actor) from '^[a-z]+://([^/:]+)') = domain
+             or right(substring(lower(actor) from '^[a-z]+://([^/:]+)'), length(domain) + 1)
+                = '.' || domain)
+    )
+$$ language sql stable;
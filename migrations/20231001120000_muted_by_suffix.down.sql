@@ -0,0 +1,9 @@
+create or replace function muted_by (owner text, actor text) returns boolean as $$
+  select exists(
+    select 1 from user_filters
+    where user_filters.owner = lower($1) and kind in ('block', 'mute')
+      and ((not domain and target = lower(actor))
+           or (domain and (substring(lower(actor) from '^[a-z]+://([^/:]+)') = target
+                           or substring(lower(actor) from '^[a-z]+://([^/:]+)') like '%.' || target)))
+  )
+$$ language sql stable;
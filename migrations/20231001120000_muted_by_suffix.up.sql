@@ -0,0 +1,12 @@
+-- Match subdomains by suffix instead of with `like`, where `_` and `%` in a
+-- domain would act as wildcards.
+create or replace function muted_by (owner text, actor text) returns boolean as $$
+  select exists(
+    select 1 from user_filters
+    where user_filters.owner = lower($1) and kind in ('block', 'mute')
+      and ((not domain and target = lower(actor))
+           or (domain and (substring(lower(actor) from '^[a-z]+://([^/:]+)') = target
+                           or right(substring(lower(actor) from '^[a-z]+://([^/:]+)'),
+                                    length(target) + 1) = '.' || target)))
+  )
+$$ language sql stable;
@@ -0,0 +1,37 @@
+create index reading_progress_updated on reading_progress (updated);
+
+-- Like before, but readers who made progress in a novel over the last week
+-- count too, once per reader.
+create or replace function trending_novels (cap bigint) returns table (novel text, score double precision) as $$
+  with signals as (
+      select lower(following) as novel, 1.0 as weight, published
+      from follows
+      where published > now() - interval '7 days'
+    union all
+      select lower(coalesce(c.audience, a.object)), 2.0, max(a.published)
+      from announces a
+      left join chapters c on lower(c.apub_id) = lower(a.object)
+      where a.published > now() - interval '7 days'
+      group by lower(coalesce(c.audience, a.object)), lower(a.actor)
+    union all
+      select novel, 1.0, updated
+      from reading_progress
+      where updated > now() - interval '7 days'
+  ),
+  scores as (
+    select lower(n.apub_id) as novel,
+      sum(s.weight * power(0.5, extract(epoch from now() - s.published) / 172800))::double precision as score
+    from signals s
+    join novels n on lower(n.apub_id) = s.novel
+    where not n.hidden and not n.sensitive and not authors_hidden(n.apub_id)
+    group by lower(n.apub_id)
+  ),
+  ranks as (
+    select s.novel,
+      row_number() over (partition by lower(a.author) order by s.score desc, s.novel) as rank
+    from scores s
+    join author_roles a on lower(a.id) = s.novel
+  )
+  select s.novel, s.score from scores s
+  where not exists(select 1 from ranks r where r.novel = s.novel and r.rank > cap)
+$$ language sql stable;
@@ -0,0 +1,2 @@
+alter table bookmarks
+drop column paragraph_id;
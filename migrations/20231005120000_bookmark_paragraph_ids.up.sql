@@ -0,0 +1,4 @@
+-- The ID chapters render the paragraph with. Bookmarks made before there
+-- were IDs keep an empty one and are found by their text.
+alter table bookmarks
+add column paragraph_id text not null default '';
@@ -0,0 +1 @@
+drop index reports_reporter;
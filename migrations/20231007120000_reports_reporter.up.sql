@@ -0,0 +1,2 @@
+-- Remote reports are limited per reporter.
+create index reports_reporter on reports (lower(reporter)) where remote;
@@ -0,0 +1,3 @@
+drop index reports_reporter_domain;
+
+create index reports_reporter on reports (lower(reporter)) where remote;
@@ -0,0 +1,4 @@
+-- Remote reports are limited per reporter domain.
+drop index reports_reporter;
+
+create index reports_reporter_domain on reports (substring(lower(reporter) from '^[a-z]+://([^/:]+)')) where remote;
@@ -0,0 +1,3 @@
+-- Can't be reverted, since the converted policies can't be told apart from
+-- the ones that were allow already.
+select 1;
@@ -0,0 +1,7 @@
+-- Remote media isn't fetched, so reject_media federated like allow, which it
+-- becomes. Allowlist mode treated both as allowed too.
+update domain_policies
+set
+  policy = 'allow'
+where
+  policy = 'reject_media';
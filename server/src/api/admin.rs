@@ -0,0 +1,493 @@
+use super::report::report_error;
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{
+    delete,
+    error::{
+        ErrorBadRequest, ErrorForbidden, ErrorInternalServerError, ErrorNotFound, ErrorUnauthorized,
+    },
+    get, post, put, web, HttpResponse, Scope,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::str::FromStr;
+use uuid::Uuid;
+use wordforge_api::{
+    admin::{self, AdminError},
+    enums::{
+        ActorRestriction, DomainPolicyKind, InstanceRole, RegistrationMode, ReportStatus,
+        TrendingKind,
+    },
+    instance,
+    moderation_log::{self, LogFilter},
+    report, DbHandle,
+};
+
+fn admin_error(e: AdminError) -> actix_web::Error {
+    match e {
+        AdminError::Unauthorized(e) => ErrorUnauthorized(e),
+        AdminError::Forbidden(e) => ErrorForbidden(e),
+        AdminError::BadRequest(e) => ErrorBadRequest(e),
+        AdminError::NotFound => ErrorNotFound(json!({ "error": "Not found" })),
+        AdminError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+#[get("/staff")]
+async fn list_staff(data: Data<DbHandle>, session: Session) -> actix_web::Result<HttpResponse> {
+    let staff = admin::list_staff(data.app_data(), &session)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(staff))
+}
+
+#[derive(Deserialize)]
+struct RoleChange {
+    account: String,
+    role: String,
+    reason: Option<String>,
+}
+
+#[post("/role")]
+async fn set_role(
+    info: web::Json<RoleChange>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    let role = InstanceRole::from_str(&info.role).map_err(ErrorBadRequest)?;
+    admin::set_role(data.app_data(), &session, &info.account, role, info.reason)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/registrations")]
+async fn pending_users(data: Data<DbHandle>, session: Session) -> actix_web::Result<HttpResponse> {
+    admin::staff(data.app_data(), &session, InstanceRole::Moderator)
+        .await
+        .map_err(admin_error)?;
+    let users = instance::pending_users(data.app_data())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(users))
+}
+
+#[derive(Deserialize)]
+struct Registration {
+    account: String,
+    reason: Option<String>,
+}
+
+#[post("/registrations/approve")]
+async fn approve_user(
+    info: web::Json<Registration>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    admin::review_registration(data.app_data(), &session, &info.account, true, info.reason)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/registrations/reject")]
+async fn reject_user(
+    info: web::Json<Registration>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    admin::review_registration(data.app_data(), &session, &info.account, false, info.reason)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/settings")]
+async fn get_settings(data: Data<DbHandle>, session: Session) -> actix_web::Result<HttpResponse> {
+    admin::staff(data.app_data(), &session, InstanceRole::Admin)
+        .await
+        .map_err(admin_error)?;
+    let settings = instance::settings(data.app_data())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(json!({
+        "registration_mode": settings.registration_mode,
+        "allowlist_mode": settings.allowlist_mode,
+    })))
+}
+
+#[derive(Deserialize)]
+struct SettingsUpdate {
+    registration_mode: Option<RegistrationMode>,
+    allowlist_mode: Option<bool>,
+}
+
+#[put("/settings")]
+async fn update_settings(
+    info: web::Json<SettingsUpdate>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    if let Some(mode) = info.registration_mode {
+        admin::set_registration_mode(data.app_data(), &session, mode)
+            .await
+            .map_err(admin_error)?;
+    }
+    if let Some(enabled) = info.allowlist_mode {
+        admin::set_allowlist_mode(data.app_data(), &session, enabled)
+            .await
+            .map_err(admin_error)?;
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/stats")]
+async fn stats(data: Data<DbHandle>, session: Session) -> actix_web::Result<HttpResponse> {
+    let stats = admin::stats(data.app_data(), &session)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    limit: Option<i64>,
+}
+
+#[get("/users")]
+async fn recent_users(
+    query: web::Query<ListQuery>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let users = admin::recent_users(data.app_data(), &session, limit)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(users))
+}
+
+#[get("/novels")]
+async fn recent_novels(
+    query: web::Query<ListQuery>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let novels = admin::recent_novels(data.app_data(), &session, limit)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(novels))
+}
+
+#[get("/instances")]
+async fn known_instances(
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let instances = admin::known_instances(data.app_data(), &session)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(instances))
+}
+
+#[get("/deliveries")]
+async fn delivery_stats(data: Data<DbHandle>, session: Session) -> actix_web::Result<HttpResponse> {
+    let deliveries = admin::delivery_stats(data.app_data(), &session)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+#[derive(Deserialize)]
+struct Suspension {
+    account: String,
+    suspend: bool,
+    reason: Option<String>,
+}
+
+#[post("/suspend")]
+async fn suspend_user(
+    info: web::Json<Suspension>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    admin::suspend_user(
+        data.app_data(),
+        &session,
+        &info.account,
+        info.suspend,
+        info.reason,
+    )
+    .await
+    .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Deserialize)]
+struct Restriction {
+    account: String,
+    restriction: ActorRestriction,
+    enabled: bool,
+    reason: Option<String>,
+}
+
+#[post("/restrict")]
+async fn restrict_actor(
+    info: web::Json<Restriction>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    admin::restrict_actor(
+        data.app_data(),
+        &session,
+        &info.account,
+        info.restriction,
+        info.enabled,
+        info.reason,
+    )
+    .await
+    .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Deserialize)]
+struct Visibility {
+    novel: String,
+    hidden: bool,
+    reason: Option<String>,
+}
+
+#[post("/novels/hide")]
+async fn hide_novel(
+    info: web::Json<Visibility>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    admin::hide_novel(
+        data.app_data(),
+        &session,
+        &info.novel,
+        info.hidden,
+        info.reason,
+    )
+    .await
+    .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Deserialize)]
+struct ReportQuery {
+    status: Option<ReportStatus>,
+}
+
+#[get("/reports")]
+async fn list_reports(
+    query: web::Query<ReportQuery>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let status = query.into_inner().status.unwrap_or(ReportStatus::Open);
+    let reports = report::list_reports(data.app_data(), &session, status)
+        .await
+        .map_err(report_error)?;
+    Ok(HttpResponse::Ok().json(reports))
+}
+
+#[derive(Deserialize)]
+struct ReportNote {
+    note: Option<String>,
+}
+
+#[post("/reports/{id}/resolve")]
+async fn resolve_report(
+    path: web::Path<Uuid>,
+    info: web::Json<ReportNote>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    report::close_report(
+        data.app_data(),
+        &session,
+        path.into_inner(),
+        ReportStatus::Resolved,
+        info.into_inner().note,
+    )
+    .await
+    .map_err(report_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/reports/{id}/dismiss")]
+async fn dismiss_report(
+    path: web::Path<Uuid>,
+    info: web::Json<ReportNote>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    report::close_report(
+        data.app_data(),
+        &session,
+        path.into_inner(),
+        ReportStatus::Dismissed,
+        info.into_inner().note,
+    )
+    .await
+    .map_err(report_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/domains")]
+async fn domain_policies(
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let policies = admin::domain_policies(data.app_data(), &session)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(policies))
+}
+
+#[derive(Deserialize)]
+struct PolicyUpdate {
+    domain: String,
+    policy: DomainPolicyKind,
+    reason: Option<String>,
+}
+
+#[put("/domains")]
+async fn set_domain_policy(
+    info: web::Json<PolicyUpdate>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    admin::set_domain_policy(
+        data.app_data(),
+        &session,
+        &info.domain,
+        info.policy,
+        info.reason,
+    )
+    .await
+    .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/domains/{domain}")]
+async fn remove_domain_policy(
+    path: web::Path<String>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    admin::remove_domain_policy(data.app_data(), &session, &path)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/log")]
+async fn moderation_log(
+    query: web::Query<LogFilter>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let entries = admin::moderation_log(data.app_data(), &session, query.into_inner(), false)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+#[get("/log/export")]
+async fn export_moderation_log(
+    query: web::Query<LogFilter>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let entries = admin::moderation_log(data.app_data(), &session, query.into_inner(), true)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .append_header((
+            "Content-Disposition",
+            "attachment; filename=\"moderation-log.csv\"",
+        ))
+        .body(moderation_log::to_csv(&entries)))
+}
+
+#[get("/trending")]
+async fn trending_candidates(
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let candidates = admin::trending_candidates(data.app_data(), &session)
+        .await
+        .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().json(candidates))
+}
+
+#[derive(Deserialize)]
+struct TrendingReview {
+    kind: TrendingKind,
+    target: String,
+    approved: bool,
+    reason: Option<String>,
+}
+
+#[post("/trending/review")]
+async fn review_trending(
+    info: web::Json<TrendingReview>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    admin::review_trending(
+        data.app_data(),
+        &session,
+        info.kind,
+        &info.target,
+        info.approved,
+        info.reason,
+    )
+    .await
+    .map_err(admin_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Staff-only endpoints. Each handler checks the role it requires.
+pub fn scope() -> Scope {
+    web::scope("/admin")
+        .service(list_staff)
+        .service(set_role)
+        .service(pending_users)
+        .service(approve_user)
+        .service(reject_user)
+        .service(get_settings)
+        .service(update_settings)
+        .service(stats)
+        .service(recent_users)
+        .service(recent_novels)
+        .service(hide_novel)
+        .service(known_instances)
+        .service(delivery_stats)
+        .service(suspend_user)
+        .service(restrict_actor)
+        .service(list_reports)
+        .service(resolve_report)
+        .service(dismiss_report)
+        .service(domain_policies)
+        .service(set_domain_policy)
+        .service(remove_domain_policy)
+        .service(moderation_log)
+        .service(export_moderation_log)
+        .service(trending_candidates)
+        .service(review_trending)
+}
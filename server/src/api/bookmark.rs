@@ -0,0 +1,105 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{
+    delete,
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    get, post, put, web, HttpRequest, HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use wordforge_api::{
+    api::bookmark::{self, BookmarkError, NewBookmark},
+    enums::Scopes,
+    DbHandle,
+};
+
+fn bookmark_error(e: BookmarkError) -> actix_web::Error {
+    match e {
+        BookmarkError::BadRequest(e) => ErrorBadRequest(e),
+        BookmarkError::NotFound => ErrorNotFound(json!({ "error": "Not found" })),
+        BookmarkError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+/// Every bookmark and highlight of the signed in user.
+#[get("/account/bookmarks")]
+async fn list_bookmarks(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    let bookmarks = bookmark::list_bookmarks(data.app_data(), &owner)
+        .await
+        .map_err(bookmark_error)?;
+    Ok(HttpResponse::Ok().json(bookmarks))
+}
+
+/// A chapter, by its URL.
+#[derive(Deserialize)]
+struct BookmarkChapter {
+    chapter: String,
+}
+
+#[get("/account/bookmarks/chapter")]
+async fn chapter_bookmarks(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Query<BookmarkChapter>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    let bookmarks = bookmark::chapter_bookmarks(data.app_data(), &owner, &info.chapter)
+        .await
+        .map_err(bookmark_error)?;
+    Ok(HttpResponse::Ok().json(bookmarks))
+}
+
+#[post("/account/bookmarks")]
+async fn add_bookmark(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Json<NewBookmark>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    let id = bookmark::add_bookmark(data.app_data(), &owner, info.into_inner())
+        .await
+        .map_err(bookmark_error)?;
+    Ok(HttpResponse::Created().json(json!({ "id": id })))
+}
+
+#[derive(Deserialize)]
+struct BookmarkNote {
+    note: String,
+}
+
+#[put("/account/bookmarks/{id}")]
+async fn update_bookmark(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    id: web::Path<Uuid>,
+    info: web::Json<BookmarkNote>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    bookmark::set_note(data.app_data(), &owner, *id, &info.note)
+        .await
+        .map_err(bookmark_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/account/bookmarks/{id}")]
+async fn delete_bookmark(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    id: web::Path<Uuid>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    bookmark::delete_bookmark(data.app_data(), &owner, *id)
+        .await
+        .map_err(bookmark_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
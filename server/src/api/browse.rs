@@ -0,0 +1,22 @@
+use activitypub_federation::config::Data;
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    get, web, HttpResponse,
+};
+use wordforge_api::{
+    api::browse::{self, BrowseError, BrowseQuery},
+    DbHandle,
+};
+
+/// Takes the fields of `BrowseQuery` as query parameters.
+#[get("/browse")]
+async fn browse(
+    query: web::Query<BrowseQuery>,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    match browse::browse(data.app_data(), query.into_inner()).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(page)),
+        Err(BrowseError::BadRequest(e)) => Err(ErrorBadRequest(e)),
+        Err(BrowseError::InternalServerError(e)) => Err(ErrorInternalServerError(e)),
+    }
+}
@@ -0,0 +1,70 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{
+    delete,
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    get, post, web, HttpRequest, HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use wordforge_api::{
+    api::follow::{self, FollowError},
+    enums::Scopes,
+    DbHandle,
+};
+
+fn follow_error(e: FollowError) -> actix_web::Error {
+    match e {
+        FollowError::BadRequest(e) => ErrorBadRequest(e),
+        FollowError::NotFound => ErrorNotFound(json!({ "error": "Not found" })),
+        FollowError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+/// A user or novel, by its actor URL.
+#[derive(Deserialize)]
+struct FollowTarget {
+    target: String,
+}
+
+#[get("/account/follows")]
+async fn is_following(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Query<FollowTarget>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    let following = follow::is_following(data.app_data(), &owner, &info.target)
+        .await
+        .map_err(follow_error)?;
+    Ok(HttpResponse::Ok().json(json!({ "following": following })))
+}
+
+#[post("/account/follows")]
+async fn add_follow(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Json<FollowTarget>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Follow).await?;
+    follow::follow(&data, &owner, &info.target)
+        .await
+        .map_err(follow_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/account/follows")]
+async fn remove_follow(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Query<FollowTarget>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Follow).await?;
+    follow::unfollow(&data, &owner, &info.target)
+        .await
+        .map_err(follow_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
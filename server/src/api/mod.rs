@@ -1,11 +1,158 @@
-use activitypub_federation::FEDERATION_CONTENT_TYPE;
-use actix_web::{guard, web, Route, Scope};
+use activitypub_federation::{config::Data, FEDERATION_CONTENT_TYPE};
+use actix_session::Session;
+use actix_web::{
+    error::{ErrorBadRequest, ErrorForbidden, ErrorInternalServerError, ErrorUnauthorized},
+    guard,
+    http::header::AUTHORIZATION,
+    web, HttpRequest, HttpResponse, Route, Scope,
+};
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+use wordforge_api::{
+    account::{self, UserValidateError},
+    enums::Scopes,
+    oauth::{self as api_oauth, OAuthError},
+    restriction, DbHandle,
+};
 
+pub mod admin;
+pub mod bookmark;
+pub mod browse;
+pub mod follow;
 pub mod novel;
+pub mod oauth;
+pub mod progress;
+pub mod report;
+pub mod search;
+pub mod shelf;
+pub mod tag;
+pub mod timeline;
+pub mod trending;
 pub mod user;
 
+/// Resolves the user acting on a request, either from an `Authorization:
+/// Bearer` token granting `scope` or from the session cookie.
+pub async fn authenticate(
+    request: &HttpRequest,
+    session: &Session,
+    data: &Data<DbHandle>,
+    scope: Scopes,
+) -> actix_web::Result<String> {
+    let bearer = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    match bearer {
+        Some(token) => api_oauth::authenticate(data.app_data(), token.trim(), scope)
+            .await
+            .map_err(|e| match e {
+                OAuthError::Forbidden(e) => ErrorForbidden(e),
+                OAuthError::InternalServerError(e) => ErrorInternalServerError(e),
+                e => ErrorUnauthorized(e.to_string()),
+            }),
+        None => session_user(session, data).await,
+    }
+}
+
+/// Resolves the user signed in with the session cookie.
+pub async fn session_user(session: &Session, data: &Data<DbHandle>) -> actix_web::Result<String> {
+    account::current_user(data.app_data(), session)
+        .await
+        .map_err(|e| match e {
+            UserValidateError::Unauthorized(e) => ErrorUnauthorized(e),
+            e => ErrorInternalServerError(e.to_string()),
+        })
+}
+
+#[derive(Deserialize)]
+struct ActivityActor {
+    actor: Url,
+}
+
+/// Refuses an activity received in an inbox if its actor is suspended. The
+/// URL verifier only sees the activity id and actors that still have to be
+/// fetched, so this has to run before the activity is handled.
+pub async fn reject_suspended(payload: &[u8], data: &Data<DbHandle>) -> actix_web::Result<()> {
+    let activity: ActivityActor = serde_json::from_slice(payload).map_err(ErrorBadRequest)?;
+    if restriction::is_suspended(data.app_data(), activity.actor.as_str())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        return Err(ErrorForbidden("Actor is suspended"));
+    }
+    Ok(())
+}
+
+/// Answers requests for a suspended actor or its content with `410 Gone` and
+/// a `Tombstone`, so that other instances drop their copies.
+pub fn tombstone(id: &str) -> HttpResponse {
+    HttpResponse::Gone()
+        .content_type(FEDERATION_CONTENT_TYPE)
+        .json(json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": id,
+            "type": "Tombstone",
+        }))
+}
+
 pub fn scope() -> Scope {
-    web::scope("/api/v1").service(novel::new_novel)
+    web::scope("/api/v1")
+        .service(oauth::register_app)
+        .service(oauth::list_tokens)
+        .service(oauth::create_token)
+        .service(oauth::revoke_token)
+        .service(novel::new_novel)
+        .service(novel::add_chapter)
+        .service(report::new_report)
+        .service(search::search)
+        .service(search::resolve)
+        .service(tag::novels_with_tag)
+        .service(browse::browse)
+        .service(timeline::home)
+        .service(timeline::public)
+        .service(trending::trending)
+        .service(follow::is_following)
+        .service(follow::add_follow)
+        .service(follow::remove_follow)
+        .service(shelf::list_shelves)
+        .service(shelf::create_shelf)
+        .service(shelf::update_shelf)
+        .service(shelf::delete_shelf)
+        .service(shelf::add_novel)
+        .service(shelf::remove_novel)
+        .service(shelf::get_shelf)
+        .service(progress::unread_novels)
+        .service(progress::novel_progress)
+        .service(progress::set_position)
+        .service(progress::mark_read)
+        .service(progress::mark_unread)
+        .service(bookmark::list_bookmarks)
+        .service(bookmark::chapter_bookmarks)
+        .service(bookmark::add_bookmark)
+        .service(bookmark::update_bookmark)
+        .service(bookmark::delete_bookmark)
+        .service(user::add_alias)
+        .service(user::move_account)
+        .service(user::change_password)
+        .service(user::list_blocks)
+        .service(user::add_block)
+        .service(user::remove_block)
+        .service(user::list_mutes)
+        .service(user::add_mute)
+        .service(user::remove_mute)
+        .service(admin::scope())
+        .service(user::export_account)
+        .service(
+            web::resource("/account/import")
+                .app_data(web::JsonConfig::default().limit(64 * 1024 * 1024))
+                .route(web::post().to(user::import_account)),
+        )
+        .service(user::pending_authors)
+        .service(user::restore_author)
+        .service(user::dismiss_author)
 }
 
 pub fn users() -> Route {
@@ -1,13 +1,12 @@
 use activitypub_federation::{
-    actix_web::inbox::receive_activity,
-    config::Data,
-    fetch::webfinger::webfinger_resolve_actor,
-    protocol::context::WithContext,
-    traits::{Actor, Collection},
+    actix_web::inbox::receive_activity, config::Data, protocol::context::WithContext,
+    traits::Collection,
 };
 use actix_session::Session;
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound, ErrorUnauthorized},
+    error::{
+        ErrorBadRequest, ErrorForbidden, ErrorInternalServerError, ErrorNotFound, ErrorUnauthorized,
+    },
     get, post,
     web::{self, Bytes},
     HttpRequest, HttpResponse,
@@ -16,8 +15,12 @@ use serde_json::json;
 use url::Url;
 use uuid::Uuid;
 use wordforge_api::{
-    activities::{self, add::NewChapter},
-    api::novel::{self, create_novel, CreateNovelError, GetNovelError, NewNovel},
+    activities::add::NewChapter,
+    api::{
+        chapter::{new_chapter, ChapterCreationError},
+        novel::{self, create_novel, CreateNovelError, GetNovelError, NewNovel},
+    },
+    enums::Scopes,
     objects::{
         chapter::ChapterList,
         novel::{DbNovel, NovelAcceptedActivities},
@@ -33,8 +36,10 @@ async fn new_novel(
     info: web::Json<NewNovel>,
     data: Data<DbHandle>,
     session: Session,
+    request: HttpRequest,
 ) -> actix_web::Result<HttpResponse> {
-    match create_novel(state, data, session, info.into_inner()).await {
+    let apub_id = super::authenticate(&request, &session, &data, Scopes::WriteNovels).await?;
+    match create_novel(state, data, apub_id, info.into_inner()).await {
         Ok(id) => Ok(HttpResponse::Ok().body(id)),
         Err(CreateNovelError::Unauthorized(e)) => Err(ErrorUnauthorized(e)),
         Err(CreateNovelError::BadRequest(e)) => Err(ErrorBadRequest(e)),
@@ -57,6 +62,7 @@ pub async fn get_novel(
         Err(GetNovelError::NovelNotFound) => {
             Err(ErrorNotFound(json!({ "error": "Novel not found" })))
         }
+        Err(GetNovelError::Gone(id)) => Ok(super::tombstone(&id)),
         Err(GetNovelError::InternalServerError(e)) => Err(ErrorInternalServerError(e)),
     }
 }
@@ -66,27 +72,33 @@ async fn add_chapter(
     path: web::Path<String>,
     info: web::Json<NewChapter>,
     session: Session,
+    request: HttpRequest,
     state: web::Data<AppState>,
     data: Data<DbHandle>,
 ) -> actix_web::Result<HttpResponse> {
-    let apub_id: Url = session
-        .get::<String>("id")?
-        .ok_or_else(|| ErrorUnauthorized("Not signed in"))?
+    let apub_id: Url = super::authenticate(&request, &session, &data, Scopes::WriteChapters)
+        .await?
         .parse()
         .map_err(ErrorInternalServerError)?;
-    session.renew();
-
-    let novel_id: DbNovel = webfinger_resolve_actor(&path, &data)
-        .await
-        .map_err(|_| ErrorNotFound("Novel not found"))?;
-    let novel_id = novel_id.inbox();
-    let activity_id =
-        activities::add::Add::send(info.into_inner(), apub_id, novel_id, &state.scheme, &data)
-            .await
-            .map_err(ErrorInternalServerError)?;
 
-    // TODO: Response with Chapter apub_id
-    Ok(HttpResponse::Ok().body(activity_id.to_string()))
+    match new_chapter(
+        path.into_inner(),
+        info.into_inner(),
+        apub_id,
+        &data,
+        &state.scheme,
+    )
+    .await
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(ChapterCreationError::Unauthorized) => {
+            Err(ErrorForbidden("Only authors can add chapters"))
+        }
+        Err(ChapterCreationError::NotFound) => {
+            Err(ErrorNotFound(json!({ "error": "Novel not found" })))
+        }
+        Err(ChapterCreationError::InternalError(e)) => Err(ErrorInternalServerError(e)),
+    }
 }
 
 #[post("/novel/{uuid}/inbox")]
@@ -95,6 +107,7 @@ async fn novel_inbox(
     request: HttpRequest,
     payload: Bytes,
 ) -> actix_web::Result<HttpResponse> {
+    super::reject_suspended(&payload, &data).await?;
     receive_activity::<WithContext<NovelAcceptedActivities>, User, DbHandle>(
         request, payload, &data,
     )
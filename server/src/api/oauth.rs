@@ -0,0 +1,130 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{delete, get, post, web, HttpResponse};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use wordforge_api::{
+    oauth::{self, OAuthError, TokenRequest},
+    DbHandle,
+};
+
+fn oauth_error(e: OAuthError) -> HttpResponse {
+    let body = json!({ "error": e.code(), "error_description": e.to_string() });
+    match e {
+        OAuthError::Unauthorized(_) | OAuthError::InvalidClient => {
+            HttpResponse::Unauthorized().json(body)
+        }
+        OAuthError::Forbidden(_) => HttpResponse::Forbidden().json(body),
+        OAuthError::InvalidGrant(_) | OAuthError::BadRequest(_) => {
+            HttpResponse::BadRequest().json(body)
+        }
+        OAuthError::InternalServerError(_) => HttpResponse::InternalServerError().json(body),
+    }
+}
+
+#[derive(Deserialize)]
+struct NewApp {
+    client_name: String,
+    redirect_uris: String,
+    scopes: Option<String>,
+    website: Option<String>,
+}
+
+#[post("/apps")]
+async fn register_app(info: web::Json<NewApp>, data: Data<DbHandle>) -> HttpResponse {
+    let info = info.into_inner();
+    match oauth::register_app(
+        data.app_data(),
+        info.client_name,
+        info.website,
+        info.redirect_uris,
+        info.scopes,
+    )
+    .await
+    {
+        Ok(app) => HttpResponse::Ok().json(app),
+        Err(e) => oauth_error(e),
+    }
+}
+
+#[post("/oauth/token")]
+async fn token(info: web::Form<TokenRequest>, data: Data<DbHandle>) -> HttpResponse {
+    match oauth::token(data.app_data(), info.into_inner()).await {
+        Ok(token) => HttpResponse::Ok().json(token),
+        Err(e) => oauth_error(e),
+    }
+}
+
+#[derive(Deserialize)]
+struct RevokeRequest {
+    client_id: Uuid,
+    client_secret: Option<String>,
+    token: String,
+}
+
+#[post("/oauth/revoke")]
+async fn revoke(info: web::Form<RevokeRequest>, data: Data<DbHandle>) -> HttpResponse {
+    let info = info.into_inner();
+    match oauth::revoke(
+        data.app_data(),
+        info.client_id,
+        info.client_secret,
+        info.token,
+    )
+    .await
+    {
+        Ok(_) => HttpResponse::Ok().json(json!({})),
+        Err(e) => oauth_error(e),
+    }
+}
+
+#[get("/tokens")]
+async fn list_tokens(data: Data<DbHandle>, session: Session) -> actix_web::Result<HttpResponse> {
+    let apub_id = super::session_user(&session, &data).await?;
+    Ok(match oauth::list_tokens(data.app_data(), &apub_id).await {
+        Ok(tokens) => HttpResponse::Ok().json(tokens),
+        Err(e) => oauth_error(e),
+    })
+}
+
+#[derive(Deserialize)]
+struct NewToken {
+    name: String,
+    scopes: String,
+}
+
+#[post("/tokens")]
+async fn create_token(
+    info: web::Json<NewToken>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let apub_id = super::session_user(&session, &data).await?;
+    let info = info.into_inner();
+    let scopes = match oauth::parse_scopes(&info.scopes) {
+        Ok(s) => s,
+        Err(e) => return Ok(oauth_error(e)),
+    };
+    Ok(
+        match oauth::create_personal_token(data.app_data(), &apub_id, info.name, scopes).await {
+            Ok(token) => HttpResponse::Ok().json(json!({ "access_token": token })),
+            Err(e) => oauth_error(e),
+        },
+    )
+}
+
+#[delete("/tokens/{id}")]
+async fn revoke_token(
+    id: web::Path<Uuid>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let apub_id = super::session_user(&session, &data).await?;
+    Ok(
+        match oauth::revoke_token(data.app_data(), &apub_id, id.into_inner()).await {
+            Ok(_) => HttpResponse::Ok().finish(),
+            Err(e) => oauth_error(e),
+        },
+    )
+}
@@ -0,0 +1,110 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{
+    delete,
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    get, post, put, web, HttpRequest, HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use wordforge_api::{
+    api::progress::{self, ProgressError},
+    enums::Scopes,
+    DbHandle,
+};
+
+fn progress_error(e: ProgressError) -> actix_web::Error {
+    match e {
+        ProgressError::BadRequest(e) => ErrorBadRequest(e),
+        ProgressError::NotFound => ErrorNotFound(json!({ "error": "Not found" })),
+        ProgressError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+/// Followed novels with unread chapters and where to continue them.
+#[get("/account/progress")]
+async fn unread_novels(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let reader = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    let novels = progress::unread_following(data.app_data(), &reader)
+        .await
+        .map_err(progress_error)?;
+    Ok(HttpResponse::Ok().json(novels))
+}
+
+/// A novel, by its actor URL.
+#[derive(Deserialize)]
+struct NovelTarget {
+    novel: String,
+}
+
+#[get("/account/progress/novel")]
+async fn novel_progress(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Query<NovelTarget>,
+) -> actix_web::Result<HttpResponse> {
+    let reader = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    let progress = progress::get_progress(data.app_data(), &reader, &info.novel)
+        .await
+        .map_err(progress_error)?;
+    Ok(HttpResponse::Ok().json(progress))
+}
+
+#[derive(Deserialize)]
+struct PositionUpdate {
+    chapter: String,
+    position: f32,
+}
+
+#[put("/account/progress")]
+async fn set_position(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Json<PositionUpdate>,
+) -> actix_web::Result<HttpResponse> {
+    let reader = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    progress::set_position(data.app_data(), &reader, &info.chapter, info.position)
+        .await
+        .map_err(progress_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// A chapter, by its URL.
+#[derive(Deserialize)]
+struct ChapterTarget {
+    chapter: String,
+}
+
+#[post("/account/progress/read")]
+async fn mark_read(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Json<ChapterTarget>,
+) -> actix_web::Result<HttpResponse> {
+    let reader = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    progress::mark_read(data.app_data(), &reader, &info.chapter, true)
+        .await
+        .map_err(progress_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/account/progress/read")]
+async fn mark_unread(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Query<ChapterTarget>,
+) -> actix_web::Result<HttpResponse> {
+    let reader = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    progress::mark_read(data.app_data(), &reader, &info.chapter, false)
+        .await
+        .map_err(progress_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,66 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{
+    error::{
+        ErrorBadRequest, ErrorForbidden, ErrorInternalServerError, ErrorNotFound,
+        ErrorTooManyRequests, ErrorUnauthorized,
+    },
+    post, web, HttpRequest, HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use wordforge_api::{
+    enums::{ReportTarget, Scopes},
+    report::{self, NewReport, ReportError},
+    util::{AppState, RedisHandle},
+    DbHandle,
+};
+
+pub fn report_error(e: ReportError) -> actix_web::Error {
+    match e {
+        ReportError::Unauthorized(e) => ErrorUnauthorized(e),
+        ReportError::Forbidden(e) => ErrorForbidden(e),
+        ReportError::BadRequest(e) => ErrorBadRequest(e),
+        ReportError::NotFound => ErrorNotFound(json!({ "error": "Not found" })),
+        ReportError::TooManyRequests(e) => ErrorTooManyRequests(e),
+        ReportError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+#[derive(Deserialize)]
+struct ReportInfo {
+    target: String,
+    kind: ReportTarget,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    forward: bool,
+}
+
+#[post("/reports")]
+async fn new_report(
+    info: web::Json<ReportInfo>,
+    session: Session,
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    data: Data<DbHandle>,
+    redis: web::Data<RedisHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let reporter = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    let info = info.into_inner();
+    let id = report::create_report(
+        &data,
+        &redis,
+        &reporter,
+        &state.scheme,
+        NewReport {
+            target: info.target,
+            kind: info.kind,
+            reason: info.reason,
+            forward: info.forward,
+        },
+    )
+    .await
+    .map_err(report_error)?;
+    Ok(HttpResponse::Ok().json(json!({ "id": id })))
+}
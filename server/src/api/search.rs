@@ -0,0 +1,65 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{
+    error::{
+        ErrorBadRequest, ErrorInternalServerError, ErrorNotFound, ErrorTooManyRequests,
+        ErrorUnauthorized,
+    },
+    get, web, HttpRequest, HttpResponse,
+};
+use serde::Deserialize;
+use wordforge_api::{
+    api::search::{self, SearchError, SearchQuery},
+    enums::Scopes,
+    util::RedisHandle,
+    DbHandle,
+};
+
+fn search_error(e: SearchError) -> actix_web::Error {
+    match e {
+        SearchError::BadRequest(e) => ErrorBadRequest(e),
+        SearchError::Unauthorized(e) => ErrorUnauthorized(e),
+        SearchError::NotFound(e) => ErrorNotFound(e),
+        SearchError::TooManyRequests(e) => ErrorTooManyRequests(e),
+        SearchError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+/// Public full-text search. Takes the fields of `SearchQuery` as query
+/// parameters.
+#[get("/search")]
+async fn search(
+    query: web::Query<SearchQuery>,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let results = search::search(data.app_data(), query.into_inner())
+        .await
+        .map_err(search_error)?;
+    Ok(HttpResponse::Ok().json(results))
+}
+
+#[derive(Deserialize)]
+struct ResolveInfo {
+    q: String,
+}
+
+/// Looks up a novel or user from a handle or URL, fetching it if it is
+/// remote. Needs a signed in user, since it makes this instance fetch and
+/// store what the URL points at.
+#[get("/resolve")]
+async fn resolve(
+    request: HttpRequest,
+    session: Session,
+    info: web::Query<ResolveInfo>,
+    data: Data<DbHandle>,
+    redis: web::Data<RedisHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let user = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    match search::resolve(&info.q, &data, &redis, Some(&user))
+        .await
+        .map_err(search_error)?
+    {
+        Some(resolved) => Ok(HttpResponse::Ok().json(resolved)),
+        None => Err(ErrorBadRequest("Not a handle or URL")),
+    }
+}
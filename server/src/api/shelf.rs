@@ -0,0 +1,145 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{
+    delete,
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    get, post, put, web, HttpRequest, HttpResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use wordforge_api::{
+    api::shelf::{self, ShelfError},
+    enums::Scopes,
+    DbHandle,
+};
+
+fn shelf_error(e: ShelfError) -> actix_web::Error {
+    match e {
+        ShelfError::BadRequest(e) => ErrorBadRequest(e),
+        ShelfError::NotFound => ErrorNotFound(json!({ "error": "Not found" })),
+        ShelfError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+/// Every shelf of the signed in user, private ones included.
+#[get("/account/shelves")]
+async fn list_shelves(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    let shelves = shelf::list_shelves(data.app_data(), &owner, true)
+        .await
+        .map_err(shelf_error)?;
+    Ok(HttpResponse::Ok().json(shelves))
+}
+
+#[derive(Deserialize)]
+struct NewShelf {
+    name: String,
+    #[serde(default)]
+    public: bool,
+}
+
+#[post("/account/shelves")]
+async fn create_shelf(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Json<NewShelf>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    let id = shelf::create_shelf(data.app_data(), &owner, &info.name, info.public)
+        .await
+        .map_err(shelf_error)?;
+    Ok(HttpResponse::Created().json(json!({ "id": id })))
+}
+
+#[derive(Deserialize)]
+struct ShelfVisibility {
+    public: bool,
+}
+
+#[put("/account/shelves/{id}")]
+async fn update_shelf(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    id: web::Path<Uuid>,
+    info: web::Json<ShelfVisibility>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    shelf::set_public(data.app_data(), &owner, *id, info.public)
+        .await
+        .map_err(shelf_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/account/shelves/{id}")]
+async fn delete_shelf(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    id: web::Path<Uuid>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    shelf::delete_shelf(data.app_data(), &owner, *id)
+        .await
+        .map_err(shelf_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// A novel, by its URL.
+#[derive(Deserialize)]
+struct ShelfNovel {
+    novel: String,
+}
+
+#[post("/account/shelves/{id}/novels")]
+async fn add_novel(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    id: web::Path<Uuid>,
+    info: web::Json<ShelfNovel>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    shelf::add_novel(&data, &owner, *id, &info.novel)
+        .await
+        .map_err(shelf_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/account/shelves/{id}/novels")]
+async fn remove_novel(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    id: web::Path<Uuid>,
+    info: web::Query<ShelfNovel>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Write).await?;
+    shelf::remove_novel(data.app_data(), &owner, *id, &info.novel)
+        .await
+        .map_err(shelf_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// A public shelf, or a private one of the signed in user.
+#[get("/shelves/{id}")]
+async fn get_shelf(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    id: web::Path<Uuid>,
+) -> actix_web::Result<HttpResponse> {
+    let viewer = super::authenticate(&request, &session, &data, Scopes::Read)
+        .await
+        .ok();
+    let page = shelf::get_shelf(data.app_data(), viewer.as_deref(), *id)
+        .await
+        .map_err(shelf_error)?;
+    Ok(HttpResponse::Ok().json(page))
+}
@@ -0,0 +1,29 @@
+use activitypub_federation::config::Data;
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    get, web, HttpResponse,
+};
+use serde::Deserialize;
+use wordforge_api::{
+    api::tag::{self, TagError},
+    DbHandle,
+};
+
+#[derive(Deserialize)]
+struct TagQuery {
+    #[serde(default)]
+    page: i64,
+}
+
+#[get("/tags/{tag}")]
+async fn novels_with_tag(
+    path: web::Path<String>,
+    query: web::Query<TagQuery>,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    match tag::novels_with_tag(data.app_data(), &path, query.page).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(page)),
+        Err(TagError::BadRequest(e)) => Err(ErrorBadRequest(e)),
+        Err(TagError::InternalServerError(e)) => Err(ErrorInternalServerError(e)),
+    }
+}
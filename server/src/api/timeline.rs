@@ -0,0 +1,43 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{error::ErrorInternalServerError, get, web, HttpRequest, HttpResponse};
+use wordforge_api::{
+    api::timeline::{self, PublicTimelineQuery, TimelineError, TimelineQuery},
+    enums::Scopes,
+    DbHandle,
+};
+
+/// The home timeline of the signed in user. Takes the fields of
+/// `TimelineQuery` as query parameters.
+#[get("/timelines/home")]
+async fn home(
+    request: HttpRequest,
+    session: Session,
+    query: web::Query<TimelineQuery>,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    match timeline::home(data.app_data(), &owner, query.into_inner()).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(page)),
+        Err(TimelineError::InternalServerError(e)) => Err(ErrorInternalServerError(e)),
+    }
+}
+
+/// The local or federated public timeline. Takes the fields of
+/// `PublicTimelineQuery` as query parameters. Signed in users don't see the
+/// actors they blocked or muted.
+#[get("/timelines/public")]
+async fn public(
+    request: HttpRequest,
+    session: Session,
+    query: web::Query<PublicTimelineQuery>,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let viewer = super::authenticate(&request, &session, &data, Scopes::Read)
+        .await
+        .ok();
+    match timeline::public(data.app_data(), viewer.as_deref(), query.into_inner()).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(page)),
+        Err(TimelineError::InternalServerError(e)) => Err(ErrorInternalServerError(e)),
+    }
+}
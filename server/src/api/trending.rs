@@ -0,0 +1,25 @@
+use activitypub_federation::config::Data;
+use actix_session::Session;
+use actix_web::{error::ErrorInternalServerError, get, HttpRequest, HttpResponse};
+use wordforge_api::{
+    api::trending::{self, TrendingError},
+    enums::Scopes,
+    DbHandle,
+};
+
+/// The approved trending novels and tags. Signed in users don't see the
+/// actors they blocked or muted.
+#[get("/trending")]
+async fn trending(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let viewer = super::authenticate(&request, &session, &data, Scopes::Read)
+        .await
+        .ok();
+    match trending::trending(data.app_data(), viewer.as_deref()).await {
+        Ok(trending) => Ok(HttpResponse::Ok().json(trending)),
+        Err(TrendingError::InternalServerError(e)) => Err(ErrorInternalServerError(e)),
+    }
+}
@@ -1,16 +1,35 @@
 use activitypub_federation::{
+    actix_web::inbox::receive_activity,
     config::Data,
-    fetch::webfinger::{extract_webfinger_name, webfinger_resolve_actor},
+    fetch::webfinger::extract_webfinger_name,
     protocol::context::WithContext,
     traits::{Collection, Object},
 };
+use actix_session::Session;
 use actix_web::{
-    error::{ErrorInternalServerError, ErrorNotFound},
-    get, web, HttpResponse,
+    delete,
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound, ErrorUnauthorized},
+    get, post,
+    web::{self, Bytes},
+    HttpRequest, HttpResponse,
 };
+use serde::Deserialize;
 use serde_json::json;
+use uuid::Uuid;
 use wordforge_api::{
-    objects::{novel_list::NovelList, person::User},
+    account::{self, AccountMoveError, PasswordChangeError},
+    api::takeout::{self, Takeout, TakeoutError},
+    domain_policy,
+    enums::{FilterKind, Scopes},
+    objects::{
+        actor::DbActor,
+        novel_list::NovelList,
+        person::{User, UserAcceptedActivities},
+        shelf::Shelf,
+    },
+    restriction,
+    user_filter::{self, FilterError},
+    util::{AppState, RedisHandle},
     DbHandle,
 };
 
@@ -25,8 +44,9 @@ pub async fn get_user(
             .append_header(("Location", format!("/user/{name}")))
             .finish());
     }
-    let user = if path.contains('@') {
-        webfinger_resolve_actor(&path, &data)
+    let remote = path.contains('@');
+    let user: User = if remote {
+        domain_policy::resolve_actor(&path, &data)
             .await
             .map_err(|_| ErrorNotFound(json!({ "error": "User not found" })))?
     } else {
@@ -34,10 +54,20 @@ pub async fn get_user(
             .await
             .map_err(ErrorInternalServerError)?
             .ok_or_else(|| ErrorNotFound(json!({ "error": "User not found" })))?
+    };
+    if restriction::is_suspended(data.app_data(), &user.apub_id)
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        if remote {
+            return Err(ErrorNotFound(json!({ "error": "User not found" })));
+        }
+        return Ok(super::tombstone(&user.apub_id));
     }
-    .into_json(&data)
-    .await
-    .map_err(ErrorInternalServerError)?;
+    let user = user
+        .into_json(&data)
+        .await
+        .map_err(ErrorInternalServerError)?;
 
     let res = WithContext::new_default(user);
     Ok(HttpResponse::Ok().json(res))
@@ -52,9 +82,320 @@ async fn user_outbox(
         .await
         .map_err(ErrorInternalServerError)?
         .ok_or(ErrorNotFound(json!({"error": "User not found"})))?;
+    if restriction::is_suspended(data.app_data(), &owner.apub_id)
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        return Ok(super::tombstone(&owner.outbox));
+    }
     let chapters = NovelList::read_local(&owner, &data)
         .await
         .map_err(ErrorInternalServerError)?;
     let res = WithContext::new_default(chapters);
     Ok(HttpResponse::Ok().json(res))
 }
+
+/// A public reading list of a local user, as an `OrderedCollection`.
+#[get("/user/{name}/shelves/{id}")]
+async fn user_shelf(
+    path: web::Path<(String, Uuid)>,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let (name, id) = path.into_inner();
+    let owner = User::read_from_username(&name, &data)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or(ErrorNotFound(json!({"error": "User not found"})))?;
+    if restriction::is_suspended(data.app_data(), &owner.apub_id)
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        return Ok(super::tombstone(&format!("{}/shelves/{id}", owner.apub_id)));
+    }
+    let shelf = Shelf::read_local(&owner, id, &data)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or(ErrorNotFound(json!({"error": "Shelf not found"})))?;
+    let res = WithContext::new_default(shelf);
+    Ok(HttpResponse::Ok().json(res))
+}
+
+#[post("/user/{name}/inbox")]
+async fn user_inbox(
+    data: Data<DbHandle>,
+    request: HttpRequest,
+    payload: Bytes,
+) -> actix_web::Result<HttpResponse> {
+    super::reject_suspended(&payload, &data).await?;
+    receive_activity::<WithContext<UserAcceptedActivities>, DbActor, DbHandle>(
+        request, payload, &data,
+    )
+    .await
+    .map_err(ErrorInternalServerError)
+}
+
+#[derive(Deserialize)]
+struct AccountHandle {
+    account: String,
+}
+
+fn account_move_error(e: AccountMoveError) -> actix_web::Error {
+    match e {
+        AccountMoveError::Unauthorized(e) => ErrorUnauthorized(e),
+        AccountMoveError::BadRequest(e) => ErrorBadRequest(e),
+        AccountMoveError::NotFound => ErrorNotFound(json!({ "error": "User not found" })),
+        AccountMoveError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+#[post("/account/alias")]
+async fn add_alias(
+    info: web::Json<AccountHandle>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    account::add_alias(&data, session, info.into_inner().account)
+        .await
+        .map_err(account_move_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/account/move")]
+async fn move_account(
+    info: web::Json<AccountHandle>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    account::move_account(&data, session, info.into_inner().account)
+        .await
+        .map_err(account_move_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Deserialize)]
+struct PasswordChange {
+    current_password: String,
+    new_password: String,
+}
+
+#[post("/account/password")]
+async fn change_password(
+    state: web::Data<AppState>,
+    info: web::Json<PasswordChange>,
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let info = info.into_inner();
+    account::change_password(
+        data.app_data(),
+        &state,
+        &session,
+        info.current_password,
+        info.new_password,
+    )
+    .await
+    .map_err(|e| match e {
+        PasswordChangeError::Unauthorized(e) => ErrorUnauthorized(e),
+        PasswordChangeError::WrongPassword => ErrorUnauthorized("Wrong password"),
+        PasswordChangeError::BadRequest(e) => ErrorBadRequest(e),
+        PasswordChangeError::InternalServerError(e) => ErrorInternalServerError(e),
+    })?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+fn takeout_error(e: TakeoutError) -> actix_web::Error {
+    match e {
+        TakeoutError::Unauthorized(e) => ErrorUnauthorized(e),
+        TakeoutError::BadRequest(e) => ErrorBadRequest(e),
+        TakeoutError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+#[get("/account/export")]
+async fn export_account(data: Data<DbHandle>, session: Session) -> actix_web::Result<HttpResponse> {
+    let archive = takeout::export(&data, session)
+        .await
+        .map_err(takeout_error)?;
+    Ok(HttpResponse::Ok()
+        .append_header((
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"wordforge-{}.json\"",
+                archive.profile.preferred_username
+            ),
+        ))
+        .json(archive))
+}
+
+pub async fn import_account(
+    state: web::Data<AppState>,
+    info: web::Json<Takeout>,
+    data: Data<DbHandle>,
+    session: Session,
+    redis: web::Data<RedisHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let result = takeout::import(state, &data, &redis, session, info.into_inner())
+        .await
+        .map_err(takeout_error)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[derive(Deserialize)]
+struct PendingAuthorTarget {
+    novel: String,
+    author: String,
+}
+
+#[get("/account/pending_authors")]
+async fn pending_authors(
+    data: Data<DbHandle>,
+    session: Session,
+) -> actix_web::Result<HttpResponse> {
+    let pending = takeout::pending_authors(&data, session)
+        .await
+        .map_err(takeout_error)?;
+    Ok(HttpResponse::Ok().json(pending))
+}
+
+#[post("/account/pending_authors")]
+async fn restore_author(
+    data: Data<DbHandle>,
+    session: Session,
+    info: web::Json<PendingAuthorTarget>,
+) -> actix_web::Result<HttpResponse> {
+    takeout::restore_author(&data, session, &info.novel, &info.author)
+        .await
+        .map_err(takeout_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/account/pending_authors")]
+async fn dismiss_author(
+    data: Data<DbHandle>,
+    session: Session,
+    info: web::Query<PendingAuthorTarget>,
+) -> actix_web::Result<HttpResponse> {
+    takeout::dismiss_author(&data, session, &info.novel, &info.author)
+        .await
+        .map_err(takeout_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+fn filter_error(e: FilterError) -> actix_web::Error {
+    match e {
+        FilterError::BadRequest(e) => ErrorBadRequest(e),
+        FilterError::NotFound => ErrorNotFound(json!({ "error": "Not found" })),
+        FilterError::InternalServerError(e) => ErrorInternalServerError(e),
+    }
+}
+
+#[derive(Deserialize)]
+struct NewFilter {
+    target: String,
+    #[serde(default)]
+    domain: bool,
+}
+
+#[derive(Deserialize)]
+struct FilterTarget {
+    target: String,
+}
+
+async fn list_filters(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    kind: FilterKind,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Read).await?;
+    let filters = user_filter::list_filters(data.app_data(), &owner, kind)
+        .await
+        .map_err(filter_error)?;
+    Ok(HttpResponse::Ok().json(filters))
+}
+
+async fn add_filter(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: NewFilter,
+    kind: FilterKind,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Follow).await?;
+    user_filter::add_filter(&data, &owner, kind, &info.target, info.domain)
+        .await
+        .map_err(filter_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn remove_filter(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: FilterTarget,
+    kind: FilterKind,
+) -> actix_web::Result<HttpResponse> {
+    let owner = super::authenticate(&request, &session, &data, Scopes::Follow).await?;
+    user_filter::remove_filter(&data, &owner, kind, &info.target)
+        .await
+        .map_err(filter_error)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/account/blocks")]
+async fn list_blocks(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    list_filters(request, session, data, FilterKind::Block).await
+}
+
+#[post("/account/blocks")]
+async fn add_block(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Json<NewFilter>,
+) -> actix_web::Result<HttpResponse> {
+    add_filter(request, session, data, info.into_inner(), FilterKind::Block).await
+}
+
+#[delete("/account/blocks")]
+async fn remove_block(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Query<FilterTarget>,
+) -> actix_web::Result<HttpResponse> {
+    remove_filter(request, session, data, info.into_inner(), FilterKind::Block).await
+}
+
+#[get("/account/mutes")]
+async fn list_mutes(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    list_filters(request, session, data, FilterKind::Mute).await
+}
+
+#[post("/account/mutes")]
+async fn add_mute(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Json<NewFilter>,
+) -> actix_web::Result<HttpResponse> {
+    add_filter(request, session, data, info.into_inner(), FilterKind::Mute).await
+}
+
+#[delete("/account/mutes")]
+async fn remove_mute(
+    request: HttpRequest,
+    session: Session,
+    data: Data<DbHandle>,
+    info: web::Query<FilterTarget>,
+) -> actix_web::Result<HttpResponse> {
+    remove_filter(request, session, data, info.into_inner(), FilterKind::Mute).await
+}
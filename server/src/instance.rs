@@ -1,8 +1,14 @@
 use activitypub_federation::{
     config::{Data, FederationConfig, UrlVerifier},
     fetch::webfinger::{build_webfinger_response_with_type, extract_webfinger_name},
+    protocol::context::WithContext,
+    traits::Object,
+    FEDERATION_CONTENT_TYPE,
+};
+use actix_web::{
+    error::{ErrorInternalServerError, ErrorNotFound},
+    get, post, web, HttpResponse,
 };
-use actix_web::{error::ErrorNotFound, get, web, HttpResponse};
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::json;
@@ -11,18 +17,45 @@ use std::sync::Arc;
 use url::Url;
 use uuid::Uuid;
 use wordforge_api::{
-    objects::{novel::DbNovel, person::User},
+    domain_policy,
+    enums::RegistrationMode,
+    instance,
+    objects::{instance_actor::InstanceActor, novel::DbNovel, person::User},
+    restriction,
+    util::AppState,
     DbHandle,
 };
 
+/// Rejects URLs on blocked domains and ids of suspended actors. This runs for
+/// every object fetched from another instance, actors included, and for the
+/// id of every activity received in an inbox. Actors of received activities
+/// that are already stored aren't fetched and never get here, so inboxes
+/// check them with `api::reject_suspended`.
 #[derive(Clone)]
-struct VerifyUrl();
+struct VerifyUrl {
+    pool: DbHandle,
+    domain: String,
+}
 
 #[async_trait]
 impl UrlVerifier for VerifyUrl {
     async fn verify(&self, url: &Url) -> Result<(), &'static str> {
-        println!("{url}");
-        Ok(())
+        match domain_policy::is_url_blocked(&self.pool, url, &self.domain).await {
+            Ok(false) => (),
+            Ok(true) => return Err("Domain is blocked"),
+            Err(e) => {
+                log::error!("Domain policy lookup for {url} failed: {e}");
+                return Err("Domain policy lookup failed");
+            }
+        }
+        match restriction::is_suspended(&self.pool, url.as_str()).await {
+            Ok(false) => Ok(()),
+            Ok(true) => Err("Actor is suspended"),
+            Err(e) => {
+                log::error!("Restriction lookup for {url} failed: {e}");
+                Err("Restriction lookup failed")
+            }
+        }
     }
 }
 
@@ -40,8 +73,11 @@ pub async fn new_database(
 
     FederationConfig::builder()
         .debug(cfg!(debug_assertions))
-        .domain(host)
-        .url_verifier(Box::new(VerifyUrl()))
+        .domain(host.clone())
+        .url_verifier(Box::new(VerifyUrl {
+            pool: pool.clone(),
+            domain: host,
+        }))
         .app_data(pool)
         .build()
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
@@ -89,3 +125,67 @@ async fn webfinger(
         Ok(HttpResponse::Ok().json(res))
     }
 }
+
+#[get("/.well-known/nodeinfo")]
+async fn nodeinfo_links(state: web::Data<AppState>, data: Data<DbHandle>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "links": [{
+            "rel": "http://nodeinfo.diaspora.software/ns/schema/2.0",
+            "href": format!("{}://{}/nodeinfo/2.0", state.scheme, data.domain()),
+        }]
+    }))
+}
+
+#[get("/nodeinfo/2.0")]
+async fn nodeinfo(data: Data<DbHandle>) -> actix_web::Result<HttpResponse> {
+    let settings = instance::settings(data.app_data())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let usage = instance::usage(data.app_data())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let mode = settings.registration_mode;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "version": "2.0",
+        "software": {
+            "name": "wordforge",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "protocols": ["activitypub"],
+        "services": { "inbound": [], "outbound": [] },
+        "openRegistrations": matches!(mode, RegistrationMode::Open | RegistrationMode::Approval),
+        "usage": {
+            "users": { "total": usage.users },
+            "localPosts": usage.chapters,
+        },
+        "metadata": {
+            "registrationMode": mode.to_string(),
+            "approvalRequired": mode == RegistrationMode::Approval,
+            "novels": usage.novels,
+        },
+    })))
+}
+
+/// The instance actor, which sends activities on behalf of the instance.
+#[get("/actor")]
+async fn instance_actor(
+    state: web::Data<AppState>,
+    data: Data<DbHandle>,
+) -> actix_web::Result<HttpResponse> {
+    let actor = InstanceActor::read(&state.scheme, &data)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .into_json(&data)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok()
+        .content_type(FEDERATION_CONTENT_TYPE)
+        .json(WithContext::new_default(actor)))
+}
+
+/// Nothing is addressed to the instance actor, so whatever arrives is dropped.
+#[post("/actor/inbox")]
+async fn instance_inbox() -> HttpResponse {
+    HttpResponse::Accepted().finish()
+}
@@ -1,6 +1,9 @@
-use crate::instance::{new_database, webfinger};
+use crate::instance::{
+    instance_actor, instance_inbox, new_database, nodeinfo, nodeinfo_links, webfinger,
+};
 use activitypub_federation::config::FederationMiddleware;
 use actix_files::Files;
+use actix_redis::RedisActor;
 use actix_session::{
     config::{CookieContentSecurity, PersistentSession},
     storage::RedisActorSessionStore,
@@ -11,15 +14,25 @@ use actix_web::{
     middleware::{self, Compress, NormalizePath},
     web, HttpServer,
 };
+use argon2::Params;
 use leptos::view;
 use leptos_actix::{generate_route_list, handle_server_fns, LeptosRoutes};
 use std::{env, io};
-use wordforge_api::util::AppState;
+use wordforge_api::{account::SESSION_TTL_DAYS, util::AppState};
 use wordforge_ui::{app::*, register_server_functions};
 
 mod api;
 mod instance;
 
+fn argon2_param(var: &str, default: u32) -> u32 {
+    env::var(var)
+        .map(|v| {
+            v.parse()
+                .unwrap_or_else(|_| panic!("{var} must be a number"))
+        })
+        .unwrap_or(default)
+}
+
 #[actix_web::main]
 async fn main() -> io::Result<()> {
     dotenv::dotenv().ok();
@@ -43,11 +56,34 @@ async fn main() -> io::Result<()> {
     let db_url = env::var("DATABASE_URL").expect("DATABASE_URL is required");
     let state = AppState {
         scheme: env::var("SCHEME").expect("SCHEME is required").into(),
+        uniform_login_errors: env::var("UNIFORM_LOGIN_ERRORS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false),
+        argon2_params: Params::new(
+            argon2_param("ARGON2_MEMORY", Params::DEFAULT_M_COST),
+            argon2_param("ARGON2_ITERATIONS", Params::DEFAULT_T_COST),
+            argon2_param("ARGON2_PARALLELISM", Params::DEFAULT_P_COST),
+            None,
+        )
+        .expect("Invalid Argon2 parameters"),
+        trusted_proxies: env::var("TRUSTED_PROXIES")
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| {
+                        p.parse()
+                            .unwrap_or_else(|_| panic!("Invalid address in TRUSTED_PROXIES: {p}"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
     };
     let config = new_database(addr.to_string(), db_url)
         .await
         .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
     let key = Key::from(include_bytes!("cookie.key")); // TODO: Better way to do this
+    let redis = RedisActor::start(redis_url.clone());
 
     log::info!("Starting server");
     log::info!("Listening on {addr}");
@@ -55,7 +91,10 @@ async fn main() -> io::Result<()> {
     HttpServer::new(move || {
         let session =
             SessionMiddleware::builder(RedisActorSessionStore::new(redis_url.clone()), key.clone())
-                .session_lifecycle(PersistentSession::default().session_ttl(Duration::days(7)))
+                .session_lifecycle(
+                    PersistentSession::default()
+                        .session_ttl(Duration::days(SESSION_TTL_DAYS.into())),
+                )
                 .cookie_content_security(CookieContentSecurity::Private)
                 .cookie_same_site(SameSite::Strict)
                 .cookie_secure(cfg!(not(debug_assertions)))
@@ -67,6 +106,7 @@ async fn main() -> io::Result<()> {
 
         actix_web::App::new()
             .app_data(web::Data::new(state.clone()))
+            .app_data(web::Data::new(redis.clone()))
             .wrap(middleware::Logger::default())
             .wrap(session)
             .wrap(Compress::default())
@@ -76,9 +116,17 @@ async fn main() -> io::Result<()> {
             .route("/novel/{uuid}", api::novels())
             .service(api::novel::novel_inbox)
             .service(api::novel::novel_outbox)
+            .service(api::user::user_inbox)
             .service(api::user::user_outbox)
+            .service(api::user::user_shelf)
             .service(api::scope())
+            .service(api::oauth::token)
+            .service(api::oauth::revoke)
             .service(webfinger)
+            .service(instance_actor)
+            .service(instance_inbox)
+            .service(nodeinfo_links)
+            .service(nodeinfo)
             .route("/server/{tail:.*}", handle_server_fns())
             .leptos_routes(
                 opts.to_owned(),
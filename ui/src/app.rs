@@ -1,7 +1,10 @@
 use crate::{
-    components::panel::*,
+    components::{panel::*, progress::*, trending::*},
     fallback::*,
-    routes::{auth::*, novel::*},
+    routes::{
+        admin::*, auth::*, bookmark::*, browse::*, chapter::*, novel::*, oauth::*, search::*,
+        settings::*, shelf::*, tag::*, timeline::*, user::*,
+    },
 };
 use leptos::*;
 use leptos_icons::*;
@@ -92,23 +95,445 @@ pub fn App(cx: Scope) -> impl IntoView {
                             }
                         }
                     />
+                    <Route
+                        path="/oauth/authorize"
+                        view=move |cx| {
+                            let loc = use_location(cx);
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <OAuthAuthorize/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            let path = format!(
+                                                "/auth?redirect_to={}",
+                                                escape(&format!("{}{}", loc.pathname.get(), loc.search.get()))
+                                            );
+                                            view! { cx, <Redirect path=path/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/settings/password"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <PasswordSettings/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/settings/invites"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <InviteSettings/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/settings/tokens"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <TokenSettings/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                                                            <Route
+                        path="/settings/sessions"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <SessionSettings/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/settings/filters"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <FilterSettings/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/admin"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <AdminDashboard/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/admin/reports"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <ReportQueue/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/admin/federation"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <FederationSettings/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/admin/log"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <ModerationLog/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/admin/trending"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <TrendingReview/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/shelves"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <ReadingLists/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/bookmarks"
+                        view=move |cx| {
+                            view! { cx,
+                                <Suspense fallback=|| ()>
+                                    {move || match valid(cx) {
+                                        None => ().into_view(cx),
+                                        Some(Ok(_)) => {
+                                            view! { cx, <Bookmarks/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Unauthorized(e))) => {
+                                            log!("Validation: {}", e);
+                                            view! { cx, <Redirect path="/auth"/> }
+                                                .into_view(cx)
+                                        }
+                                        Some(Err(ValidationError::Error(e))) => {
+                                            error!("ValidationError::Error@app::Router: {}", e);
+                                            view! { cx, <InternalErrorPage/> }
+                                                .into_view(cx)
+                                        }
+                                    }}
+                                </Suspense>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/shelves/:id"
+                        view=|cx| {
+                            view! { cx, <ShelfView/> }
+                        }
+                    />
+                    <Route
+                        path="/search"
+                        view=|cx| {
+                            view! { cx, <Search/> }
+                        }
+                    />
                     <Route
                         path="/novel/:uuid"
                         view=|cx| {
                             view! { cx, <NovelView/> }
                         }
                     />
+                    <Route
+                        path="/novel/:uuid/:chapter"
+                        view=|cx| {
+                            view! { cx, <ChapterView/> }
+                        }
+                    />
+                    <Route
+                        path="/user/:name"
+                        view=|cx| {
+                            view! { cx, <UserView/> }
+                        }
+                    />
+                    <Route
+                        path="/tags/:tag"
+                        view=|cx| {
+                            view! { cx, <TagView/> }
+                        }
+                    />
+                    <Route
+                        path="/browse"
+                        view=|cx| {
+                            view! { cx,
+                                <Title text="Browse"/>
+                                <Browse/>
+                            }
+                        }
+                    />
+                    <Route
+                        path="/explore"
+                        view=|cx| {
+                            view! { cx, <Explore local=true/> }
+                        }
+                    />
+                    <Route
+                        path="/explore/public"
+                        view=|cx| {
+                            view! { cx, <Explore local=false/> }
+                        }
+                    />
                 </Routes>
             </Overlay>
         </Router>
     }
 }
 
+/// The home timeline when signed in, and novels to browse otherwise.
 #[component]
 fn Home(cx: Scope) -> impl IntoView {
+    let validate = use_context::<
+        Resource<(), Result<Result<(String, String), ValidationError>, ServerFnError>>,
+    >(cx)
+    .unwrap();
+    let signed_in = move || matches!(validate.read(cx), Some(Ok(Ok(_))));
+
     view! { cx,
-        <p class="text-6xl">"EVENTS"</p>
-        <p class="text-6xl">"RECOMMENDATIONS"</p>
+        <Transition fallback=|| ()>
+            <Show
+                when=signed_in
+                fallback=|cx| {
+                    view! { cx,
+                        <div class="mx-auto max-w-2xl px-4">
+                            <Trending/>
+                        </div>
+                        <Browse/>
+                    }
+                }
+            >
+                <div class="mx-auto max-w-2xl px-4">
+                    <h1 class="p-2 text-3xl">"Home"</h1>
+                    <ContinueReading/>
+                    <Trending/>
+                    <Timeline
+                        timeline="home"
+                        empty="Nothing here yet. Follow novels and people to see their new chapters and novels here."
+                    />
+                </div>
+            </Show>
+        </Transition>
     }
 }
 
@@ -170,6 +595,7 @@ fn Sidebar(
     });
     let (name, set_name) = create_signal::<Option<String>>(cx, None);
     let panel = create_rw_signal(cx, false);
+    let staff = create_resource(cx, || (), move |_| is_staff(cx));
 
     view! { cx,
         <div class="md:flex flex-none flex-col p-2 items-start text-xl align-top h-screen w-0 hidden md:w-60">
@@ -197,6 +623,16 @@ fn Sidebar(
                 />
                 <span class="my-auto">"Public"</span>
             </A>
+            <A
+                href="/search"
+                class="flex flex-row gap-2 w-full p-2 rounded-md hover:dark:bg-gray-800"
+            >
+                <Icon
+                    icon=OcIcon::OcSearchLg
+                    class="dark:stroke-white py-1 w-10 h-10 stroke-0 my-auto"
+                />
+                <span class="my-auto">"Search"</span>
+            </A>
             <span class="my-auto"></span>
             <div class="w-full">
                 <Transition fallback=move || {
@@ -307,6 +743,51 @@ fn Sidebar(
                             when=panel
                             class="absolute flex flex-col bottom-[4.5rem] -mx-2 p-2 w-56 dark:bg-gray-800 rounded-md"
                         >
+                            <A
+                                href="/shelves"
+                                class="flex flex-row gap-2 my-auto text-left w-full p-2 rounded-md hover:dark:bg-gray-700"
+                            >
+                                <Icon
+                                    icon=OcIcon::OcBookLg
+                                    class="dark:stroke-white w-8 h-8 my-auto stroke-0 pointer-events-none"
+                                />
+                                <span class="my-auto">"Reading lists"</span>
+                            </A>
+                            <A
+                                href="/bookmarks"
+                                class="flex flex-row gap-2 my-auto text-left w-full p-2 rounded-md hover:dark:bg-gray-700"
+                            >
+                                <Icon
+                                    icon=OcIcon::OcBookmarkLg
+                                    class="dark:stroke-white w-8 h-8 my-auto stroke-0 pointer-events-none"
+                                />
+                                <span class="my-auto">"Bookmarks"</span>
+                            </A>
+                            <A
+                                href="/settings/password"
+                                class="flex flex-row gap-2 my-auto text-left w-full p-2 rounded-md hover:dark:bg-gray-700"
+                            >
+                                <Icon
+                                    icon=OcIcon::OcGearLg
+                                    class="dark:stroke-white w-8 h-8 my-auto stroke-0 pointer-events-none"
+                                />
+                                <span class="my-auto">"Settings"</span>
+                            </A>
+                            <Show
+                                when=move || staff.read(cx).and_then(|r| r.ok()).unwrap_or(false)
+                                fallback=|_| ()
+                            >
+                                <A
+                                    href="/admin"
+                                    class="flex flex-row gap-2 my-auto text-left w-full p-2 rounded-md hover:dark:bg-gray-700"
+                                >
+                                    <Icon
+                                        icon=OcIcon::OcShieldLg
+                                        class="dark:stroke-white w-8 h-8 my-auto stroke-0 pointer-events-none"
+                                    />
+                                    <span class="my-auto">"Admin"</span>
+                                </A>
+                            </Show>
                             <button
                                 class="flex flex-row gap-2 my-auto text-left w-full p-2 rounded-md hover:dark:bg-gray-700"
                                 on:click=move |_| logout.dispatch(())
@@ -338,10 +819,60 @@ fn BottomBar(
             .map(|resp| resp.unwrap_or_else(|e| Err(ValidationError::Error(e.to_string()))))
     });
     let panel = create_rw_signal(cx, false);
+    let staff = create_resource(cx, || (), move |_| is_staff(cx));
 
     view! { cx,
         <div class="fixed bottom-0">
             <Panel when=panel class="p-2 rounded-t-xl w-full dark:bg-gray-950">
+                <A
+                    href="/shelves"
+                    class="relative flex flex-row gap-3 my-auto text-left w-full p-3 rounded-md hover:dark:bg-gray-900"
+                    on:click=move |_| panel.set(false)
+                >
+                    <Icon
+                        icon=OcIcon::OcBookLg
+                        class="dark:stroke-white w-6 h-6 my-auto stroke-0 pointer-events-none"
+                    />
+                    <span class="my-auto">"Reading lists"</span>
+                </A>
+                <A
+                    href="/bookmarks"
+                    class="relative flex flex-row gap-3 my-auto text-left w-full p-3 rounded-md hover:dark:bg-gray-900"
+                    on:click=move |_| panel.set(false)
+                >
+                    <Icon
+                        icon=OcIcon::OcBookmarkLg
+                        class="dark:stroke-white w-6 h-6 my-auto stroke-0 pointer-events-none"
+                    />
+                    <span class="my-auto">"Bookmarks"</span>
+                </A>
+                <A
+                    href="/settings/password"
+                    class="relative flex flex-row gap-3 my-auto text-left w-full p-3 rounded-md hover:dark:bg-gray-900"
+                    on:click=move |_| panel.set(false)
+                >
+                    <Icon
+                        icon=OcIcon::OcGearLg
+                        class="dark:stroke-white w-6 h-6 my-auto stroke-0 pointer-events-none"
+                    />
+                    <span class="my-auto">"Settings"</span>
+                </A>
+                <Show
+                    when=move || staff.read(cx).and_then(|r| r.ok()).unwrap_or(false)
+                    fallback=|_| ()
+                >
+                    <A
+                        href="/admin"
+                        class="relative flex flex-row gap-3 my-auto text-left w-full p-3 rounded-md hover:dark:bg-gray-900"
+                        on:click=move |_| panel.set(false)
+                    >
+                        <Icon
+                            icon=OcIcon::OcShieldLg
+                            class="dark:stroke-white w-6 h-6 my-auto stroke-0 pointer-events-none"
+                        />
+                        <span class="my-auto">"Admin"</span>
+                    </A>
+                </Show>
                 <button
                     class="relative flex flex-row gap-3 my-auto text-left w-full p-3 rounded-md hover:dark:bg-gray-900"
                     on:click=move |_| {
@@ -369,10 +900,12 @@ fn BottomBar(
                         class="dark:stroke-white py-1 w-10 h-10 stroke-0 my-auto"
                     />
                 </A>
-                <Icon
-                    icon=OcIcon::OcSearchLg
-                    class="dark:stroke-white py-1 w-10 h-10 stroke-0 my-auto cursor-pointer"
-                />
+                <A href="/search">
+                    <Icon
+                        icon=OcIcon::OcSearchLg
+                        class="dark:stroke-white py-1 w-10 h-10 stroke-0 my-auto"
+                    />
+                </A>
                 <Transition fallback=|| ()>
                     {move || {
                         match valid() {
@@ -451,13 +984,23 @@ async fn validate(cx: Scope) -> Result<Result<(String, String), ValidationError>
             UserValidateError::Unauthorized(v) => ValidationError::Unauthorized(v),
             UserValidateError::InternalServerError(v) => ValidationError::Error(v),
             UserValidateError::NotFound(v) => ValidationError::Error(v),
+            UserValidateError::Forbidden(v) => ValidationError::Error(v),
         }))
 }
 
 #[server(Logout, "/server")]
 async fn logout(cx: Scope) -> Result<(), ServerFnError> {
-    leptos_actix::extract(cx, |session: actix_session::Session| async move {
-        session.purge();
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{account, DbHandle};
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
     })
-    .await
+    .await?;
+
+    account::logout(pool.app_data(), session)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
 }
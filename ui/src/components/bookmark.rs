@@ -0,0 +1,179 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BookmarkItem {
+    pub id: String,
+    pub kind: String,
+    pub quote: String,
+    pub note: String,
+    pub published: String,
+    /// The ID of the paragraph it points at now. Unset when the passage is
+    /// gone.
+    pub anchor: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<wordforge_api::api::bookmark::Bookmark> for BookmarkItem {
+    fn from(b: wordforge_api::api::bookmark::Bookmark) -> Self {
+        Self {
+            id: b.id.to_string(),
+            kind: b.kind.to_string(),
+            quote: b.quote,
+            note: b.note,
+            published: chrono_humanize::HumanTime::from(b.published).to_string(),
+            anchor: b.anchor,
+        }
+    }
+}
+
+/// Splits the paragraph `text` into the passages covered by `highlights`,
+/// given with their notes, and the text around them. Highlights overlapping
+/// an earlier one are left out.
+pub fn passages(text: &str, highlights: &[(String, String)]) -> Vec<(String, Option<String>)> {
+    let mut ranges = highlights
+        .iter()
+        .filter_map(|(quote, note)| {
+            text.find(quote.as_str())
+                .map(|start| (start, start + quote.len(), note))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|(start, _, _)| *start);
+
+    let mut parts = vec![];
+    let mut at = 0;
+    for (start, end, note) in ranges {
+        if start < at {
+            continue;
+        }
+        if start > at {
+            parts.push((text[at..start].to_string(), None));
+        }
+        parts.push((text[start..end].to_string(), Some(note.clone())));
+        at = end;
+    }
+    if at < text.len() {
+        parts.push((text[at..].to_string(), None));
+    }
+    parts
+}
+
+#[server(GetChapterBookmarks, "/server")]
+pub async fn get_chapter_bookmarks(
+    cx: Scope,
+    chapter: String,
+) -> Result<Result<Vec<BookmarkItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::bookmark::{self, BookmarkError},
+        DbHandle,
+    };
+
+    if chapter.is_empty() {
+        return Ok(Ok(vec![]));
+    }
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match bookmark::chapter_bookmarks(pool.app_data(), &apub_id, &chapter).await {
+        Ok(bookmarks) => Ok(Ok(bookmarks.into_iter().map(BookmarkItem::from).collect())),
+        Err(BookmarkError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(AddBookmark, "/server")]
+pub async fn add_bookmark(
+    cx: Scope,
+    chapter: String,
+    kind: String,
+    paragraph: String,
+    quote: String,
+    note: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::bookmark::{self, BookmarkError, NewBookmark},
+        enums::BookmarkKind,
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let kind = match BookmarkKind::from_str(&kind) {
+        Ok(kind) => kind,
+        Err(_) => return Ok(Err("Invalid kind".to_string())),
+    };
+
+    let new = NewBookmark {
+        chapter,
+        kind,
+        paragraph,
+        quote,
+        note,
+    };
+    match bookmark::add_bookmark(pool.app_data(), &apub_id, new).await {
+        Ok(_) => Ok(Ok(())),
+        Err(BookmarkError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(BookmarkError::BadRequest(e)) => Ok(Err(e)),
+        Err(BookmarkError::NotFound) => Ok(Err("Chapter not found".to_string())),
+    }
+}
+
+#[server(DeleteBookmark, "/server")]
+pub async fn delete_bookmark(cx: Scope, id: String) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::bookmark::{self, BookmarkError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let id = match id.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("Not found".to_string())),
+    };
+
+    match bookmark::delete_bookmark(pool.app_data(), &apub_id, id).await {
+        Ok(_) => Ok(Ok(())),
+        Err(BookmarkError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(BookmarkError::NotFound) => Ok(Err("Not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
@@ -119,8 +119,14 @@ pub fn NewChapter(
     }
 }
 
+/// A chapter in its novel's chapter list. `read` marks it as read by the
+/// signed in user.
 #[component]
-pub fn ChapterEntry(cx: Scope, chapter: Result<ChapterItem, ServerFnError>) -> impl IntoView {
+pub fn ChapterEntry(
+    cx: Scope,
+    chapter: Result<ChapterItem, ServerFnError>,
+    read: Signal<bool>,
+) -> impl IntoView {
     view! { cx,
         <ErrorBoundary fallback=move |cx, e| {
             view! { cx,
@@ -149,6 +155,12 @@ pub fn ChapterEntry(cx: Scope, chapter: Result<ChapterItem, ServerFnError>) -> i
                                         .then_some(
                                             view! { cx, <span class="dark:bg-red-600 rounded-full my-auto px-1 text-sm">"CW"</span> },
                                         )} <h3 class="font-bold">{c.title}</h3>
+                                    {move || {
+                                        read()
+                                            .then(|| {
+                                                view! { cx, <span class="my-auto text-sm dark:text-gray-500">"Read"</span> }
+                                            })
+                                    }}
                                 </div>
                                 <Tooltip view=move || c.published_exact.clone()>
                                     <span class="dark:text-gray-400">{c.published}</span>
@@ -191,7 +203,9 @@ pub async fn create(
     use actix_session::Session;
     use actix_web::web;
     use leptos_actix::extract;
+    use url::Url;
     use wordforge_api::{
+        account,
         activities::add::NewChapter,
         api::chapter::{new_chapter, ChapterCreationError},
         util::AppState,
@@ -206,26 +220,43 @@ pub async fn create(
     )
     .await?;
 
+    let apub_id = account::current_user(data.app_data(), &session)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+        .parse::<Url>()
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+
     let chapter = NewChapter {
         title,
         summary,
         sensitive,
     };
 
-    match new_chapter(novel, chapter, session, &data, &state.scheme).await {
+    match new_chapter(novel, chapter, apub_id, &data, &state.scheme).await {
         Ok(_) => Ok(()),
         Err(ChapterCreationError::InternalError(e)) => Err(ServerFnError::ServerError(e)),
-        Err(ChapterCreationError::Unauthorized) => {
-            Err(ServerFnError::ServerError("Not signed in".to_string()))
-        }
+        Err(ChapterCreationError::Unauthorized) => Err(ServerFnError::ServerError(
+            "Only authors can add chapters".to_string(),
+        )),
         Err(ChapterCreationError::NotFound) => {
             Err(ServerFnError::ServerError("Novel not found".to_string()))
         }
     }
 }
 
+/// Where a chapter is shown relative to its novel's path: the last segment
+/// of its URL.
+#[cfg(feature = "ssr")]
+pub fn chapter_href(apub_id: &str) -> String {
+    url::Url::parse(apub_id)
+        .ok()
+        .and_then(|u| u.path_segments()?.last().map(str::to_string))
+        .unwrap_or_default()
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChapterItem {
+    pub apub_id: String,
     pub href: String,
     pub title: String,
     pub summary: String,
@@ -244,7 +275,6 @@ pub async fn get_chapter_list(
     use activitypub_federation::config::Data;
     use chrono_humanize::HumanTime;
     use leptos_actix::extract;
-    use url::Url;
     use wordforge_api::{
         api::chapter::{get_chapters, ChapterError},
         DbHandle,
@@ -258,15 +288,8 @@ pub async fn get_chapter_list(
                 .into_iter()
                 .map(|c| match c {
                     Ok(c) => Ok(ChapterItem {
-                        href: c
-                            .apub_id
-                            .parse::<Url>()
-                            .unwrap()
-                            .path_segments()
-                            .unwrap()
-                            .last()
-                            .unwrap()
-                            .to_string(),
+                        apub_id: c.apub_id.clone(),
+                        href: chapter_href(&c.apub_id),
                         title: c.title,
                         summary: c.summary,
                         sensitive: c.sensitive,
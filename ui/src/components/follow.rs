@@ -0,0 +1,139 @@
+use crate::components::errorview::ErrorView;
+use leptos::*;
+use leptos_router::*;
+
+/// Follows or unfollows `target`, the actor URL of a user or novel. Shows
+/// nothing when signed out or on the signed in user's own profile.
+#[component]
+pub fn FollowButton(cx: Scope, target: ReadSignal<String>) -> impl IntoView {
+    let set_follow = create_server_action::<SetFollow>(cx);
+    let following = create_resource(
+        cx,
+        move || (target(), set_follow.version().get()),
+        move |(target, _)| is_following(cx, target),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let response = set_follow.value();
+    let followed = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(_)) => set_errormsg(String::new()),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Transition fallback=|| ()>
+            {move || {
+                following
+                    .read(cx)
+                    .map(|v| match v {
+                        Err(e) => {
+                            error!("following: {}", e.to_string());
+                            ().into_view(cx)
+                        }
+                        Ok(Err(_)) => ().into_view(cx),
+                        Ok(Ok(following)) => {
+                            view! { cx,
+                                <ActionForm action=set_follow class="my-auto">
+                                    <input type="hidden" name="target" value=target/>
+                                    <input type="hidden" name="follow" value=(!following).to_string()/>
+                                    <input
+                                        type="submit"
+                                        class=if following {
+                                            "dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                        } else {
+                                            "dark:bg-purple-600 hover:dark:bg-purple-700 rounded-md px-3 py-1 cursor-pointer"
+                                        }
+                                        value=if following { "Unfollow" } else { "Follow" }
+                                    />
+                                </ActionForm>
+                            }
+                                .into_view(cx)
+                        }
+                    })
+            }}
+        </Transition>
+        <ErrorView message=errormsg/>
+        {followed}
+    }
+}
+
+#[server(IsFollowing, "/server")]
+pub async fn is_following(
+    cx: Scope,
+    target: String,
+) -> Result<Result<bool, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::follow::{self, FollowError},
+        DbHandle,
+    };
+
+    if target.is_empty() {
+        return Ok(Err("Not found".to_string()));
+    }
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    if target.eq_ignore_ascii_case(&apub_id) {
+        return Ok(Err("You can't follow yourself".to_string()));
+    }
+
+    match follow::is_following(pool.app_data(), &apub_id, &target).await {
+        Ok(following) => Ok(Ok(following)),
+        Err(FollowError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(SetFollow, "/server")]
+pub async fn set_follow(
+    cx: Scope,
+    target: String,
+    follow: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::follow::{self, FollowError},
+        DbHandle,
+    };
+
+    let (session, data) = extract(cx, |session: Session, data: Data<DbHandle>| async move {
+        (session, data)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(data.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    let res = if follow {
+        follow::follow(&data, &apub_id, &target).await
+    } else {
+        follow::unfollow(&data, &apub_id, &target).await
+    };
+    match res {
+        Ok(_) => Ok(Ok(())),
+        Err(FollowError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(FollowError::NotFound) => Ok(Err("Not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
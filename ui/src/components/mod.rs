@@ -1,7 +1,14 @@
 pub mod basicinput;
+pub mod bookmark;
 pub mod chapter;
 pub mod errorview;
+pub mod follow;
 pub mod listbox;
+pub mod novelcard;
 pub mod panel;
+pub mod progress;
+pub mod report;
+pub mod shelf;
 pub mod toggle;
 pub mod tooltip;
+pub mod trending;
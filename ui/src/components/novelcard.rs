@@ -0,0 +1,64 @@
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NovelSummary {
+    pub path: String,
+    pub title: String,
+    pub summary: String,
+    pub genre: String,
+    pub tags: Vec<String>,
+    /// The language's English name.
+    pub language: String,
+    pub sensitive: bool,
+}
+
+#[cfg(feature = "ssr")]
+impl From<wordforge_api::api::novel::NovelListing> for NovelSummary {
+    fn from(n: wordforge_api::api::novel::NovelListing) -> Self {
+        Self {
+            path: n.path,
+            title: n.title,
+            summary: n.summary,
+            genre: n.genre,
+            tags: n.tags,
+            language: isolang::Language::from_639_1(&n.language)
+                .map(|l| l.to_name().to_string())
+                .unwrap_or(n.language),
+            sensitive: n.sensitive,
+        }
+    }
+}
+
+#[component]
+pub fn NovelCard(cx: Scope, novel: NovelSummary) -> impl IntoView {
+    view! { cx,
+        <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+            <A href=novel.path class="text-xl hover:underline">
+                {novel.title}
+            </A>
+            <span class="text-sm dark:text-gray-400">
+                {novel.genre} " · " {novel.language}
+                {novel.sensitive.then_some(" · Sensitive")}
+            </span>
+            <p class="line-clamp-3">{novel.summary}</p>
+            <span class="flex flex-row flex-wrap gap-2">
+                {novel
+                    .tags
+                    .into_iter()
+                    .map(|tag| {
+                        view! { cx,
+                            <A
+                                href=format!("/tags/{}", tag.to_lowercase())
+                                class="italic text-sm dark:text-gray-500 dark:hover:text-gray-400 hover:underline"
+                            >
+                                {format!("#{tag}")}
+                            </A>
+                        }
+                    })
+                    .collect_view(cx)}
+            </span>
+        </li>
+    }
+}
@@ -0,0 +1,227 @@
+use crate::components::novelcard::*;
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NovelProgress {
+    /// Where the first unread chapter is shown relative to the novel, and
+    /// how far into it the reader got. Unset once every chapter is read.
+    pub resume: Option<(String, f32)>,
+    /// The chapters read so far, lowercased.
+    pub read: Vec<String>,
+    pub unread: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnreadItem {
+    novel: NovelSummary,
+    unread: i64,
+    /// Where the first unread chapter is shown.
+    href: String,
+}
+
+/// Followed novels with unread chapters, for the signed in user's home page.
+/// Shows nothing while there aren't any.
+#[component]
+pub fn ContinueReading(cx: Scope) -> impl IntoView {
+    let unread = create_resource(cx, || (), move |_| get_unread_novels(cx));
+
+    view! { cx,
+        <Transition fallback=|| ()>
+            {move || {
+                unread
+                    .read(cx)
+                    .map(|v| match v {
+                        Err(e) => {
+                            error!("unread novels: {}", e.to_string());
+                            ().into_view(cx)
+                        }
+                        Ok(Err(_)) => ().into_view(cx),
+                        Ok(Ok(novels)) if novels.is_empty() => ().into_view(cx),
+                        Ok(Ok(novels)) => {
+                            view! { cx,
+                                <section class="p-2">
+                                    <h2 class="text-xl">"Continue reading"</h2>
+                                    <ul class="flex flex-col gap-2 py-2">
+                                        {novels
+                                            .into_iter()
+                                            .map(|n| {
+                                                view! { cx,
+                                                    <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                        <div class="flex flex-col">
+                                                            <A href=n.novel.path class="text-xl hover:underline">
+                                                                {n.novel.title}
+                                                            </A>
+                                                            <span class="text-sm dark:text-gray-400">
+                                                                {format!("{} unread chapter(s)", n.unread)}
+                                                            </span>
+                                                        </div>
+                                                        <A
+                                                            href=n.href
+                                                            class="my-auto whitespace-nowrap dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1"
+                                                        >
+                                                            "Continue"
+                                                        </A>
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </ul>
+                                </section>
+                            }
+                                .into_view(cx)
+                        }
+                    })
+            }}
+        </Transition>
+    }
+}
+
+#[server(GetUnreadNovels, "/server")]
+pub async fn get_unread_novels(
+    cx: Scope,
+) -> Result<Result<Vec<UnreadItem>, String>, ServerFnError> {
+    use crate::components::chapter::chapter_href;
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::progress::{self, ProgressError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match progress::unread_following(pool.app_data(), &apub_id).await {
+        Ok(novels) => Ok(Ok(novels
+            .into_iter()
+            .map(|n| UnreadItem {
+                href: format!("{}/{}", n.novel.path, chapter_href(&n.resume.chapter)),
+                unread: n.unread,
+                novel: NovelSummary::from(n.novel),
+            })
+            .collect())),
+        Err(ProgressError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(GetNovelProgress, "/server")]
+pub async fn get_novel_progress(
+    cx: Scope,
+    novel: String,
+) -> Result<Result<NovelProgress, String>, ServerFnError> {
+    use crate::components::chapter::chapter_href;
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::progress::{self, ProgressError},
+        DbHandle,
+    };
+
+    if novel.is_empty() {
+        return Ok(Err("Not found".to_string()));
+    }
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match progress::get_progress(pool.app_data(), &apub_id, &novel).await {
+        Ok(p) => Ok(Ok(NovelProgress {
+            resume: p.resume.map(|r| (chapter_href(&r.chapter), r.position)),
+            read: p.read,
+            unread: p.unread,
+        })),
+        Err(ProgressError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(MarkChapterRead, "/server")]
+pub async fn mark_chapter_read(
+    cx: Scope,
+    chapter: String,
+    read: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::progress::{self, ProgressError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match progress::mark_read(pool.app_data(), &apub_id, &chapter, read).await {
+        Ok(_) => Ok(Ok(())),
+        Err(ProgressError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ProgressError::NotFound) => Ok(Err("Chapter not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(SetReadingPosition, "/server")]
+pub async fn set_reading_position(
+    cx: Scope,
+    chapter: String,
+    position: f32,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::progress::{self, ProgressError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match progress::set_position(pool.app_data(), &apub_id, &chapter, position).await {
+        Ok(_) => Ok(Ok(())),
+        Err(ProgressError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ProgressError::NotFound) => Ok(Err("Chapter not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
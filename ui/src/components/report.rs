@@ -0,0 +1,152 @@
+use crate::components::{basicinput::FloatingLabel, toggle::Toggle};
+use leptos::{ev::Event, html::*, *};
+use leptos_router::*;
+use wasm_bindgen::UnwrapThrowExt;
+
+#[component]
+pub fn ReportDialog(
+    cx: Scope,
+    target: ReadSignal<String>,
+    kind: &'static str,
+    #[allow(unused_variables)] node_ref: NodeRef<Dialog>,
+) -> impl IntoView {
+    let report = create_server_action::<CreateReport>(cx);
+    let response = report.value();
+    let (errormsg, set_errormsg) = create_signal::<Option<String>>(cx, None);
+    let (sent, set_sent) = create_signal(cx, false);
+    let err = move || {
+        response().map(|v| match v {
+            Ok(Ok(_)) => {
+                set_errormsg(None);
+                set_sent(true);
+            }
+            Ok(Err(e)) => set_errormsg(Some(e)),
+            Err(e) => set_errormsg(Some(e.to_string())),
+        })
+    };
+
+    let form = create_node_ref::<Form>(cx);
+    let forward_ref = create_node_ref::<Input>(cx);
+    let forward = create_rw_signal(cx, false);
+
+    let reset_form = move |_: Event| {
+        if let Some(form) = form() {
+            form.reset();
+        }
+        forward.set(false);
+        set_sent(false);
+        set_errormsg(None)
+    };
+
+    view! { cx,
+        <dialog
+            class="rounded-xl w-md max-w-md backdrop:bg-gray-950/60 dark:bg-gray-900 dark:text-white"
+            node_ref=node_ref
+            on:close=reset_form
+            on:cancel=reset_form
+        >
+            <Show
+                when=sent
+                fallback=move |cx| {
+                    view! { cx,
+                        <ActionForm
+                            class="flex flex-col justify-center text-center place-content-center items-center space-y-4 p-4 w-full"
+                            node_ref=form
+                            action=report
+                        >
+                            <h2 class="text-2xl">{format!("Report {kind}")}</h2>
+                            <div class="relative w-full">
+                                <textarea
+                                    class="basic-input w-full peer"
+                                    placeholder=" "
+                                    name="reason"
+                                    maxlength=1000
+                                ></textarea>
+                                <FloatingLabel target="reason">"What's wrong?"</FloatingLabel>
+                            </div>
+                            <input type="hidden" name="target" value=target/>
+                            <input type="hidden" name="kind" value=kind/>
+                            <input type="hidden" name="forward" value=move || forward().to_string()/>
+                            <div class="flex justify-start mr-auto">
+                                <Toggle value=forward node_ref=forward_ref>
+                                    "Also send to the moderators of its instance if it is remote"
+                                </Toggle>
+                            </div>
+                            <input type="submit" class="button-1" value="Report"/>
+                        </ActionForm>
+                    }
+                }
+            >
+                <div class="flex flex-col items-center space-y-4 p-4">
+                    <p>"Thanks. The moderators will look into it."</p>
+                    <button
+                        class="button-1"
+                        on:click=move |_| node_ref().unwrap_throw().close()
+                    >
+                        "Close"
+                    </button>
+                </div>
+            </Show>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <p class="text-red-800 break-words">
+                    {move || {
+                        err();
+                        errormsg
+                    }}
+                </p>
+            </div>
+        </dialog>
+    }
+}
+
+#[server(CreateReport, "/server")]
+pub async fn create_report(
+    cx: Scope,
+    target: String,
+    kind: String,
+    reason: String,
+    forward: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use actix_web::web;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        enums::ReportTarget,
+        report::{self, NewReport, ReportError},
+        util::{AppState, RedisHandle},
+        DbHandle,
+    };
+
+    let (session, state, data, redis) = extract(
+        cx,
+        |session: Session,
+         state: web::Data<AppState>,
+         data: Data<DbHandle>,
+         redis: web::Data<RedisHandle>| async move { (session, state, data, redis) },
+    )
+    .await?;
+
+    let reporter = match account::current_user(data.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let kind =
+        ReportTarget::from_str(&kind).map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+
+    let info = NewReport {
+        target,
+        kind,
+        reason,
+        forward,
+    };
+    match report::create_report(&data, &redis, &reporter, &state.scheme, info).await {
+        Ok(_) => Ok(Ok(())),
+        Err(ReportError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ReportError::NotFound) => Ok(Err("Not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
@@ -0,0 +1,189 @@
+use crate::components::errorview::ErrorView;
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShelfItem {
+    pub id: String,
+    pub kind: String,
+    pub name: String,
+    pub public: bool,
+    pub novels: i64,
+}
+
+#[cfg(feature = "ssr")]
+impl From<wordforge_api::api::shelf::Shelf> for ShelfItem {
+    fn from(s: wordforge_api::api::shelf::Shelf) -> Self {
+        Self {
+            id: s.id.to_string(),
+            kind: s.kind.to_string(),
+            name: s.name,
+            public: s.public,
+            novels: s.novels,
+        }
+    }
+}
+
+/// Puts `novel`, the URL of a novel, on the signed in user's reading lists or
+/// takes it off them.
+#[component]
+pub fn ShelfMenu(cx: Scope, novel: ReadSignal<String>) -> impl IntoView {
+    let set_shelf = create_server_action::<SetShelfNovel>(cx);
+    let shelves = create_resource(
+        cx,
+        move || (novel(), set_shelf.version().get()),
+        move |(novel, _)| get_novel_shelves(cx, novel),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let response = set_shelf.value();
+    let changed = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(_)) => set_errormsg(String::new()),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Transition fallback=|| ()>
+            {move || {
+                shelves
+                    .read(cx)
+                    .map(|v| match v {
+                        Err(e) => {
+                            error!("novel shelves: {}", e.to_string());
+                            ().into_view(cx)
+                        }
+                        Ok(Err(_)) => ().into_view(cx),
+                        Ok(Ok(shelves)) => {
+                            let on = shelves.iter().filter(|(_, on)| *on).count();
+                            view! { cx,
+                                <details class="relative my-auto">
+                                    <summary class="list-none dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer">
+                                        {if on == 0 { "Add to list".to_string() } else { format!("On {on} list(s)") }}
+                                    </summary>
+                                    <ul class="absolute right-0 z-10 flex flex-col w-56 p-2 mt-1 rounded-md dark:bg-gray-800">
+                                        {shelves
+                                            .into_iter()
+                                            .map(|(s, on)| {
+                                                view! { cx,
+                                                    <li>
+                                                        <ActionForm action=set_shelf>
+                                                            <input type="hidden" name="shelf" value=s.id/>
+                                                            <input type="hidden" name="novel" value=novel/>
+                                                            <input type="hidden" name="add" value=(!on).to_string()/>
+                                                            <input
+                                                                type="submit"
+                                                                class="w-full text-left p-2 rounded-md cursor-pointer hover:dark:bg-gray-700"
+                                                                value=if on { format!("✓ {}", s.name) } else { s.name }
+                                                            />
+                                                        </ActionForm>
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                        <li>
+                                            <A
+                                                href="/shelves"
+                                                class="block p-2 rounded-md text-sm dark:text-gray-400 hover:dark:bg-gray-700"
+                                            >
+                                                "Manage lists"
+                                            </A>
+                                        </li>
+                                    </ul>
+                                </details>
+                            }
+                                .into_view(cx)
+                        }
+                    })
+            }}
+        </Transition>
+        <ErrorView message=errormsg/>
+        {changed}
+    }
+}
+
+#[server(GetNovelShelves, "/server")]
+pub async fn get_novel_shelves(
+    cx: Scope,
+    novel: String,
+) -> Result<Result<Vec<(ShelfItem, bool)>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::shelf::{self, ShelfError},
+        DbHandle,
+    };
+
+    if novel.is_empty() {
+        return Ok(Err("Not found".to_string()));
+    }
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match shelf::shelves_with_novel(pool.app_data(), &apub_id, &novel).await {
+        Ok(shelves) => Ok(Ok(shelves
+            .into_iter()
+            .map(|(s, on)| (ShelfItem::from(s), on))
+            .collect())),
+        Err(ShelfError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(SetShelfNovel, "/server")]
+pub async fn set_shelf_novel(
+    cx: Scope,
+    shelf: String,
+    novel: String,
+    add: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::shelf::{self, ShelfError},
+        DbHandle,
+    };
+
+    let (data, session) = extract(cx, |data: Data<DbHandle>, session: Session| async move {
+        (data, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(data.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let id = match shelf.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("Not found".to_string())),
+    };
+
+    let res = if add {
+        shelf::add_novel(&data, &apub_id, id, &novel).await
+    } else {
+        shelf::remove_novel(data.app_data(), &apub_id, id, &novel).await
+    };
+    match res {
+        Ok(_) => Ok(Ok(())),
+        Err(ShelfError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ShelfError::NotFound) => Ok(Err("Not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
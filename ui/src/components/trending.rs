@@ -0,0 +1,90 @@
+use crate::components::novelcard::*;
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrendingSummary {
+    novels: Vec<NovelSummary>,
+    tags: Vec<String>,
+}
+
+/// The trending novels and tags. Shows nothing while there aren't any.
+#[component]
+pub fn Trending(cx: Scope) -> impl IntoView {
+    let trending = create_resource(cx, || (), move |_| get_trending(cx));
+
+    view! { cx,
+        <Transition fallback=|| ()>
+            {move || {
+                trending
+                    .read(cx)
+                    .map(|v| match v {
+                        Err(e) => {
+                            error!("trending: {}", e.to_string());
+                            ().into_view(cx)
+                        }
+                        Ok(t) if t.novels.is_empty() && t.tags.is_empty() => ().into_view(cx),
+                        Ok(t) => {
+                            view! { cx,
+                                <section class="p-2">
+                                    <h2 class="text-xl">"Trending"</h2>
+                                    <div class="flex flex-row flex-wrap gap-2 py-2">
+                                        {t
+                                            .tags
+                                            .into_iter()
+                                            .map(|tag| {
+                                                view! { cx,
+                                                    <A
+                                                        href=format!("/tags/{tag}")
+                                                        class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+                                                    >
+                                                        {format!("#{tag}")}
+                                                    </A>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </div>
+                                    <ul class="flex flex-col gap-2">
+                                        {t
+                                            .novels
+                                            .into_iter()
+                                            .map(|novel| view! { cx, <NovelCard novel=novel/> })
+                                            .collect_view(cx)}
+                                    </ul>
+                                </section>
+                            }
+                                .into_view(cx)
+                        }
+                    })
+            }}
+        </Transition>
+    }
+}
+
+#[server(GetTrending, "/server")]
+pub async fn get_trending(cx: Scope) -> Result<TrendingSummary, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account,
+        api::trending::{self, TrendingError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let viewer = account::current_user(pool.app_data(), &session).await.ok();
+
+    match trending::trending(pool.app_data(), viewer.as_deref()).await {
+        Ok(t) => Ok(TrendingSummary {
+            novels: t.novels.into_iter().map(NovelSummary::from).collect(),
+            tags: t.tags.into_iter().map(|t| t.tag).collect(),
+        }),
+        Err(TrendingError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+    }
+}
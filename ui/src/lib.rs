@@ -19,11 +19,17 @@ pub fn hydrate() {
 
 #[cfg(feature = "ssr")]
 pub fn register_server_functions() {
-    use components::chapter::*;
-    use routes::{auth::*, novel::*};
+    use components::{
+        bookmark::*, chapter::*, follow::*, progress::*, report::*, shelf::*, trending::*,
+    };
+    use routes::{
+        admin::*, auth::*, bookmark::*, browse::*, chapter::*, novel::*, oauth::*, search::*,
+        settings::*, shelf::*, tag::*, timeline::*, user::*,
+    };
 
     _ = ServerLogin::register();
     _ = ServerRegister::register();
+    _ = GetRegistrationMode::register();
     _ = UserValidate::register();
     _ = Logout::register();
     _ = CreateNovel::register();
@@ -34,4 +40,63 @@ pub fn register_server_functions() {
     _ = GetUsername::register();
     _ = CreateChapter::register();
     _ = GetChapters::register();
+    _ = GetAppInfo::register();
+    _ = AuthorizeApp::register();
+    _ = ChangePassword::register();
+    _ = GetInvites::register();
+    _ = CreateInvite::register();
+    _ = DeleteInvite::register();
+    _ = GetTokens::register();
+    _ = CreateToken::register();
+    _ = RevokeToken::register();
+    _ = GetSessions::register();
+    _ = RevokeSession::register();
+    _ = RevokeOtherSessions::register();
+    _ = GetNotices::register();
+    _ = DismissNotice::register();
+    _ = GetFilters::register();
+    _ = AddFilter::register();
+    _ = RemoveFilter::register();
+    _ = IsStaff::register();
+    _ = GetAdminOverview::register();
+    _ = GetPendingUsers::register();
+    _ = RestrictActor::register();
+    _ = HideNovel::register();
+    _ = ReviewRegistration::register();
+    _ = SetRegistrationMode::register();
+    _ = CreateReport::register();
+    _ = GetReports::register();
+    _ = CloseReport::register();
+    _ = GetDomainPolicies::register();
+    _ = SetDomainPolicy::register();
+    _ = RemoveDomainPolicy::register();
+    _ = SetAllowlistMode::register();
+    _ = GetModerationLog::register();
+    _ = GetSearchResults::register();
+    _ = GetProfile::register();
+    _ = GetTagPage::register();
+    _ = GetBrowsePage::register();
+    _ = GetTimeline::register();
+    _ = IsFollowing::register();
+    _ = SetFollow::register();
+    _ = GetTrending::register();
+    _ = GetTrendingCandidates::register();
+    _ = ReviewTrending::register();
+    _ = GetNovelShelves::register();
+    _ = SetShelfNovel::register();
+    _ = GetShelves::register();
+    _ = CreateShelf::register();
+    _ = SetShelfPublic::register();
+    _ = DeleteShelf::register();
+    _ = GetShelf::register();
+    _ = GetUnreadNovels::register();
+    _ = GetNovelProgress::register();
+    _ = MarkChapterRead::register();
+    _ = SetReadingPosition::register();
+    _ = GetChapterPage::register();
+    _ = GetChapterBookmarks::register();
+    _ = AddBookmark::register();
+    _ = DeleteBookmark::register();
+    _ = GetBookmarks::register();
+    _ = SetBookmarkNote::register();
 }
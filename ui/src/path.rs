@@ -6,7 +6,40 @@ pub struct NovelViewParams {
     pub uuid: String,
 }
 
+#[derive(Params, Debug, PartialEq, Clone)]
+pub struct UserViewParams {
+    pub name: String,
+}
+
+#[derive(Params, Debug, PartialEq, Clone)]
+pub struct TagViewParams {
+    pub tag: String,
+}
+
 #[derive(Params, Debug, PartialEq, Clone)]
 pub struct AuthQueries {
     pub redirect_to: String,
+    pub invite: Option<String>,
+}
+
+#[derive(Params, Debug, PartialEq, Clone)]
+pub struct AuthorizeQueries {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    pub code_challenge_method: Option<String>,
+}
+
+#[derive(Params, Debug, PartialEq, Clone)]
+pub struct ShelfViewParams {
+    pub id: String,
+}
+
+#[derive(Params, Debug, PartialEq, Clone)]
+pub struct ChapterViewParams {
+    pub uuid: String,
+    pub chapter: String,
 }
@@ -0,0 +1,1764 @@
+use crate::{
+    components::{basicinput::*, errorview::*, listbox::*},
+    routes::auth::get_registration_mode,
+};
+use leptos::*;
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdminOverview {
+    stats: Vec<(String, i64)>,
+    users: Vec<UserItem>,
+    novels: Vec<NovelItem>,
+    instances: Vec<InstanceItem>,
+    deliveries: Option<DeliveryInfo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserItem {
+    apub_id: String,
+    username: String,
+    name: String,
+    local: bool,
+    role: String,
+    suspended: bool,
+    silenced: bool,
+    force_sensitive: bool,
+    published: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NovelItem {
+    apub_id: String,
+    title: String,
+    local: bool,
+    hidden: bool,
+    published: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InstanceItem {
+    domain: String,
+    users: i64,
+    novels: i64,
+    policy: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeliveryInfo {
+    queued: i64,
+    failed: i64,
+    failures: Vec<(String, String, String, String)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingItem {
+    apub_id: String,
+    username: String,
+    email: String,
+    reason: Option<String>,
+    published: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReportItem {
+    id: String,
+    reporter: String,
+    target: String,
+    kind: String,
+    reason: String,
+    remote: bool,
+    forwarded: bool,
+    note: Option<String>,
+    resolved_by: Option<String>,
+    published: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DomainPolicyItem {
+    domain: String,
+    policy: String,
+    reason: Option<String>,
+    published: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogItem {
+    actor: String,
+    action: String,
+    target: String,
+    reason: Option<String>,
+    published: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrendingItem {
+    kind: String,
+    target: String,
+    name: String,
+    /// Where the novel or tag is shown on this instance.
+    path: String,
+    approved: Option<bool>,
+}
+
+const LOG_ACTIONS: [&str; 19] = [
+    "suspend_user",
+    "unsuspend_user",
+    "silence_actor",
+    "unsilence_actor",
+    "force_sensitive",
+    "unforce_sensitive",
+    "hide_novel",
+    "unhide_novel",
+    "set_role",
+    "approve_registration",
+    "reject_registration",
+    "set_registration_mode",
+    "set_domain_policy",
+    "remove_domain_policy",
+    "set_allowlist_mode",
+    "resolve_report",
+    "dismiss_report",
+    "approve_trending",
+    "hide_trending",
+];
+
+/// Picks a restriction to apply to or lift from an actor, offering only the
+/// ones that change its current state.
+#[component]
+fn RestrictionSelect(
+    cx: Scope,
+    suspended: bool,
+    silenced: bool,
+    force_sensitive: bool,
+) -> impl IntoView {
+    let options = [
+        if suspended {
+            ("unsuspend", "Unsuspend")
+        } else {
+            ("suspend", "Suspend")
+        },
+        if silenced {
+            ("unsilence", "Unsilence")
+        } else {
+            ("silence", "Silence")
+        },
+        if force_sensitive {
+            ("unforce_sensitive", "Stop forcing sensitive")
+        } else {
+            ("force_sensitive", "Force sensitive")
+        },
+    ];
+
+    view! { cx,
+        <select name="action" class="rounded-md px-2 py-1 dark:bg-gray-900">
+            {options
+                .into_iter()
+                .map(|(value, label)| view! { cx, <option value=value>{label}</option> })
+                .collect_view(cx)}
+        </select>
+    }
+}
+
+#[component]
+fn AdminNav(cx: Scope) -> impl IntoView {
+    view! { cx,
+        <nav class="flex flex-row gap-2 overflow-x-auto whitespace-nowrap p-2 text-xl md:text-base">
+            <A
+                href="/admin"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Overview"
+            </A>
+            <A
+                href="/admin/reports"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Reports"
+            </A>
+            <A
+                href="/admin/federation"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Federation"
+            </A>
+            <A
+                href="/admin/trending"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Trending"
+            </A>
+            <A
+                href="/admin/log"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Log"
+            </A>
+        </nav>
+    }
+}
+
+#[component]
+pub fn AdminDashboard(cx: Scope) -> impl IntoView {
+    let suspend = create_server_action::<RestrictActor>(cx);
+    let hide = create_server_action::<HideNovel>(cx);
+    let review = create_server_action::<ReviewRegistration>(cx);
+    let set_mode = create_server_action::<SetRegistrationMode>(cx);
+    let overview = create_resource(
+        cx,
+        move || {
+            (
+                suspend.version().get(),
+                hide.version().get(),
+                review.version().get(),
+            )
+        },
+        move |_| get_admin_overview(cx),
+    );
+    let pending = create_resource(
+        cx,
+        move || review.version().get(),
+        move |_| get_pending_users(cx),
+    );
+    let mode = create_resource(
+        cx,
+        move || set_mode.version().get(),
+        move |_| get_registration_mode(cx),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let results = move || {
+        [
+            suspend.value().get(),
+            hide.value().get(),
+            review.value().get(),
+            set_mode.value().get(),
+        ]
+        .into_iter()
+        .flatten()
+        .for_each(|v| match v {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    let spinner = move || {
+        view! { cx,
+            <Icon
+                icon=CgIcon::CgSpinner
+                class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+            />
+        }
+    };
+
+    view! { cx,
+        <Title text="Admin"/>
+        <div class="mx-auto max-w-4xl px-4">
+            <AdminNav/>
+            <h1 class="p-2 text-3xl">"Admin"</h1>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {results}
+            </div>
+            <Transition fallback=spinner>
+                {move || {
+                    overview
+                        .read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("admin overview: {}", e.to_string());
+                                view! { cx, <p>"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => {
+                                view! { cx, <p>{e}</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(overview)) => {
+                                view! { cx,
+                                    <div class="grid grid-cols-2 md:grid-cols-3 gap-2 p-2">
+                                        {overview
+                                            .stats
+                                            .into_iter()
+                                            .map(|(label, value)| {
+                                                view! { cx,
+                                                    <div class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                        <span class="text-3xl font-bold">{value}</span>
+                                                        <span class="text-sm dark:text-gray-400">{label}</span>
+                                                    </div>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </div>
+                                    <h2 class="p-2 text-2xl">"Users"</h2>
+                                    <ActionForm action=suspend class="flex flex-row flex-wrap gap-2 p-2">
+                                        <input
+                                            type="url"
+                                            name="account"
+                                            placeholder="Actor URL of any account"
+                                            class="grow rounded-md px-2 py-1 dark:bg-gray-800"
+                                            required
+                                        />
+                                        <RestrictionSelect suspended=false silenced=false force_sensitive=false/>
+                                        <input
+                                            type="text"
+                                            name="reason"
+                                            placeholder="Reason"
+                                            class="w-32 rounded-md px-2 py-1 dark:bg-gray-800"
+                                        />
+                                        <input
+                                            type="submit"
+                                            class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                            value="Apply"
+                                        />
+                                    </ActionForm>
+                                    <ul class="flex flex-col gap-2 my-2">
+                                        {overview
+                                            .users
+                                            .into_iter()
+                                            .map(|u| {
+                                                view! { cx,
+                                                    <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                        <div class="flex flex-col">
+                                                            <span class="font-bold">{u.name}</span>
+                                                            <a
+                                                                href=u.apub_id.clone()
+                                                                class="text-sm dark:text-gray-400 break-all"
+                                                            >
+                                                                {u.username}
+                                                            </a>
+                                                            <span class="text-sm dark:text-gray-500">
+                                                                {format!(
+                                                                    "{}, {}, joined {}", if u.local { "Local" } else {
+                                                                    "Remote" }, u.role, u.published
+                                                                )}
+                                                            </span>
+                                                            <span class="flex flex-row gap-1 text-sm">
+                                                                {[
+                                                                    (u.suspended, "suspended"),
+                                                                    (u.silenced, "silenced"),
+                                                                    (u.force_sensitive, "sensitive"),
+                                                                ]
+                                                                    .into_iter()
+                                                                    .filter(|(on, _)| *on)
+                                                                    .map(|(_, label)| {
+                                                                        view! { cx,
+                                                                            <span class="rounded-full px-2 dark:bg-red-900">{label}</span>
+                                                                        }
+                                                                    })
+                                                                    .collect_view(cx)}
+                                                            </span>
+                                                        </div>
+                                                        <ActionForm action=suspend class="flex flex-row gap-2 my-auto">
+                                                            <input type="hidden" name="account" value=u.apub_id/>
+                                                            <RestrictionSelect
+                                                                suspended=u.suspended
+                                                                silenced=u.silenced
+                                                                force_sensitive=u.force_sensitive
+                                                            />
+                                                            <input
+                                                                type="text"
+                                                                name="reason"
+                                                                placeholder="Reason"
+                                                                class="w-32 rounded-md px-2 py-1 dark:bg-gray-900"
+                                                            />
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                                value="Apply"
+                                                            />
+                                                        </ActionForm>
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </ul>
+                                    <h2 class="p-2 text-2xl">"Novels"</h2>
+                                    <ul class="flex flex-col gap-2 my-2">
+                                        {overview
+                                            .novels
+                                            .into_iter()
+                                            .map(|n| {
+                                                view! { cx,
+                                                    <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                        <div class="flex flex-col">
+                                                            <a href=n.apub_id.clone() class="font-bold">
+                                                                {n.title}
+                                                            </a>
+                                                            <span class="text-sm dark:text-gray-500">
+                                                                {format!(
+                                                                    "{}, created {}", if n.local { "Local" } else { "Remote"
+                                                                    }, n.published
+                                                                )}
+                                                            </span>
+                                                        </div>
+                                                        <ActionForm action=hide class="flex flex-row gap-2 my-auto">
+                                                            <input type="hidden" name="novel" value=n.apub_id/>
+                                                            <input
+                                                                type="text"
+                                                                name="reason"
+                                                                placeholder="Reason"
+                                                                class="w-32 rounded-md px-2 py-1 dark:bg-gray-900"
+                                                            />
+                                                            <input
+                                                                type="hidden"
+                                                                name="hidden"
+                                                                value=(!n.hidden).to_string()
+                                                            />
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                                                value=if n.hidden { "Unhide" } else { "Hide" }
+                                                            />
+                                                        </ActionForm>
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </ul>
+                                    <h2 class="p-2 text-2xl">"Known instances"</h2>
+                                    <ul class="flex flex-col gap-2 my-2">
+                                        {overview
+                                            .instances
+                                            .into_iter()
+                                            .map(|i| {
+                                                view! { cx,
+                                                    <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                        <span class="font-bold">{i.domain}</span>
+                                                        <span class="text-sm dark:text-gray-400">
+                                                            {format!("{} users, {} novels", i.users, i.novels)}
+                                                        </span>
+                                                        {i
+                                                            .policy
+                                                            .map(|p| {
+                                                                view! { cx,
+                                                                    <span class="rounded-full px-2 text-sm dark:bg-gray-700">{p}</span>
+                                                                }
+                                                            })}
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </ul>
+                                    {overview
+                                        .deliveries
+                                        .map(|d| {
+                                            view! { cx,
+                                                <h2 class="p-2 text-2xl">"Deliveries"</h2>
+                                                <p class="px-2 dark:text-gray-400">
+                                                    {format!(
+                                                        "{} queued and {} couldn't be queued in the last 24 hours. Queued deliveries are retried in the background.",
+                                                        d.queued, d.failed
+                                                    )}
+                                                </p>
+                                                <ul class="flex flex-col gap-2 my-2">
+                                                    {d
+                                                        .failures
+                                                        .into_iter()
+                                                        .map(|(kind, inboxes, error, published)| {
+                                                            view! { cx,
+                                                                <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                                    <span class="font-bold break-all">
+                                                                        {format!("{kind} to {inboxes} inbox(es)")}
+                                                                    </span>
+                                                                    <span class="text-sm dark:text-gray-400 break-all">{error}</span>
+                                                                    <span class="text-sm dark:text-gray-500">{published}</span>
+                                                                </li>
+                                                            }
+                                                        })
+                                                        .collect_view(cx)}
+                                                </ul>
+                                            }
+                                        })}
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+            <h2 class="p-2 text-2xl">"Registrations"</h2>
+            <Transition fallback=|| ()>
+                {move || {
+                    mode.read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("registration mode: {}", e.to_string());
+                                ().into_view(cx)
+                            }
+                            Ok(current) => {
+                                view! { cx,
+                                    <div class="flex flex-row flex-wrap gap-2 p-2">
+                                        {["open", "approval", "invite", "closed"]
+                                            .into_iter()
+                                            .map(|m| {
+                                                let class = if m == current {
+                                                    "rounded-full px-3 py-1 dark:bg-purple-600"
+                                                } else {
+                                                    "rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700 cursor-pointer"
+                                                };
+                                                view! { cx,
+                                                    <ActionForm action=set_mode>
+                                                        <input type="hidden" name="mode" value=m/>
+                                                        <input type="submit" class=class value=m/>
+                                                    </ActionForm>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </div>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=|| ()>
+                    {move || {
+                        pending
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("pending users: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(users)) if users.is_empty() => {
+                                    view! { cx,
+                                        <li class="px-2 dark:text-gray-400">"No pending registrations"</li>
+                                    }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(users)) => {
+                                    users
+                                        .into_iter()
+                                        .map(|u| {
+                                            let account = u.apub_id.clone();
+                                            view! { cx,
+                                                <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col">
+                                                        <span class="font-bold">{u.username}</span>
+                                                        <span class="text-sm dark:text-gray-400">{u.email}</span>
+                                                        {u
+                                                            .reason
+                                                            .map(|r| {
+                                                                view! { cx, <span class="text-sm">{r}</span> }
+                                                            })}
+                                                        <span class="text-sm dark:text-gray-500">{u.published}</span>
+                                                    </div>
+                                                    <div class="flex flex-row gap-2 my-auto">
+                                                        <ActionForm action=review>
+                                                            <input type="hidden" name="account" value=account/>
+                                                            <input type="hidden" name="approve" value="true"/>
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-purple-600 hover:dark:bg-purple-700 rounded-md px-3 py-1 cursor-pointer"
+                                                                value="Approve"
+                                                            />
+                                                        </ActionForm>
+                                                        <ActionForm action=review>
+                                                            <input type="hidden" name="account" value=u.apub_id/>
+                                                            <input type="hidden" name="approve" value="false"/>
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                                value="Reject"
+                                                            />
+                                                        </ActionForm>
+                                                    </div>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[component]
+pub fn ReportQueue(cx: Scope) -> impl IntoView {
+    let close = create_server_action::<CloseReport>(cx);
+    let (status, set_status) = create_signal(cx, "open".to_string());
+    let reports = create_resource(
+        cx,
+        move || (status(), close.version().get()),
+        move |(status, _)| get_reports(cx, status),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let response = close.value();
+    let result = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(_)) => set_errormsg(String::new()),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Title text="Reports"/>
+        <div class="mx-auto max-w-4xl px-4">
+            <AdminNav/>
+            <h1 class="p-2 text-3xl">"Reports"</h1>
+            <div class="flex flex-row gap-2 p-2">
+                {["open", "resolved", "dismissed"]
+                    .into_iter()
+                    .map(|s| {
+                        view! { cx,
+                            <button
+                                class=move || {
+                                    if status() == s {
+                                        "rounded-full px-3 py-1 dark:bg-purple-600"
+                                    } else {
+                                        "rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+                                    }
+                                }
+                                on:click=move |_| set_status(s.to_string())
+                            >
+                                {s}
+                            </button>
+                        }
+                    })
+                    .collect_view(cx)}
+            </div>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {result}
+            </div>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        reports
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("reports: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(reports)) if reports.is_empty() => {
+                                    view! { cx, <li class="px-2 dark:text-gray-400">"No reports"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(reports)) => {
+                                    reports
+                                        .into_iter()
+                                        .map(|r| {
+                                            let action = create_rw_signal(cx, "resolved");
+                                            let open = status() == "open";
+                                            view! { cx,
+                                                <li class="flex flex-col gap-1 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-row flex-wrap gap-2">
+                                                        <span class="rounded-full px-2 text-sm dark:bg-gray-700">{r.kind}</span>
+                                                        {r
+                                                            .remote
+                                                            .then(|| {
+                                                                view! { cx,
+                                                                    <span class="rounded-full px-2 text-sm dark:bg-gray-700">
+                                                                        "From another instance"
+                                                                    </span>
+                                                                }
+                                                            })}
+                                                        {r
+                                                            .forwarded
+                                                            .then(|| {
+                                                                view! { cx,
+                                                                    <span class="rounded-full px-2 text-sm dark:bg-gray-700">
+                                                                        "Forwarded"
+                                                                    </span>
+                                                                }
+                                                            })}
+                                                    </div>
+                                                    <a href=r.target.clone() class="font-bold break-all">
+                                                        {r.target}
+                                                    </a>
+                                                    <p>{r.reason}</p>
+                                                    <span class="text-sm dark:text-gray-500 break-all">
+                                                        {format!("Reported by {} {}", r.reporter, r.published)}
+                                                    </span>
+                                                    {r
+                                                        .resolved_by
+                                                        .map(|m| {
+                                                            view! { cx,
+                                                                <span class="text-sm dark:text-gray-400 break-all">
+                                                                    {format!("Closed by {m}")}
+                                                                </span>
+                                                            }
+                                                        })}
+                                                    {r
+                                                        .note
+                                                        .map(|n| {
+                                                            view! { cx, <p class="text-sm italic dark:text-gray-400">{n}</p> }
+                                                        })}
+                                                    {open
+                                                        .then(|| {
+                                                            view! { cx,
+                                                                <ActionForm action=close class="flex flex-col gap-2 w-full">
+                                                                    <input type="hidden" name="id" value=r.id/>
+                                                                    <input type="hidden" name="status" value=action/>
+                                                                    <div class="relative">
+                                                                        <textarea
+                                                                            class="basic-input peer"
+                                                                            placeholder=" "
+                                                                            name="note"
+                                                                        ></textarea>
+                                                                        <FloatingLabel target="note">"Note"</FloatingLabel>
+                                                                    </div>
+                                                                    <div class="flex flex-row gap-2">
+                                                                        <input
+                                                                            type="submit"
+                                                                            class="dark:bg-purple-600 hover:dark:bg-purple-700 rounded-md px-3 py-1 cursor-pointer"
+                                                                            value="Resolve"
+                                                                            on:click=move |_| action.set("resolved")
+                                                                        />
+                                                                        <input
+                                                                            type="submit"
+                                                                            class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                                                            value="Dismiss"
+                                                                            on:click=move |_| action.set("dismissed")
+                                                                        />
+                                                                    </div>
+                                                                </ActionForm>
+                                                            }
+                                                        })}
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[component]
+pub fn FederationSettings(cx: Scope) -> impl IntoView {
+    let set_policy = create_server_action::<SetDomainPolicy>(cx);
+    let remove_policy = create_server_action::<RemoveDomainPolicy>(cx);
+    let set_allowlist = create_server_action::<SetAllowlistMode>(cx);
+    let policies = create_resource(
+        cx,
+        move || {
+            (
+                set_policy.version().get(),
+                remove_policy.version().get(),
+                set_allowlist.version().get(),
+            )
+        },
+        move |_| get_domain_policies(cx),
+    );
+    let policy = create_rw_signal(cx, "suspend".to_string());
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let results = move || {
+        [
+            set_policy.value().get(),
+            remove_policy.value().get(),
+            set_allowlist.value().get(),
+        ]
+        .into_iter()
+        .flatten()
+        .for_each(|v| match v {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Title text="Federation"/>
+        <div class="mx-auto max-w-4xl px-4">
+            <AdminNav/>
+            <h1 class="p-2 text-3xl">"Federation"</h1>
+            <ActionForm action=set_policy class="space-y-4 p-2 w-full">
+                <div class="relative">
+                    <input type="text" class="basic-input peer" placeholder=" " name="domain" required/>
+                    <FloatingLabel target="domain">"Domain"</FloatingLabel>
+                </div>
+                <input type="hidden" name="policy" value=move || policy.get()/>
+                <FilterListbox
+                    option=policy
+                    name="policy"
+                    label="Policy"
+                    initial="suspend"
+                    items=vec!["allow".to_string(), "silence".to_string(), "suspend".to_string()]
+                />
+                <div class="relative">
+                    <input type="text" class="basic-input peer" placeholder=" " name="reason"/>
+                    <FloatingLabel target="reason">"Reason"</FloatingLabel>
+                </div>
+                <input type="submit" class="button-1" value="Save policy"/>
+            </ActionForm>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {results}
+            </div>
+            <Transition fallback=move || {
+                view! { cx,
+                    <Icon
+                        icon=CgIcon::CgSpinner
+                        class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                    />
+                }
+            }>
+                {move || {
+                    policies
+                        .read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("domain policies: {}", e.to_string());
+                                view! { cx, <p>"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => {
+                                view! { cx, <p>{e}</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok((allowlist, policies))) => {
+                                view! { cx,
+                                    <div class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2 my-2">
+                                        <div class="flex flex-col">
+                                            <span class="font-bold">
+                                                {if allowlist { "Allowlist mode" } else { "Open federation" }}
+                                            </span>
+                                            <span class="text-sm dark:text-gray-400">
+                                                {if allowlist {
+                                                    "Only domains with the allow policy can federate with this instance."
+                                                } else {
+                                                    "Every domain that isn't suspended can federate with this instance."
+                                                }}
+                                            </span>
+                                        </div>
+                                        <ActionForm action=set_allowlist class="my-auto">
+                                            <input type="hidden" name="enabled" value=(!allowlist).to_string()/>
+                                            <input
+                                                type="submit"
+                                                class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                                value=if allowlist { "Disable" } else { "Enable allowlist" }
+                                            />
+                                        </ActionForm>
+                                    </div>
+                                    <ul class="flex flex-col gap-2 my-2">
+                                        {policies
+                                            .into_iter()
+                                            .map(|p| {
+                                                view! { cx,
+                                                    <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                        <div class="flex flex-col">
+                                                            <span class="font-bold">{p.domain.clone()}</span>
+                                                            <span class="text-sm dark:text-gray-400">{p.policy}</span>
+                                                            {p
+                                                                .reason
+                                                                .map(|r| {
+                                                                    view! { cx, <span class="text-sm">{r}</span> }
+                                                                })}
+                                                            <span class="text-sm dark:text-gray-500">{p.published}</span>
+                                                        </div>
+                                                        <ActionForm action=remove_policy class="my-auto">
+                                                            <input type="hidden" name="domain" value=p.domain/>
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                                value="Remove"
+                                                            />
+                                                        </ActionForm>
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view(cx)}
+                                    </ul>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+pub fn ModerationLog(cx: Scope) -> impl IntoView {
+    let query = use_query_map(cx);
+    let param = move |key: &str| query.with(|q| q.get(key).cloned().unwrap_or_default());
+    let entries = create_resource(
+        cx,
+        move || (param("action"), param("actor"), param("target")),
+        move |(action, actor, target)| get_moderation_log(cx, action, actor, target),
+    );
+    let search = use_location(cx).search;
+    let export = move || {
+        format!(
+            "/api/v1/admin/log/export?{}",
+            search().trim_start_matches('?')
+        )
+    };
+
+    view! { cx,
+        <Title text="Moderation log"/>
+        <div class="mx-auto max-w-4xl px-4">
+            <AdminNav/>
+            <h1 class="p-2 text-3xl">"Moderation log"</h1>
+            <Form method="GET" action="" class="flex flex-row flex-wrap gap-2 p-2">
+                <select name="action" class="rounded-md px-2 py-1 dark:bg-gray-800">
+                    <option value="" selected=move || param("action").is_empty()>
+                        "All actions"
+                    </option>
+                    {LOG_ACTIONS
+                        .into_iter()
+                        .map(|a| {
+                            view! { cx,
+                                <option value=a selected=move || param("action") == a>
+                                    {a.replace('_', " ")}
+                                </option>
+                            }
+                        })
+                        .collect_view(cx)}
+                </select>
+                <input
+                    type="text"
+                    name="actor"
+                    placeholder="Moderator"
+                    class="rounded-md px-2 py-1 dark:bg-gray-800"
+                    prop:value=move || param("actor")
+                />
+                <input
+                    type="text"
+                    name="target"
+                    placeholder="Target"
+                    class="rounded-md px-2 py-1 dark:bg-gray-800"
+                    prop:value=move || param("target")
+                />
+                <input
+                    type="submit"
+                    class="dark:bg-purple-600 hover:dark:bg-purple-700 rounded-md px-3 py-1 cursor-pointer"
+                    value="Filter"
+                />
+                <a
+                    href=export
+                    rel="external"
+                    class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1"
+                >
+                    "Export CSV"
+                </a>
+            </Form>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        entries
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("moderation log: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(entries)) if entries.is_empty() => {
+                                    view! { cx, <li class="p-2">"Nothing has been logged yet"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(entries)) => {
+                                    entries
+                                        .into_iter()
+                                        .map(|e| {
+                                            view! { cx,
+                                                <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <span>
+                                                        <span class="font-bold">{e.actor}</span>
+                                                        " "
+                                                        {e.action.replace('_', " ")}
+                                                        " "
+                                                        <span class="font-bold break-all">{e.target}</span>
+                                                    </span>
+                                                    {e
+                                                        .reason
+                                                        .map(|r| {
+                                                            view! { cx, <span class="text-sm dark:text-gray-400">{r}</span> }
+                                                        })}
+                                                    <span class="text-sm dark:text-gray-500">{e.published}</span>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[component]
+pub fn TrendingReview(cx: Scope) -> impl IntoView {
+    let review = create_server_action::<ReviewTrending>(cx);
+    let candidates = create_resource(
+        cx,
+        move || review.version().get(),
+        move |_| get_trending_candidates(cx),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let reviewed = move || {
+        review.value().get().map(|v| match v {
+            Ok(Ok(_)) => set_errormsg(String::new()),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Title text="Trending"/>
+        <div class="mx-auto max-w-4xl px-4">
+            <AdminNav/>
+            <h1 class="p-2 text-3xl">"Trending"</h1>
+            <p class="p-2 dark:text-gray-400">
+                "Novels and tags only show up as trending once they are approved."
+            </p>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {reviewed}
+            </div>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        candidates
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("trending candidates: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(items)) if items.is_empty() => {
+                                    view! { cx, <li class="p-2">"Nothing is trending right now"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(items)) => {
+                                    items
+                                        .into_iter()
+                                        .map(|t| {
+                                            let status = match t.approved {
+                                                None => "Pending",
+                                                Some(true) => "Approved",
+                                                Some(false) => "Hidden",
+                                            };
+                                            view! { cx,
+                                                <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col">
+                                                        <A href=t.path class="font-bold hover:underline">
+                                                            {t.name}
+                                                        </A>
+                                                        <span class="text-sm dark:text-gray-400">
+                                                            {t.kind.clone()} " · " {status}
+                                                        </span>
+                                                    </div>
+                                                    <ActionForm action=review class="flex flex-row gap-2 my-auto">
+                                                        <input type="hidden" name="kind" value=t.kind/>
+                                                        <input type="hidden" name="target" value=t.target/>
+                                                        <input
+                                                            type="hidden"
+                                                            name="approved"
+                                                            value=(t.approved != Some(true)).to_string()
+                                                        />
+                                                        <input
+                                                            type="text"
+                                                            name="reason"
+                                                            placeholder="Reason"
+                                                            class="rounded-md px-2 py-1 dark:bg-gray-700"
+                                                        />
+                                                        <input
+                                                            type="submit"
+                                                            class=if t.approved == Some(true) {
+                                                                "dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                            } else {
+                                                                "dark:bg-purple-600 hover:dark:bg-purple-700 rounded-md px-3 py-1 cursor-pointer"
+                                                            }
+                                                            value=if t.approved == Some(true) { "Hide" } else { "Approve" }
+                                                        />
+                                                    </ActionForm>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[server(IsStaff, "/server")]
+pub async fn is_staff(cx: Scope) -> Result<bool, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{admin, enums::InstanceRole, DbHandle};
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    Ok(
+        admin::staff(pool.app_data(), &session, InstanceRole::Moderator)
+            .await
+            .is_ok(),
+    )
+}
+
+#[server(GetAdminOverview, "/server")]
+pub async fn get_admin_overview(cx: Scope) -> Result<Result<AdminOverview, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+    let pool = pool.app_data();
+
+    let stats = match admin::stats(pool, &session).await {
+        Ok(s) => s,
+        Err(AdminError::InternalServerError(e)) => return Err(ServerFnError::ServerError(e)),
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+    let users = admin::recent_users(pool, &session, 20)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+        .into_iter()
+        .map(|u| UserItem {
+            apub_id: u.apub_id,
+            username: u.preferred_username,
+            name: u.name,
+            local: u.local,
+            role: u.role,
+            suspended: u.suspended,
+            silenced: u.silenced,
+            force_sensitive: u.force_sensitive,
+            published: HumanTime::from(u.published).to_string(),
+        })
+        .collect();
+    let novels = admin::recent_novels(pool, &session, 20)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+        .into_iter()
+        .map(|n| NovelItem {
+            apub_id: n.apub_id,
+            title: n.title,
+            local: n.local,
+            hidden: n.hidden,
+            published: HumanTime::from(n.published).to_string(),
+        })
+        .collect();
+    let instances = admin::known_instances(pool, &session)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+        .into_iter()
+        .map(|i| InstanceItem {
+            domain: i.domain,
+            users: i.users,
+            novels: i.novels,
+            policy: i.policy,
+        })
+        .collect();
+    // Delivery state is only shown to admins.
+    let deliveries = match admin::delivery_stats(pool, &session).await {
+        Ok(d) => Some(DeliveryInfo {
+            queued: d.queued,
+            failed: d.failed,
+            failures: d
+                .recent_failures
+                .into_iter()
+                .map(|f| {
+                    (
+                        f.kind,
+                        f.inboxes.to_string(),
+                        f.error,
+                        HumanTime::from(f.published).to_string(),
+                    )
+                })
+                .collect(),
+        }),
+        Err(AdminError::Forbidden(_)) => None,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+    };
+
+    Ok(Ok(AdminOverview {
+        stats: vec![
+            ("Local users".to_string(), stats.local_users),
+            ("Remote users".to_string(), stats.remote_users),
+            ("Pending users".to_string(), stats.pending_users),
+            ("Local novels".to_string(), stats.local_novels),
+            ("Remote novels".to_string(), stats.remote_novels),
+            ("Known instances".to_string(), stats.instances),
+        ],
+        users,
+        novels,
+        instances,
+        deliveries,
+    }))
+}
+
+#[server(GetPendingUsers, "/server")]
+pub async fn get_pending_users(
+    cx: Scope,
+) -> Result<Result<Vec<PendingItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        enums::InstanceRole,
+        instance, DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    match admin::staff(pool.app_data(), &session, InstanceRole::Moderator).await {
+        Ok(_) => (),
+        Err(AdminError::InternalServerError(e)) => return Err(ServerFnError::ServerError(e)),
+        Err(e) => return Ok(Err(e.to_string())),
+    }
+
+    let users = instance::pending_users(pool.app_data())
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+        .into_iter()
+        .map(|u| PendingItem {
+            apub_id: u.apub_id,
+            username: u.preferred_username,
+            email: u.email,
+            reason: u.reason,
+            published: HumanTime::from(u.published).to_string(),
+        })
+        .collect();
+    Ok(Ok(users))
+}
+
+#[server(RestrictActor, "/server")]
+pub async fn restrict_actor(
+    cx: Scope,
+    account: String,
+    action: String,
+    reason: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        enums::ActorRestriction,
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let (restriction, enabled) = match action.strip_prefix("un") {
+        Some(r) => (ActorRestriction::from_str(r), false),
+        None => (ActorRestriction::from_str(&action), true),
+    };
+    let restriction = match restriction {
+        Ok(r) => r,
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+
+    match admin::restrict_actor(
+        pool.app_data(),
+        &session,
+        &account,
+        restriction,
+        enabled,
+        Some(reason),
+    )
+    .await
+    {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(HideNovel, "/server")]
+pub async fn hide_novel(
+    cx: Scope,
+    novel: String,
+    hidden: bool,
+    reason: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    match admin::hide_novel(pool.app_data(), &session, &novel, hidden, Some(reason)).await {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(ReviewRegistration, "/server")]
+pub async fn review_registration(
+    cx: Scope,
+    account: String,
+    approve: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    match admin::review_registration(pool.app_data(), &session, &account, approve, None).await {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(SetRegistrationMode, "/server")]
+pub async fn set_registration_mode(
+    cx: Scope,
+    mode: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        enums::RegistrationMode,
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let mode = match RegistrationMode::from_str(&mode) {
+        Ok(m) => m,
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+
+    match admin::set_registration_mode(pool.app_data(), &session, mode).await {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(GetReports, "/server")]
+pub async fn get_reports(
+    cx: Scope,
+    status: String,
+) -> Result<Result<Vec<ReportItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        enums::ReportStatus,
+        report::{self, ReportError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let status =
+        ReportStatus::from_str(&status).map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+
+    match report::list_reports(pool.app_data(), &session, status).await {
+        Ok(reports) => Ok(Ok(reports
+            .into_iter()
+            .map(|r| ReportItem {
+                id: r.id.to_string(),
+                reporter: r.reporter,
+                target: r.target,
+                kind: r.target_kind.to_string(),
+                reason: r.reason,
+                remote: r.remote,
+                forwarded: r.forwarded,
+                note: r.note,
+                resolved_by: r.resolved_by,
+                published: HumanTime::from(r.published).to_string(),
+            })
+            .collect())),
+        Err(ReportError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(CloseReport, "/server")]
+pub async fn close_report(
+    cx: Scope,
+    id: String,
+    status: String,
+    note: Option<String>,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        enums::ReportStatus,
+        report::{self, ReportError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let id = id
+        .parse()
+        .map_err(|_| ServerFnError::ServerError("Invalid report".to_string()))?;
+    let status =
+        ReportStatus::from_str(&status).map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+
+    match report::close_report(pool.app_data(), &session, id, status, note).await {
+        Ok(_) => Ok(Ok(())),
+        Err(ReportError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(GetDomainPolicies, "/server")]
+pub async fn get_domain_policies(
+    cx: Scope,
+) -> Result<Result<(bool, Vec<DomainPolicyItem>), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        instance, DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let policies = match admin::domain_policies(pool.app_data(), &session).await {
+        Ok(p) => p,
+        Err(AdminError::InternalServerError(e)) => return Err(ServerFnError::ServerError(e)),
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+    let settings = instance::settings(pool.app_data())
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+
+    Ok(Ok((
+        settings.allowlist_mode,
+        policies
+            .into_iter()
+            .map(|p| DomainPolicyItem {
+                domain: p.domain,
+                policy: p.policy.to_string(),
+                reason: p.reason,
+                published: HumanTime::from(p.published).to_string(),
+            })
+            .collect(),
+    )))
+}
+
+#[server(SetDomainPolicy, "/server")]
+pub async fn set_domain_policy(
+    cx: Scope,
+    domain: String,
+    policy: String,
+    reason: Option<String>,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        enums::DomainPolicyKind,
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let policy = match DomainPolicyKind::from_str(&policy) {
+        Ok(p) => p,
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+
+    match admin::set_domain_policy(pool.app_data(), &session, &domain, policy, reason).await {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(RemoveDomainPolicy, "/server")]
+pub async fn remove_domain_policy(
+    cx: Scope,
+    domain: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    match admin::remove_domain_policy(pool.app_data(), &session, &domain).await {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(SetAllowlistMode, "/server")]
+pub async fn set_allowlist_mode(
+    cx: Scope,
+    enabled: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    match admin::set_allowlist_mode(pool.app_data(), &session, enabled).await {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(GetModerationLog, "/server")]
+pub async fn get_moderation_log(
+    cx: Scope,
+    action: String,
+    actor: String,
+    target: String,
+) -> Result<Result<Vec<LogItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        enums::ModAction,
+        moderation_log::LogFilter,
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let action = match action.as_str() {
+        "" => None,
+        a => match ModAction::from_str(a) {
+            Ok(a) => Some(a),
+            Err(e) => return Ok(Err(e.to_string())),
+        },
+    };
+    let filter = LogFilter {
+        action,
+        actor: Some(actor),
+        target: Some(target),
+        ..Default::default()
+    };
+
+    match admin::moderation_log(pool.app_data(), &session, filter, false).await {
+        Ok(entries) => Ok(Ok(entries
+            .into_iter()
+            .map(|e| LogItem {
+                actor: e.actor,
+                action: e.action.to_string(),
+                target: e.target,
+                reason: e.reason,
+                published: HumanTime::from(e.published).to_string(),
+            })
+            .collect())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(GetTrendingCandidates, "/server")]
+pub async fn get_trending_candidates(
+    cx: Scope,
+) -> Result<Result<Vec<TrendingItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    match admin::trending_candidates(pool.app_data(), &session).await {
+        Ok(candidates) => Ok(Ok(candidates
+            .into_iter()
+            .map(|c| TrendingItem {
+                path: c.path,
+                kind: c.kind.to_string(),
+                target: c.target,
+                name: c.name,
+                approved: c.approved,
+            })
+            .collect())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(ReviewTrending, "/server")]
+pub async fn review_trending(
+    cx: Scope,
+    kind: String,
+    target: String,
+    approved: bool,
+    reason: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        admin::{self, AdminError},
+        enums::TrendingKind,
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let kind = match TrendingKind::from_str(&kind) {
+        Ok(k) => k,
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+
+    match admin::review_trending(
+        pool.app_data(),
+        &session,
+        kind,
+        &target,
+        approved,
+        Some(reason),
+    )
+    .await
+    {
+        Ok(_) => Ok(Ok(())),
+        Err(AdminError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
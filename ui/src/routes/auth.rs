@@ -25,13 +25,14 @@ pub(crate) fn Auth(cx: Scope) -> impl IntoView {
                 "/".to_string()
             })
     };
+    let invite = move || query.with(|q| q.as_ref().ok().and_then(|q| q.invite.clone()));
 
     view! { cx,
         <Title text="Sign in or create an account"/>
         <div class="mx-auto w-full">
             <div class="flex flex-col md:flex-row w-full mx-auto max-w-3xl text-2xl m-4 justify-center text-center place-content-center items-center">
                 <Login redirect_to=path() set_errormsg=set_errormsg/>
-                <Register redirect_to=path() set_errormsg=set_errormsg/>
+                <Register redirect_to=path() invite=invite() set_errormsg=set_errormsg/>
             </div>
             <div class="flex mx-auto text-2xl m-4 justify-center text-center">
                 <ErrorView message=errormsg/>
@@ -90,24 +91,32 @@ pub async fn login(
 ) -> Result<Result<String, String>, ServerFnError> {
     use activitypub_federation::config::Data;
     use actix_session::Session;
+    use actix_web::{web, HttpRequest};
     use leptos_actix::extract;
     use wordforge_api::{
-        account::{self, FormAuthError, LoginError},
+        account::{self, ClientInfo, FormAuthError, LoginError},
+        util::{AppState, RedisHandle},
         DbHandle,
     };
 
-    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
-        (pool, session)
-    })
+    let (pool, redis, state, session, request) = extract(
+        cx,
+        |pool: Data<DbHandle>,
+         redis: web::Data<RedisHandle>,
+         state: web::Data<AppState>,
+         session: Session,
+         request: HttpRequest| async move { (pool, redis, state, session, request) },
+    )
     .await?;
 
     match account::login(
         pool.app_data().as_ref(),
+        &redis,
+        &state,
         session,
         email,
         password,
-        client_app,
-        client_website,
+        ClientInfo::new(&request, client_app, client_website, &state.trusted_proxies),
     )
     .await
     {
@@ -124,73 +133,168 @@ pub async fn login(
         }
         Err(LoginError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
         Err(LoginError::BadRequest(e)) => Ok(Err(e)),
+        Err(LoginError::TooManyRequests(retry_after)) => Ok(Err(format!(
+            "Too many failed attempts. Try again in {} minutes",
+            (retry_after + 59) / 60
+        ))),
         Err(LoginError::Unauthorized(FormAuthError::Email)) => {
             Ok(Err("Email not found".to_string()))
         }
         Err(LoginError::Unauthorized(FormAuthError::Password)) => {
             Ok(Err("Wrong password".to_string()))
         }
+        Err(LoginError::Unauthorized(FormAuthError::Credentials)) => {
+            Ok(Err("Invalid email or password".to_string()))
+        }
+        Err(LoginError::Unauthorized(FormAuthError::Pending)) => {
+            Ok(Err("Your account is awaiting approval".to_string()))
+        }
+        Err(LoginError::Unauthorized(FormAuthError::Suspended)) => {
+            Ok(Err("Your account has been suspended".to_string()))
+        }
         _ => unreachable!(),
     }
 }
 
 #[component]
-fn Register(cx: Scope, redirect_to: String, set_errormsg: WriteSignal<String>) -> impl IntoView {
+fn Register(
+    cx: Scope,
+    redirect_to: String,
+    invite: Option<String>,
+    set_errormsg: WriteSignal<String>,
+) -> impl IntoView {
     let register = create_server_action::<ServerRegister>(cx);
+    let mode = create_resource(cx, || (), move |_| get_registration_mode(cx));
+    let (notice, set_notice) = create_signal::<Option<String>>(cx, None);
     let response = register.value();
     let err = move || {
         response.get().map(|v| match v {
-            Ok(Ok(_)) => (),
+            Ok(Ok(v)) => set_notice(v),
             Ok(Err(v)) => set_errormsg(v),
             Err(e) => set_errormsg(e.to_string()),
         })
     };
 
+    let form = move |mode: String| {
+        let invite = invite.clone();
+        let redirect_to = redirect_to.clone();
+        view! { cx,
+            <ActionForm action=register class="space-y-4 p-4 w-full">
+                <div class="relative">
+                    <input
+                        type="text"
+                        class="basic-input peer"
+                        placeholder=" "
+                        name="display_name"
+                        required
+                    />
+                    <FloatingLabel target="display_name">"Display name"</FloatingLabel>
+                </div>
+                <div class="relative">
+                    <input
+                        type="text"
+                        class="basic-input peer"
+                        placeholder=" "
+                        name="username"
+                        required
+                    />
+                    <FloatingLabel target="username">"Username"</FloatingLabel>
+                </div>
+                <div class="relative">
+                    <input type="email" class="basic-input peer" placeholder=" " name="email" required/>
+                    <FloatingLabel target="email">"Email"</FloatingLabel>
+                </div>
+                <div class="relative">
+                    <input
+                        type="password"
+                        class="basic-input peer"
+                        placeholder=" "
+                        name="password"
+                        minlength=8
+                        required
+                    />
+                    <FloatingLabel target="password">"Password"</FloatingLabel>
+                </div>
+                {(mode == "approval" && invite.is_none())
+                    .then(|| {
+                        view! { cx,
+                            <div class="relative">
+                                <textarea
+                                    class="basic-input peer"
+                                    placeholder=" "
+                                    name="reason"
+                                    required
+                                ></textarea>
+                                <FloatingLabel target="reason">"Why do you want to join?"</FloatingLabel>
+                            </div>
+                        }
+                    })}
+                {(mode == "invite" || invite.is_some())
+                    .then(|| {
+                        view! { cx,
+                            <div class="relative">
+                                <input
+                                    type="text"
+                                    class="basic-input peer"
+                                    placeholder=" "
+                                    name="invite"
+                                    value=invite.clone().unwrap_or_default()
+                                    required
+                                />
+                                <FloatingLabel target="invite">"Invite code"</FloatingLabel>
+                            </div>
+                        }
+                    })}
+                <input type="hidden" name="client_app" value="Web"/>
+                <input type="hidden" name="redirect_to" value=redirect_to/>
+                <input type="submit" class="button-1" value="Sign up"/>
+                {err}
+            </ActionForm>
+        }
+    };
+
     view! { cx,
-        <ActionForm action=register class="space-y-4 p-4 w-full">
-            <div class="relative">
-                <input
-                    type="text"
-                    class="basic-input peer"
-                    placeholder=" "
-                    name="display_name"
-                    required
-                />
-                <FloatingLabel target="display_name">"Display name"</FloatingLabel>
-            </div>
-            <div class="relative">
-                <input
-                    type="text"
-                    class="basic-input peer"
-                    placeholder=" "
-                    name="username"
-                    required
-                />
-                <FloatingLabel target="username">"Username"</FloatingLabel>
-            </div>
-            <div class="relative">
-                <input type="email" class="basic-input peer" placeholder=" " name="email" required/>
-                <FloatingLabel target="email">"Email"</FloatingLabel>
-            </div>
-            <div class="relative">
-                <input
-                    type="password"
-                    class="basic-input peer"
-                    placeholder=" "
-                    name="password"
-                    minlength=8
-                    required
-                />
-                <FloatingLabel target="password">"Password"</FloatingLabel>
-            </div>
-            <input type="hidden" name="client_app" value="Web"/>
-            <input type="hidden" name="redirect_to" value=redirect_to/>
-            <input type="submit" class="button-1" value="Sign up"/>
-            {err}
-        </ActionForm>
+        <Suspense fallback=|| ()>
+            {move || {
+                mode.read(cx)
+                    .map(|v| match (v, notice()) {
+                        (_, Some(notice)) => {
+                            view! { cx, <p class="p-4 dark:text-gray-400">{notice}</p> }
+                                .into_view(cx)
+                        }
+                        (Ok(mode), None) if mode == "closed" => {
+                            view! { cx,
+                                <p class="p-4 dark:text-gray-400">
+                                    "This instance is not accepting new accounts."
+                                </p>
+                            }
+                                .into_view(cx)
+                        }
+                        (Ok(mode), None) => form(mode).into_view(cx),
+                        (Err(e), None) => {
+                            error!("registration mode: {}", e.to_string());
+                            form("open".to_string()).into_view(cx)
+                        }
+                    })
+            }}
+        </Suspense>
     }
 }
 
+#[server(GetRegistrationMode, "/server")]
+pub async fn get_registration_mode(cx: Scope) -> Result<String, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use leptos_actix::extract;
+    use wordforge_api::{instance, DbHandle};
+
+    let pool = extract(cx, |pool: Data<DbHandle>| async move { pool }).await?;
+
+    instance::settings(pool.app_data())
+        .await
+        .map(|s| s.registration_mode.to_string())
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
 #[server(ServerRegister, "/server")]
 pub async fn register(
     cx: Scope,
@@ -198,15 +302,17 @@ pub async fn register(
     username: String,
     email: String,
     password: String,
+    reason: Option<String>,
+    invite: Option<String>,
     client_app: String,
     client_website: Option<String>,
     redirect_to: String,
-) -> Result<Result<String, String>, ServerFnError> {
+) -> Result<Result<Option<String>, String>, ServerFnError> {
     use activitypub_federation::config::Data;
     use actix_web::web;
     use leptos_actix::extract;
     use wordforge_api::{
-        account::{self, FormAuthError, RegistrationError},
+        account::{self, FormAuthError, NewUser, RegistrationError},
         util::AppState,
         DbHandle,
     };
@@ -217,18 +323,24 @@ pub async fn register(
     )
     .await?;
 
-    match account::register(
-        state,
-        pool,
+    let info = NewUser {
         display_name,
         username,
-        email.clone(),
-        password.clone(),
-    )
-    .await
-    {
-        Ok(_) => login(cx, email, password, client_app, client_website, redirect_to).await,
-        Err(RegistrationError::BadRequest(e)) => Ok(Err(e)),
+        email: email.clone(),
+        password: password.clone(),
+        reason,
+        invite,
+    };
+
+    match account::register(state, pool, info).await {
+        Ok(true) => login(cx, email, password, client_app, client_website, redirect_to)
+            .await
+            .map(|r| r.map(|_| None)),
+        Ok(false) => Ok(Ok(Some(
+            "Thanks for signing up! You can sign in once your account has been approved."
+                .to_string(),
+        ))),
+        Err(RegistrationError::BadRequest(e)) | Err(RegistrationError::Forbidden(e)) => Ok(Err(e)),
         Err(RegistrationError::Conflict(FormAuthError::Email)) => {
             Ok(Err("Email in use".to_string()))
         }
@@ -0,0 +1,222 @@
+use crate::components::{bookmark::*, errorview::*};
+use leptos::*;
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BookmarkEntry {
+    bookmark: BookmarkItem,
+    novel_title: String,
+    chapter_title: String,
+    path: String,
+}
+
+/// The signed in user's bookmarks and highlights, with their notes.
+#[component]
+pub fn Bookmarks(cx: Scope) -> impl IntoView {
+    let set_note = create_server_action::<SetBookmarkNote>(cx);
+    let delete = create_server_action::<DeleteBookmark>(cx);
+    let bookmarks = create_resource(
+        cx,
+        move || (set_note.version().get(), delete.version().get()),
+        move |_| get_bookmarks(cx),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let results = move || {
+        [set_note.value().get(), delete.value().get()]
+            .into_iter()
+            .flatten()
+            .for_each(|v| match v {
+                Ok(Ok(_)) => (),
+                Ok(Err(e)) => set_errormsg(e),
+                Err(e) => set_errormsg(e.to_string()),
+            })
+    };
+
+    view! { cx,
+        <Title text="Bookmarks"/>
+        <div class="mx-auto max-w-2xl px-4">
+            <h1 class="p-2 text-3xl">"Bookmarks"</h1>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {results}
+            </div>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        bookmarks
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("bookmarks: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(bookmarks)) if bookmarks.is_empty() => {
+                                    view! { cx, <li class="p-2">"Nothing bookmarked yet"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(bookmarks)) => {
+                                    bookmarks
+                                        .into_iter()
+                                        .map(|b| {
+                                            let item = b.bookmark;
+                                            let chapter = match item.anchor {
+                                                Some(anchor) => {
+                                                    view! { cx,
+                                                        <A href=format!("{}#{anchor}", b.path) class="text-xl hover:underline">
+                                                            {b.chapter_title}
+                                                        </A>
+                                                    }
+                                                        .into_view(cx)
+                                                }
+                                                None => {
+                                                    view! { cx,
+                                                        <A href=b.path class="text-xl hover:underline">
+                                                            {b.chapter_title}
+                                                        </A>
+                                                        <span class="text-sm dark:text-red-400">"Passage not found"</span>
+                                                    }
+                                                        .into_view(cx)
+                                                }
+                                            };
+                                            view! { cx,
+                                                <li class="flex flex-col gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col">
+                                                        <span class="dark:text-gray-400">{b.novel_title}</span>
+                                                        {chapter}
+                                                        <span class="text-sm dark:text-gray-400">
+                                                            {if item.kind == "highlight" { "Highlight" } else { "Bookmark" }}
+                                                            " · "
+                                                            {item.published}
+                                                        </span>
+                                                    </div>
+                                                    <blockquote class="border-l-4 dark:border-gray-600 pl-2 italic">
+                                                        {item.quote}
+                                                    </blockquote>
+                                                    <div class="flex flex-row gap-2">
+                                                        <ActionForm action=set_note class="flex flex-row gap-2 grow">
+                                                            <input type="hidden" name="id" value=item.id.clone()/>
+                                                            <input
+                                                                type="text"
+                                                                class="basic-input grow"
+                                                                name="note"
+                                                                placeholder="Private note"
+                                                                value=item.note
+                                                            />
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                                                value="Save note"
+                                                            />
+                                                        </ActionForm>
+                                                        <ActionForm action=delete>
+                                                            <input type="hidden" name="id" value=item.id/>
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                                value="Delete"
+                                                            />
+                                                        </ActionForm>
+                                                    </div>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[server(GetBookmarks, "/server")]
+pub async fn get_bookmarks(cx: Scope) -> Result<Result<Vec<BookmarkEntry>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::bookmark::{self, BookmarkError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match bookmark::list_bookmarks(pool.app_data(), &apub_id).await {
+        Ok(bookmarks) => Ok(Ok(bookmarks
+            .into_iter()
+            .map(|b| BookmarkEntry {
+                bookmark: BookmarkItem::from(b.bookmark),
+                novel_title: b.novel_title,
+                chapter_title: b.chapter_title,
+                path: b.path,
+            })
+            .collect())),
+        Err(BookmarkError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(SetBookmarkNote, "/server")]
+pub async fn set_bookmark_note(
+    cx: Scope,
+    id: String,
+    note: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::bookmark::{self, BookmarkError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let id = match id.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("Not found".to_string())),
+    };
+
+    match bookmark::set_note(pool.app_data(), &apub_id, id, &note).await {
+        Ok(_) => Ok(Ok(())),
+        Err(BookmarkError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(BookmarkError::BadRequest(e)) => Ok(Err(e)),
+        Err(BookmarkError::NotFound) => Ok(Err("Not found".to_string())),
+    }
+}
@@ -0,0 +1,293 @@
+use crate::components::novelcard::*;
+use leptos::*;
+use leptos_icons::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+const SORTS: [(&str, &str); 3] = [
+    ("newest", "Newest"),
+    ("updated", "Recently updated"),
+    ("followed", "Most followed"),
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BrowsePage {
+    total: i64,
+    page_size: i64,
+    novels: Vec<NovelSummary>,
+    genres: Vec<Facet>,
+    languages: Vec<Facet>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Facet {
+    value: String,
+    /// What is shown for the value, like a language's name for its code.
+    label: String,
+    count: i64,
+}
+
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn facet_class(selected: bool) -> &'static str {
+    if selected {
+        "block px-2 py-1 rounded-md font-bold dark:bg-gray-800"
+    } else {
+        "block px-2 py-1 rounded-md hover:dark:bg-gray-800"
+    }
+}
+
+fn browse_href(genre: &str, language: &str, sort: &str, page: i64) -> String {
+    let mut params = vec![];
+    if !genre.is_empty() {
+        params.push(format!("genre={}", encode(genre)));
+    }
+    if !language.is_empty() {
+        params.push(format!("language={}", encode(language)));
+    }
+    if !sort.is_empty() && sort != "newest" {
+        params.push(format!("sort={}", encode(sort)));
+    }
+    if page > 0 {
+        params.push(format!("page={page}"));
+    }
+    format!("?{}", params.join("&"))
+}
+
+/// Novels on this instance and the ones it knows about, with genre and
+/// language facets. The filters are kept in the query string.
+#[component]
+pub fn Browse(cx: Scope) -> impl IntoView {
+    let query = use_query_map(cx);
+    let param = move |key: &str| query.with(|q| q.get(key).cloned().unwrap_or_default());
+    let page = move || param("page").parse::<i64>().unwrap_or(0).max(0);
+    let novels = create_resource(
+        cx,
+        move || (param("genre"), param("language"), param("sort"), page()),
+        move |(genre, language, sort, page)| get_browse_page(cx, genre, language, sort, page),
+    );
+
+    let facet_list = move |name: &'static str, facets: Vec<Facet>| {
+        let selected = param(name);
+        let clear = match name {
+            "genre" => browse_href("", &param("language"), &param("sort"), 0),
+            _ => browse_href(&param("genre"), "", &param("sort"), 0),
+        };
+        view! { cx,
+            <ul class="flex flex-row md:flex-col flex-wrap gap-1 text-sm">
+                <li>
+                    <A
+                        href=clear
+                        class=facet_class(selected.is_empty())
+                    >
+                        {if name == "genre" { "All genres" } else { "All languages" }}
+                    </A>
+                </li>
+                {facets
+                    .into_iter()
+                    .map(|f| {
+                        let href = match name {
+                            "genre" => browse_href(&f.value, &param("language"), &param("sort"), 0),
+                            _ => browse_href(&param("genre"), &f.value, &param("sort"), 0),
+                        };
+                        view! { cx,
+                            <li>
+                                <A
+                                    href=href
+                                    class=facet_class(selected == f.value)
+                                >
+                                    {format!("{} ({})", f.label, f.count)}
+                                </A>
+                            </li>
+                        }
+                    })
+                    .collect_view(cx)}
+            </ul>
+        }
+    };
+
+    view! { cx,
+        <div class="mx-auto max-w-5xl px-4">
+            <h1 class="p-2 text-3xl">"Browse"</h1>
+            <div class="flex flex-row flex-wrap gap-2 px-2">
+                {SORTS
+                    .into_iter()
+                    .map(|(value, label)| {
+                        view! { cx,
+                            <A
+                                href=move || browse_href(&param("genre"), &param("language"), value, 0)
+                                class=move || {
+                                    let sort = param("sort");
+                                    if sort == value || (sort.is_empty() && value == "newest") {
+                                        "rounded-full px-3 py-1 dark:bg-purple-600"
+                                    } else {
+                                        "rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+                                    }
+                                }
+                            >
+                                {label}
+                            </A>
+                        }
+                    })
+                    .collect_view(cx)}
+            </div>
+            <Transition fallback=move || {
+                view! { cx,
+                    <Icon
+                        icon=CgIcon::CgSpinner
+                        class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                    />
+                }
+            }>
+                {move || {
+                    novels
+                        .read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("browse: {}", e.to_string());
+                                view! { cx, <p class="p-2">"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => view! { cx, <p class="p-2">{e}</p> }.into_view(cx),
+                            Ok(Ok(b)) => {
+                                let page = page();
+                                let more = (page + 1) * b.page_size < b.total;
+                                view! { cx,
+                                    <div class="flex flex-col md:flex-row gap-4 p-2">
+                                        <div class="flex flex-col gap-2 md:w-48 flex-none">
+                                            <span class="dark:text-gray-400">"Genre"</span>
+                                            {facet_list("genre", b.genres)}
+                                            <span class="dark:text-gray-400">"Language"</span>
+                                            {facet_list("language", b.languages)}
+                                        </div>
+                                        <div class="grow">
+                                            {if b.novels.is_empty() {
+                                                view! { cx, <p>"No novels found"</p> }
+                                                    .into_view(cx)
+                                            } else {
+                                                view! { cx,
+                                                    <ul class="flex flex-col gap-2">
+                                                        {b
+                                                            .novels
+                                                            .into_iter()
+                                                            .map(|n| view! { cx, <NovelCard novel=n/> })
+                                                            .collect_view(cx)}
+                                                    </ul>
+                                                }
+                                                    .into_view(cx)
+                                            }}
+                                            <div class="flex flex-row justify-between py-2">
+                                                {(page > 0)
+                                                    .then(|| {
+                                                        view! { cx,
+                                                            <A
+                                                                href=browse_href(
+                                                                    &param("genre"), &param("language"), &param("sort"), page - 1
+                                                                )
+                                                                class="hover:underline"
+                                                            >
+                                                                "Previous"
+                                                            </A>
+                                                        }
+                                                    })}
+                                                <span></span>
+                                                {more
+                                                    .then(|| {
+                                                        view! { cx,
+                                                            <A
+                                                                href=browse_href(
+                                                                    &param("genre"), &param("language"), &param("sort"), page + 1
+                                                                )
+                                                                class="hover:underline"
+                                                            >
+                                                                "Next"
+                                                            </A>
+                                                        }
+                                                    })}
+                                            </div>
+                                        </div>
+                                    </div>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+#[server(GetBrowsePage, "/server")]
+pub async fn get_browse_page(
+    cx: Scope,
+    genre: String,
+    language: String,
+    sort: String,
+    page: i64,
+) -> Result<Result<BrowsePage, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use isolang::Language;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        api::browse::{self, BrowseError, BrowseQuery, PAGE_SIZE},
+        enums::BrowseSort,
+        DbHandle,
+    };
+
+    let pool = extract(cx, |pool: Data<DbHandle>| async move { pool }).await?;
+
+    let sort = match sort.as_str() {
+        "" => BrowseSort::default(),
+        s => match BrowseSort::from_str(s) {
+            Ok(s) => s,
+            Err(e) => return Ok(Err(e.to_string())),
+        },
+    };
+    let query = BrowseQuery {
+        genre: Some(genre),
+        language: Some(language),
+        sort,
+        page,
+    };
+
+    match browse::browse(pool.app_data(), query).await {
+        Ok(b) => Ok(Ok(BrowsePage {
+            total: b.total,
+            page_size: PAGE_SIZE,
+            novels: b.novels.into_iter().map(NovelSummary::from).collect(),
+            genres: b
+                .genres
+                .into_iter()
+                .map(|f| Facet {
+                    label: f.value.clone(),
+                    value: f.value,
+                    count: f.count,
+                })
+                .collect(),
+            languages: b
+                .languages
+                .into_iter()
+                .map(|f| Facet {
+                    label: Language::from_639_1(&f.value)
+                        .map(|l| l.to_name().to_string())
+                        .unwrap_or_else(|| f.value.clone()),
+                    value: f.value,
+                    count: f.count,
+                })
+                .collect(),
+        })),
+        Err(BrowseError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
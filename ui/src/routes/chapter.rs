@@ -0,0 +1,465 @@
+use crate::{
+    app::ValidationError,
+    components::{bookmark::*, errorview::*, progress::*},
+    path::ChapterViewParams,
+};
+use leptos::{html::*, *};
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+use std::{cell::Cell, rc::Rc};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChapterPage {
+    apub_id: String,
+    novel_path: String,
+    novel_title: String,
+    title: String,
+    summary: String,
+    sensitive: bool,
+    /// The ID and text of each paragraph.
+    paragraphs: Vec<(String, String)>,
+    published: String,
+    /// Where the chapters before and after it are shown.
+    previous: Option<String>,
+    next: Option<String>,
+    /// How far the signed in user got, if this is where they continue.
+    position: Option<f32>,
+}
+
+/// How far down the page is scrolled, from 0 to 1.
+fn scroll_position() -> f32 {
+    let position = || {
+        let height = window().inner_height().ok()?.as_f64()?;
+        let scrolled = window().scroll_y().ok()?;
+        let total = document().document_element()?.scroll_height() as f64;
+        Some(if total <= height {
+            1.0
+        } else {
+            (scrolled / (total - height)).clamp(0.0, 1.0)
+        })
+    };
+    position().unwrap_or(0.0) as f32
+}
+
+/// Scrolls `position` far down the page, from 0 to 1.
+fn scroll_to(position: f32) {
+    let scroll = || {
+        let height = window().inner_height().ok()?.as_f64()?;
+        let total = document().document_element()?.scroll_height() as f64;
+        window().scroll_to_with_x_and_y(0.0, position as f64 * (total - height).max(0.0));
+        Some(())
+    };
+    scroll();
+}
+
+/// The paragraph and text of the current selection, if it is within one
+/// paragraph of the chapter.
+fn selected_passage() -> Option<(String, String)> {
+    let selection = window().get_selection().ok()??;
+    let paragraph = |node: Option<web_sys::Node>| {
+        node?
+            .parent_element()?
+            .closest("article p[id]")
+            .ok()?
+            .map(|p| p.id())
+    };
+    let start = paragraph(selection.anchor_node())?;
+    if paragraph(selection.focus_node())? != start {
+        return None;
+    }
+    let quote = String::from(selection.to_string());
+    Some((start, quote))
+}
+
+/// The first paragraph of the chapter still on screen, and its start.
+fn visible_paragraph(paragraphs: &[(String, String)]) -> Option<(String, String)> {
+    paragraphs.iter().find_map(|(id, p)| {
+        let rect = document().get_element_by_id(id)?.get_bounding_client_rect();
+        (rect.bottom() > 0.0).then(|| (id.clone(), p.chars().take(80).collect()))
+    })
+}
+
+/// A chapter to read. How far the signed in user scrolled is saved as they
+/// go, and they are taken back there when they continue the novel.
+///
+/// Paragraphs have IDs made from their text, which bookmarks and highlights
+/// point at. Links to one of them scroll there instead.
+#[component]
+pub fn ChapterView(cx: Scope) -> impl IntoView {
+    let params = use_params::<ChapterViewParams>(cx);
+    let ids = move || {
+        params
+            .with(|params| params.clone().map(|p| (p.uuid, p.chapter)))
+            .unwrap_or_default()
+    };
+    let page = create_resource(cx, ids, move |(novel, chapter)| {
+        get_chapter_page(cx, novel, chapter)
+    });
+
+    let validate = use_context::<
+        Resource<(), Result<Result<(String, String), ValidationError>, ServerFnError>>,
+    >(cx)
+    .unwrap();
+    let signed_in = move || matches!(validate.read(cx), Some(Ok(Ok(_))));
+    let location = use_location(cx);
+
+    let save = create_server_action::<SetReadingPosition>(cx);
+    let mark_read = create_server_action::<MarkChapterRead>(cx);
+    let (chapter_id, set_chapter_id) = create_signal(cx, String::new());
+    // The furthest position saved for the chapter, so that only every few
+    // percent of scrolling is sent.
+    let (saved, set_saved) = create_signal(cx, 0.0f32);
+
+    create_effect(cx, move |_| {
+        if let Some(Ok(Ok(page))) = page.read(cx) {
+            set_chapter_id(page.apub_id);
+            set_saved(page.position.unwrap_or(0.0));
+            let hash = location.hash.get_untracked();
+            request_animation_frame(move || {
+                match hash
+                    .strip_prefix('#')
+                    .and_then(|id| document().get_element_by_id(id))
+                {
+                    Some(paragraph) => paragraph.scroll_into_view(),
+                    None => {
+                        if let Some(position) = page.position.filter(|p| *p > 0.0) {
+                            scroll_to(position)
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    // The listener outlives the component, so it is turned off on cleanup.
+    let active = Rc::new(Cell::new(true));
+    on_cleanup(cx, {
+        let active = active.clone();
+        move || active.set(false)
+    });
+    window_event_listener(ev::scroll, move |_| {
+        let chapter = chapter_id.get_untracked();
+        if !active.get() || chapter.is_empty() || !signed_in() {
+            return;
+        }
+        let position = scroll_position();
+        let last = saved.get_untracked();
+        if position > last && (position >= 1.0 || position - last >= 0.05) {
+            set_saved(position);
+            save.dispatch(SetReadingPosition { chapter, position });
+        }
+    });
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+
+    let add_bookmark = create_server_action::<AddBookmark>(cx);
+    let bookmarks = create_resource(
+        cx,
+        move || (chapter_id(), add_bookmark.version().get()),
+        move |(chapter, _)| get_chapter_bookmarks(cx, chapter),
+    );
+    let note = create_node_ref::<Input>(cx);
+    let save_bookmark = move |kind: &'static str| {
+        let passage = if kind == "highlight" {
+            selected_passage().filter(|(_, quote)| !quote.trim().is_empty())
+        } else {
+            page.with(cx, |p| match p {
+                Ok(Ok(c)) => visible_paragraph(&c.paragraphs),
+                _ => None,
+            })
+            .flatten()
+        };
+        match passage {
+            Some((paragraph, quote)) => add_bookmark.dispatch(AddBookmark {
+                chapter: chapter_id.get_untracked(),
+                kind: kind.to_string(),
+                paragraph,
+                quote,
+                note: note().map(|n| n.value()).unwrap_or_default(),
+            }),
+            None if kind == "highlight" => {
+                set_errormsg("Select a passage within one paragraph first".to_string())
+            }
+            None => set_errormsg("Nothing to bookmark".to_string()),
+        }
+    };
+    create_effect(cx, move |_| match add_bookmark.value().get() {
+        None => (),
+        Some(Ok(Ok(_))) => {
+            set_errormsg(String::new());
+            if let Some(note) = note() {
+                note.set_value("");
+            }
+        }
+        Some(Ok(Err(e))) => set_errormsg(e),
+        Some(Err(e)) => set_errormsg(e.to_string()),
+    });
+    // The highlights in the paragraph `id` with their notes, and whether it
+    // is bookmarked.
+    let marks = move |id: &str| {
+        bookmarks
+            .with(cx, |b| match b {
+                Ok(Ok(b)) => (
+                    b.iter()
+                        .filter(|b| b.kind == "highlight" && b.anchor.as_deref() == Some(id))
+                        .map(|b| (b.quote.clone(), b.note.clone()))
+                        .collect::<Vec<_>>(),
+                    b.iter()
+                        .any(|b| b.kind == "bookmark" && b.anchor.as_deref() == Some(id)),
+                ),
+                _ => (vec![], false),
+            })
+            .unwrap_or_default()
+    };
+
+    let marked = move || {
+        mark_read.value().get().map(|v| match v {
+            Ok(Ok(_)) => {
+                set_errormsg(String::new());
+                view! { cx, <span class="my-auto text-sm dark:text-gray-400">"Marked as read"</span> }
+                    .into_view(cx)
+            }
+            Ok(Err(e)) => {
+                set_errormsg(e);
+                ().into_view(cx)
+            }
+            Err(e) => {
+                set_errormsg(e.to_string());
+                ().into_view(cx)
+            }
+        })
+    };
+
+    view! { cx,
+        <div class="mx-auto max-w-2xl px-4">
+            <Transition fallback=move || {
+                view! { cx,
+                    <Icon
+                        icon=CgIcon::CgSpinner
+                        class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                    />
+                }
+            }>
+                {move || {
+                    page.read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("chapter: {}", e.to_string());
+                                view! { cx, <p class="p-2">"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => {
+                                view! { cx,
+                                    <Title text="Not found"/>
+                                    <p class="p-2">{e}</p>
+                                }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(c)) => {
+                                let apub_id = c.apub_id.clone();
+                                view! { cx,
+                                    <Title text=c.title.clone()/>
+                                    <A href=c.novel_path class="px-2 dark:text-gray-400 hover:underline">
+                                        {c.novel_title}
+                                    </A>
+                                    <h1 class="p-2 text-3xl">{c.title}</h1>
+                                    {signed_in()
+                                        .then(|| {
+                                            view! { cx,
+                                                <div class="sticky top-0 z-10 flex flex-row gap-2 p-2 dark:bg-gray-900">
+                                                    <input
+                                                        type="text"
+                                                        class="basic-input grow"
+                                                        placeholder="Private note (optional)"
+                                                        node_ref=note
+                                                    />
+                                                    <button
+                                                        class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1"
+                                                        title="Bookmark the first paragraph on screen"
+                                                        on:click=move |_| save_bookmark("bookmark")
+                                                    >
+                                                        "Bookmark"
+                                                    </button>
+                                                    <button
+                                                        class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1"
+                                                        title="Highlight the selected passage"
+                                                        on:click=move |_| save_bookmark("highlight")
+                                                    >
+                                                        "Highlight"
+                                                    </button>
+                                                </div>
+                                            }
+                                        })}
+                                    <span class="px-2 text-sm dark:text-gray-400">
+                                        {c.published}
+                                        {c.sensitive.then_some(" · Content warning")}
+                                    </span>
+                                    <div class="italic p-2">
+                                        {c
+                                            .summary
+                                            .lines()
+                                            .map(|p| {
+                                                view! { cx, <p>{p.to_string()}</p> }
+                                            })
+                                            .collect_view(cx)}
+                                    </div>
+                                    <article class="flex flex-col gap-4 p-2 text-lg">
+                                        {if c.paragraphs.is_empty() {
+                                            view! { cx, <p class="dark:text-gray-400">"This chapter has no text yet."</p> }
+                                                .into_view(cx)
+                                        } else {
+                                            c.paragraphs
+                                                .into_iter()
+                                                .map(|(id, p)| {
+                                                    let mark_id = id.clone();
+                                                    view! { cx,
+                                                        <p
+                                                            id=id.clone()
+                                                            class=move || {
+                                                                if marks(&id).1 { "border-l-4 border-purple-600 pl-2 -ml-3" } else { "" }
+                                                            }
+                                                        >
+                                                            {move || {
+                                                                passages(&p, &marks(&mark_id).0)
+                                                                    .into_iter()
+                                                                    .map(|(text, note)| match note {
+                                                                        None => text.into_view(cx),
+                                                                        Some(note) => {
+                                                                            view! { cx,
+                                                                                <mark class="bg-yellow-200 dark:bg-yellow-700 dark:text-white" title=note>
+                                                                                    {text}
+                                                                                </mark>
+                                                                            }
+                                                                                .into_view(cx)
+                                                                        }
+                                                                    })
+                                                                    .collect_view(cx)
+                                                            }}
+                                                        </p>
+                                                    }
+                                                })
+                                                .collect_view(cx)
+                                        }}
+                                    </article>
+                                    {signed_in()
+                                        .then(|| {
+                                            view! { cx,
+                                                <div class="flex flex-row gap-2 p-2">
+                                                    <ActionForm action=mark_read>
+                                                        <input type="hidden" name="chapter" value=apub_id/>
+                                                        <input type="hidden" name="read" value="true"/>
+                                                        <input
+                                                            type="submit"
+                                                            class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                                            value="Mark as read"
+                                                        />
+                                                    </ActionForm>
+                                                    {marked}
+                                                </div>
+                                            }
+                                        })}
+                                    <nav class="flex flex-row justify-between p-2">
+                                        {c
+                                            .previous
+                                            .map(|href| {
+                                                view! { cx,
+                                                    <A href=href class="hover:underline">
+                                                        "Previous chapter"
+                                                    </A>
+                                                }
+                                            })}
+                                        {c
+                                            .next
+                                            .map(|href| {
+                                                view! { cx,
+                                                    <A href=href class="ml-auto hover:underline">
+                                                        "Next chapter"
+                                                    </A>
+                                                }
+                                            })}
+                                    </nav>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+            <ErrorView message=errormsg/>
+        </div>
+    }
+}
+
+#[server(GetChapterPage, "/server")]
+pub async fn get_chapter_page(
+    cx: Scope,
+    novel: String,
+    chapter: String,
+) -> Result<Result<ChapterPage, String>, ServerFnError> {
+    use crate::components::chapter::chapter_href;
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account,
+        api::{
+            chapter::{self, ChapterError},
+            progress::{self, ProgressError},
+        },
+        DbHandle,
+    };
+
+    let (data, session) = extract(cx, |data: Data<DbHandle>, session: Session| async move {
+        (data, session)
+    })
+    .await?;
+
+    let page = match chapter::get_chapter(novel.clone(), chapter, &data).await {
+        Ok(page) => page,
+        Err(ChapterError::NotFound) => return Ok(Err("Chapter not found".to_string())),
+        Err(ChapterError::InternalError(e)) => return Err(ServerFnError::ServerError(e)),
+    };
+
+    let position = match account::current_user(data.app_data(), &session).await {
+        Ok(reader) => {
+            match progress::get_progress(data.app_data(), &reader, &page.chapter.audience).await {
+                Ok(p) => p
+                    .resume
+                    .filter(|r| r.chapter.eq_ignore_ascii_case(&page.chapter.apub_id))
+                    .map(|r| r.position),
+                Err(ProgressError::InternalServerError(e)) => {
+                    return Err(ServerFnError::ServerError(e))
+                }
+                Err(_) => None,
+            }
+        }
+        Err(_) => None,
+    };
+
+    let novel_path = format!("/novel/{novel}");
+    Ok(Ok(ChapterPage {
+        previous: page
+            .previous
+            .map(|c| format!("{novel_path}/{}", chapter_href(&c))),
+        next: page
+            .next
+            .map(|c| format!("{novel_path}/{}", chapter_href(&c))),
+        novel_path,
+        novel_title: page.novel_title,
+        paragraphs: {
+            let paragraphs = chapter::paragraphs(&page.chapter.content);
+            chapter::paragraph_ids(&paragraphs)
+                .into_iter()
+                .zip(paragraphs)
+                .collect()
+        },
+        apub_id: page.chapter.apub_id,
+        title: page.chapter.title,
+        summary: page.chapter.summary,
+        sensitive: page.chapter.sensitive,
+        published: HumanTime::from(page.chapter.published).to_string(),
+        position,
+    }))
+}
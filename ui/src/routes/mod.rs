@@ -1,4 +1,26 @@
 #[allow(clippy::too_many_arguments)]
+pub mod admin;
+#[allow(clippy::too_many_arguments)]
 pub mod auth;
 #[allow(clippy::too_many_arguments)]
+pub mod bookmark;
+#[allow(clippy::too_many_arguments)]
+pub mod browse;
+#[allow(clippy::too_many_arguments)]
+pub mod chapter;
+#[allow(clippy::too_many_arguments)]
 pub mod novel;
+#[allow(clippy::too_many_arguments)]
+pub mod oauth;
+#[allow(clippy::too_many_arguments)]
+pub mod search;
+#[allow(clippy::too_many_arguments)]
+pub mod settings;
+#[allow(clippy::too_many_arguments)]
+pub mod shelf;
+#[allow(clippy::too_many_arguments)]
+pub mod tag;
+#[allow(clippy::too_many_arguments)]
+pub mod timeline;
+#[allow(clippy::too_many_arguments)]
+pub mod user;
@@ -1,6 +1,9 @@
 use crate::{
     app::ValidationError,
-    components::{basicinput::*, chapter::*, errorview::*, listbox::*, toggle::*},
+    components::{
+        basicinput::*, chapter::*, errorview::*, follow::*, listbox::*, progress::*, report::*,
+        shelf::*, toggle::*,
+    },
     fallback::*,
     path::NovelViewParams,
 };
@@ -236,6 +239,7 @@ pub async fn create_novel(
     use leptos_actix::extract;
     use std::str::FromStr;
     use wordforge_api::{
+        account::{self, UserValidateError},
         api::novel::{self, CreateNovelError, NewNovel},
         enums::*,
         util::AppState,
@@ -250,6 +254,12 @@ pub async fn create_novel(
     )
     .await?;
 
+    let apub_id = match account::validate(pool.app_data().as_ref(), session).await {
+        Ok((apub_id, _)) => apub_id,
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+    };
+
     let info = NewNovel {
         title,
         summary,
@@ -270,7 +280,7 @@ pub async fn create_novel(
         tags,
     };
 
-    match novel::create_novel(state, pool, session, info).await {
+    match novel::create_novel(state, pool, apub_id, info).await {
         Ok(id) => Ok(Ok(leptos_actix::redirect(cx, &format!("/novel/{}", id)))),
         Err(CreateNovelError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
         Err(CreateNovelError::Unauthorized(e)) => Ok(Err(e)),
@@ -309,6 +319,7 @@ pub struct Author {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Novel {
+    apub_id: String,
     name: String,
     summary: String,
     authors: Vec<Author>,
@@ -331,6 +342,7 @@ pub fn NovelView(cx: Scope) -> impl IntoView {
     let novel = create_resource(cx, uuid, move |id| get_novel(cx, id));
     let (authors, set_authors) = create_signal::<Vec<(String, String)>>(cx, Vec::new());
     let usernames = create_resource(cx, authors, move |authors| get_usernames(cx, authors));
+    let (novel_id, set_novel_id) = create_signal(cx, String::new());
 
     let author_view = move || {
         usernames.read(cx).map(|v| match v {
@@ -389,7 +401,9 @@ pub fn NovelView(cx: Scope) -> impl IntoView {
                 error!("novel server fn: {}", e.to_string());
                 view! { cx, <InternalErrorPage/> }.into_view(cx)
             }
-            Ok(Ok(novel)) => view! { cx,
+            Ok(Ok(novel)) => {
+                set_novel_id(novel.apub_id.clone());
+                view! { cx,
                                  <h1 class="text-center p-2 text-3xl">{&novel.name}</h1>
                                  <Title text=novel.name.clone()/>
                                  <div class="flex flex-row overflow-auto whitespace-nowrap gap-1 text-xl md:text-base">
@@ -446,7 +460,7 @@ pub fn NovelView(cx: Scope) -> impl IntoView {
                                          .map(|tag| {
                                              view! { cx,
                                                  <a
-                                                     href=format!("/explore/tags/{tag}")
+                                                     href=format!("/tags/{}", tag.to_lowercase())
                                                      class="italic mb-2 mt-auto dark:text-gray-500 dark:hover:text-gray-400 hover:underline rounded-full text-xl md:text-base"
                                                  >
                                                      {format!("#{tag}")}
@@ -457,7 +471,8 @@ pub fn NovelView(cx: Scope) -> impl IntoView {
                                          .collect::<Vec<_>>()}
                                  </div>
                              }
-            .into_view(cx),
+            .into_view(cx)
+            }
         })
     };
 
@@ -471,10 +486,52 @@ pub fn NovelView(cx: Scope) -> impl IntoView {
             .map(|resp| resp.unwrap_or_else(|e| Err(ValidationError::Error(e.to_string()))))
     });
 
+    let signed_in = move || matches!(valid(), Some(Ok(_)));
+    let report_novel = create_node_ref::<Dialog>(cx);
+    let report_chapter = create_node_ref::<Dialog>(cx);
+    let (chapter_id, set_chapter_id) = create_signal(cx, String::new());
+
     let chapter_button = create_node_ref::<Dialog>(cx);
     let (new_chapter, trigger_chapter) = create_signal(cx, ());
     let chapters = create_resource(cx, new_chapter, move |_| get_chapter_list(cx, uuid()));
     let (chapters_len, set_chapters_len) = create_signal(cx, 0);
+    let mark_read = create_server_action::<MarkChapterRead>(cx);
+    let progress = create_resource(
+        cx,
+        move || (novel_id(), mark_read.version().get()),
+        move |(novel, _)| get_novel_progress(cx, novel),
+    );
+    let continue_view = move || {
+        progress.read(cx).map(|v| match v {
+            Err(e) => {
+                error!("novel progress: {}", e.to_string());
+                ().into_view(cx)
+            }
+            Ok(Err(_)) => ().into_view(cx),
+            Ok(Ok(p)) => match p.resume {
+                Some((href, position)) => {
+                    let started = !p.read.is_empty() || position > 0.0;
+                    view! { cx,
+                        <span class="my-auto text-sm dark:text-gray-400">
+                            {format!("{} unread", p.unread)}
+                        </span>
+                        <A
+                            href=href
+                            class="my-auto dark:bg-purple-700 hover:dark:bg-purple-800 rounded-md px-3 py-1"
+                        >
+                            {if started { "Continue reading" } else { "Start reading" }}
+                        </A>
+                    }
+                        .into_view(cx)
+                }
+                None if p.read.is_empty() => ().into_view(cx),
+                None => {
+                    view! { cx, <span class="my-auto text-sm dark:text-gray-400">"All caught up"</span> }
+                        .into_view(cx)
+                }
+            },
+        })
+    };
 
     view! { cx,
         <Title text="Novel"/>
@@ -488,6 +545,31 @@ pub fn NovelView(cx: Scope) -> impl IntoView {
                 }
                     .into_view(cx)
             }>{metadata}</Suspense>
+            <Suspense fallback=|| ()>
+                <Show when=signed_in fallback=|_| ()>
+                    <div class="flex flex-row justify-end gap-2">
+                        {continue_view}
+                        <FollowButton target=novel_id/>
+                        <ShelfMenu novel=novel_id/>
+                        <button
+                            class="flex flex-row gap-1 p-1 rounded-md text-gray-500 dark:text-gray-300"
+                            on:click=move |_| {
+                                if let Some(v) = report_novel() {
+                                    v.show_modal().unwrap_throw()
+                                }
+                            }
+                        >
+                            <Icon
+                                icon=OcIcon::OcReportLg
+                                class="dark:stroke-white w-6 h-6 my-auto stroke-0"
+                            />
+                            <span class="my-auto pr-1">"Report"</span>
+                        </button>
+                    </div>
+                </Show>
+            </Suspense>
+            <ReportDialog target=novel_id kind="novel" node_ref=report_novel/>
+            <ReportDialog target=chapter_id kind="chapter" node_ref=report_chapter/>
             <div class="flex flex-col w-full rounded-xl px-4 py-2 my-2 dark:bg-gray-800">
                 <div class="flex flex-row justify-between w-full">
                     <span class="text-gray-600 dark:text-gray-400 text-lg my-auto p-1">
@@ -563,9 +645,61 @@ pub fn NovelView(cx: Scope) -> impl IntoView {
                                         set_chapters_len(v.len() + 1);
                                         v.into_iter()
                                             .map(|c| {
+                                                let apub_id = c.as_ref().ok().map(|c| c.apub_id.clone());
+                                                let read = {
+                                                    let id = apub_id.clone().unwrap_or_default().to_lowercase();
+                                                    Signal::derive(
+                                                        cx,
+                                                        move || {
+                                                            progress
+                                                                .with(cx, |p| matches!(p, Ok(Ok(p)) if p.read.contains(&id)))
+                                                                .unwrap_or(false)
+                                                        },
+                                                    )
+                                                };
                                                 view! { cx,
                                                     <li class="pl-1 mx-auto w-full" style="counter-increment: item -1">
-                                                        <ChapterEntry chapter=c/>
+                                                        <div class="flex flex-row gap-1">
+                                                            <ChapterEntry chapter=c read=read/>
+                                                            {apub_id
+                                                                .map(|id| {
+                                                                    view! { cx,
+                                                                        <Show when=signed_in fallback=|_| ()>
+                                                                            <ActionForm action=mark_read class="my-auto">
+                                                                                <input type="hidden" name="chapter" value=id.clone()/>
+                                                                                <input
+                                                                                    type="hidden"
+                                                                                    name="read"
+                                                                                    value=move || (!read()).to_string()
+                                                                                />
+                                                                                <input
+                                                                                    type="submit"
+                                                                                    class="p-1 rounded-md text-sm whitespace-nowrap text-gray-500 dark:text-gray-300 cursor-pointer hover:underline"
+                                                                                    value=move || if read() { "Mark unread" } else { "Mark read" }
+                                                                                />
+                                                                            </ActionForm>
+                                                                            <button
+                                                                                class="p-1 my-auto rounded-md text-gray-500 dark:text-gray-300"
+                                                                                title="Report chapter"
+                                                                                on:click={
+                                                                                    let id = id.clone();
+                                                                                    move |_| {
+                                                                                        set_chapter_id(id.clone());
+                                                                                        if let Some(v) = report_chapter() {
+                                                                                            v.show_modal().unwrap_throw()
+                                                                                        }
+                                                                                    }
+                                                                                }
+                                                                            >
+                                                                                <Icon
+                                                                                    icon=OcIcon::OcReportLg
+                                                                                    class="dark:stroke-white w-5 h-5 my-auto stroke-0"
+                                                                                />
+                                                                            </button>
+                                                                        </Show>
+                                                                    }
+                                                                })}
+                                                        </div>
                                                     </li>
                                                 }
                                             })
@@ -597,6 +731,7 @@ pub async fn get_novel(
     match novel::get_novel(uuid, &pool).await {
         Ok(v) => {
             let novel = Box::new(Novel {
+                apub_id: v.id.inner().to_string(),
                 name: v.name,
                 summary: v.summary,
                 authors: v
@@ -0,0 +1,253 @@
+use crate::{components::errorview::*, fallback::*, path::AuthorizeQueries};
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppInfo {
+    name: String,
+    website: Option<String>,
+    scopes: Vec<String>,
+}
+
+#[component]
+pub fn OAuthAuthorize(cx: Scope) -> impl IntoView {
+    let query = use_query::<AuthorizeQueries>(cx);
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+
+    let info = create_resource(
+        cx,
+        move || query.with(|q| q.clone().ok()),
+        move |q| async move {
+            match q {
+                None => Ok(Err("Invalid authorization request".to_string())),
+                Some(q) => {
+                    get_app_info(
+                        cx,
+                        q.client_id,
+                        q.redirect_uri,
+                        q.scope,
+                        q.code_challenge_method,
+                    )
+                    .await
+                }
+            }
+        },
+    );
+
+    let authorize = create_server_action::<AuthorizeApp>(cx);
+    let response = authorize.value();
+    let err = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    let hidden = move |cx, approve: bool| {
+        query.with(|q| {
+            q.clone().ok().map(|q| {
+                view! { cx,
+                    <input type="hidden" name="response_type" value=q.response_type/>
+                    <input type="hidden" name="client_id" value=q.client_id/>
+                    <input type="hidden" name="redirect_uri" value=q.redirect_uri/>
+                    <input type="hidden" name="scope" value=q.scope.unwrap_or_default()/>
+                    <input type="hidden" name="state" value=q.state.unwrap_or_default()/>
+                    <input type="hidden" name="code_challenge" value=q.code_challenge/>
+                    <input
+                        type="hidden"
+                        name="code_challenge_method"
+                        value=q.code_challenge_method.unwrap_or_default()
+                    />
+                    <input type="hidden" name="approve" value=approve.to_string()/>
+                }
+            })
+        })
+    };
+
+    view! { cx,
+        <Title text="Authorize application"/>
+        <div class="mx-auto max-w-xl px-4">
+            <Suspense fallback=|| ()>
+                {move || {
+                    info.read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("oauth: {}", e.to_string());
+                                view! { cx, <InternalErrorPage/> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => {
+                                view! { cx, <p class="text-center text-red-800 text-xl p-4">{e}</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(app)) => {
+                                view! { cx,
+                                    <h1 class="text-center p-2 text-3xl">
+                                        {format!("Authorize {}", app.name)}
+                                    </h1>
+                                    {app
+                                        .website
+                                        .map(|w| {
+                                            view! { cx,
+                                                <a href=w.clone() class="block text-center dark:text-gray-400 hover:underline">
+                                                    {w}
+                                                </a>
+                                            }
+                                        })}
+                                    <div class="dark:bg-gray-800 rounded-xl px-4 py-2 my-2">
+                                        <span class="text-gray-500 dark:text-gray-400">
+                                            "This application will be able to"
+                                        </span>
+                                        <ul class="list-disc pl-6">
+                                            {app
+                                                .scopes
+                                                .into_iter()
+                                                .map(|s| {
+                                                    let desc = match s.as_str() {
+                                                        "read" => "Read your account data",
+                                                        "write" => "Create and modify content on your behalf",
+                                                        "follow" => "Follow and unfollow on your behalf",
+                                                        _ => "Unknown permission",
+                                                    };
+                                                    view! { cx, <li>{desc}</li> }
+                                                })
+                                                .collect_view(cx)}
+                                        </ul>
+                                    </div>
+                                    <div class="flex flex-row justify-center gap-4">
+                                        <ActionForm action=authorize>
+                                            {hidden(cx, false)}
+                                            <input
+                                                type="submit"
+                                                class="bg-gray-600 hover:bg-gray-700 text-lg font-bold py-2 px-4 rounded cursor-pointer"
+                                                value="Deny"
+                                            />
+                                        </ActionForm>
+                                        <ActionForm action=authorize>
+                                            {hidden(cx, true)}
+                                            <input type="submit" class="button-1" value="Authorize"/>
+                                        </ActionForm>
+                                    </div>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Suspense>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {err}
+            </div>
+        </div>
+    }
+}
+
+#[server(GetAppInfo, "/server")]
+pub async fn get_app_info(
+    cx: Scope,
+    client_id: String,
+    redirect_uri: String,
+    scope: Option<String>,
+    code_challenge_method: Option<String>,
+) -> Result<Result<AppInfo, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        oauth::{self, AuthorizeRequest, OAuthError},
+        DbHandle,
+    };
+
+    let pool = extract(cx, |pool: Data<DbHandle>| async move { pool }).await?;
+
+    let client_id = match client_id.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("Unknown application".to_string())),
+    };
+    let request = AuthorizeRequest {
+        response_type: "code".to_string(),
+        client_id,
+        redirect_uri,
+        scope,
+        state: None,
+        code_challenge: "-".to_string(),
+        code_challenge_method,
+    };
+
+    match oauth::authorize_info(pool.app_data(), &request).await {
+        Ok(info) => Ok(Ok(AppInfo {
+            name: info.name,
+            website: info.website,
+            scopes: info.scopes,
+        })),
+        Err(OAuthError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(OAuthError::InvalidClient) => Ok(Err("Unknown application".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(AuthorizeApp, "/server")]
+pub async fn authorize_app(
+    cx: Scope,
+    response_type: String,
+    client_id: String,
+    redirect_uri: String,
+    scope: String,
+    state: String,
+    code_challenge: String,
+    code_challenge_method: String,
+    approve: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use url::Url;
+    use wordforge_api::{
+        oauth::{self, AuthorizeRequest, OAuthError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let client_id = match client_id.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("Unknown application".to_string())),
+    };
+    let request = AuthorizeRequest {
+        response_type,
+        client_id,
+        redirect_uri,
+        scope: (!scope.is_empty()).then_some(scope),
+        state: (!state.is_empty()).then_some(state),
+        code_challenge,
+        code_challenge_method: (!code_challenge_method.is_empty()).then_some(code_challenge_method),
+    };
+
+    let res = if approve {
+        oauth::authorize(pool.app_data(), session, request).await
+    } else {
+        match oauth::authorize_info(pool.app_data(), &request).await {
+            Ok(_) => {
+                let mut uri = Url::parse(&request.redirect_uri)
+                    .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+                uri.query_pairs_mut().append_pair("error", "access_denied");
+                if let Some(state) = request.state {
+                    uri.query_pairs_mut().append_pair("state", &state);
+                }
+                Ok(uri.to_string())
+            }
+            Err(e) => Err(e),
+        }
+    };
+
+    match res {
+        Ok(uri) => Ok(Ok(leptos_actix::redirect(cx, &uri))),
+        Err(OAuthError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{
+    components::novelcard::*,
+    routes::novel::{get_genres, get_langs},
+};
+use leptos::*;
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchResults {
+    /// Set when the search was a handle or URL that resolved to a novel or
+    /// user.
+    redirect: Option<String>,
+    novels: Vec<NovelSummary>,
+    chapters: Vec<ChapterResult>,
+    users: Vec<UserResult>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChapterResult {
+    novel_path: String,
+    novel_title: String,
+    sequence: i32,
+    title: String,
+    /// Alternating plain and matched parts of the chapter, starting with a
+    /// plain one.
+    headline: Vec<String>,
+    sensitive: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserResult {
+    path: String,
+    handle: String,
+    name: String,
+    summary: String,
+}
+
+#[component]
+pub fn Search(cx: Scope) -> impl IntoView {
+    let query = use_query_map(cx);
+    let param = move |key: &str| query.with(|q| q.get(key).cloned().unwrap_or_default());
+    let results = create_resource(
+        cx,
+        move || {
+            (
+                param("q"),
+                param("genre"),
+                param("language"),
+                param("tag"),
+                param("sensitive"),
+            )
+        },
+        move |(q, genre, language, tag, sensitive)| async move {
+            if q.trim().is_empty() {
+                return Ok(Ok(None));
+            }
+            get_search_results(cx, q, genre, language, tag, sensitive)
+                .await
+                .map(|r| r.map(Some))
+        },
+    );
+    let genres = create_resource(cx, || (), move |_| get_genres());
+    let langs = create_resource(cx, || (), move |_| get_langs());
+
+    view! { cx,
+        <Title text="Search"/>
+        <div class="mx-auto max-w-4xl px-4">
+            <h1 class="p-2 text-3xl">"Search"</h1>
+            <Form method="GET" action="" class="flex flex-col gap-2 p-2">
+                <input
+                    type="search"
+                    name="q"
+                    placeholder="Search, or paste a handle or link"
+                    class="rounded-md px-2 py-1 dark:bg-gray-800"
+                    prop:value=move || param("q")
+                />
+                <div class="flex flex-row flex-wrap gap-2">
+                    <select name="genre" class="rounded-md px-2 py-1 dark:bg-gray-800">
+                        <option value="" selected=move || param("genre").is_empty()>
+                            "Any genre"
+                        </option>
+                        <Transition fallback=|| ()>
+                            {move || {
+                                genres
+                                    .read(cx)
+                                    .and_then(Result::ok)
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|g| {
+                                        let value = g.clone();
+                                        view! { cx,
+                                            <option
+                                                value=g.clone()
+                                                selected=move || param("genre") == value
+                                            >
+                                                {g}
+                                            </option>
+                                        }
+                                    })
+                                    .collect_view(cx)
+                            }}
+                        </Transition>
+                    </select>
+                    <select name="language" class="rounded-md px-2 py-1 dark:bg-gray-800">
+                        <option value="" selected=move || param("language").is_empty()>
+                            "Any language"
+                        </option>
+                        <Transition fallback=|| ()>
+                            {move || {
+                                langs
+                                    .read(cx)
+                                    .and_then(Result::ok)
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|l| {
+                                        let value = l.clone();
+                                        view! { cx,
+                                            <option
+                                                value=l.clone()
+                                                selected=move || param("language") == value
+                                            >
+                                                {l}
+                                            </option>
+                                        }
+                                    })
+                                    .collect_view(cx)
+                            }}
+                        </Transition>
+                    </select>
+                    <input
+                        type="text"
+                        name="tag"
+                        placeholder="Tag"
+                        class="rounded-md px-2 py-1 dark:bg-gray-800"
+                        prop:value=move || param("tag")
+                    />
+                    <select name="sensitive" class="rounded-md px-2 py-1 dark:bg-gray-800">
+                        <option value="" selected=move || param("sensitive").is_empty()>
+                            "Any content"
+                        </option>
+                        <option value="false" selected=move || param("sensitive") == "false">
+                            "Hide sensitive"
+                        </option>
+                        <option value="true" selected=move || param("sensitive") == "true">
+                            "Only sensitive"
+                        </option>
+                    </select>
+                    <input
+                        type="submit"
+                        class="dark:bg-purple-600 hover:dark:bg-purple-700 rounded-md px-3 py-1 cursor-pointer"
+                        value="Search"
+                    />
+                </div>
+            </Form>
+            <Transition fallback=move || {
+                view! { cx,
+                    <Icon
+                        icon=CgIcon::CgSpinner
+                        class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                    />
+                }
+            }>
+                {move || {
+                    results
+                        .read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("search: {}", e.to_string());
+                                view! { cx, <p class="p-2">"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => view! { cx, <p class="p-2">{e}</p> }.into_view(cx),
+                            Ok(Ok(None)) => ().into_view(cx),
+                            Ok(Ok(Some(SearchResults { redirect: Some(path), .. }))) => {
+                                view! { cx, <Redirect path=path/> }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(Some(r)))
+                                if r.novels.is_empty() && r.chapters.is_empty()
+                                    && r.users.is_empty() => {
+                                view! { cx, <p class="p-2">"No results"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(Some(r))) => {
+                                view! { cx,
+                                    <SearchSection title="Novels" show=!r.novels.is_empty()>
+                                        {r
+                                            .novels
+                                            .into_iter()
+                                            .map(|n| view! { cx, <NovelCard novel=n/> })
+                                            .collect_view(cx)}
+                                    </SearchSection>
+                                    <SearchSection title="Chapters" show=!r.chapters.is_empty()>
+                                        {r
+                                            .chapters
+                                            .into_iter()
+                                            .map(|c| view! { cx, <ChapterHit chapter=c/> })
+                                            .collect_view(cx)}
+                                    </SearchSection>
+                                    <SearchSection title="People" show=!r.users.is_empty()>
+                                        {r
+                                            .users
+                                            .into_iter()
+                                            .map(|u| view! { cx, <UserHit user=u/> })
+                                            .collect_view(cx)}
+                                    </SearchSection>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn SearchSection(cx: Scope, title: &'static str, show: bool, children: Children) -> impl IntoView {
+    show.then(|| {
+        view! { cx,
+            <h2 class="p-2 text-2xl">{title}</h2>
+            <ul class="flex flex-col gap-2 my-2">{children(cx)}</ul>
+        }
+    })
+}
+
+#[component]
+fn ChapterHit(cx: Scope, chapter: ChapterResult) -> impl IntoView {
+    view! { cx,
+        <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+            <A href=chapter.novel_path class="text-xl hover:underline">
+                {format!("{}. {}", chapter.sequence, chapter.title)}
+            </A>
+            <span class="text-sm dark:text-gray-400">
+                {chapter.novel_title}
+                {chapter.sensitive.then_some(" · Sensitive")}
+            </span>
+            <p>
+                {chapter
+                    .headline
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, part)| {
+                        if i % 2 == 1 {
+                            view! { cx, <mark class="dark:bg-purple-800 dark:text-white">{part}</mark> }
+                                .into_view(cx)
+                        } else {
+                            part.into_view(cx)
+                        }
+                    })
+                    .collect_view(cx)}
+            </p>
+        </li>
+    }
+}
+
+#[component]
+fn UserHit(cx: Scope, user: UserResult) -> impl IntoView {
+    view! { cx,
+        <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+            <A href=user.path class="text-xl hover:underline">
+                {user.name}
+            </A>
+            <span class="text-sm dark:text-gray-400">{format!("@{}", user.handle)}</span>
+            <p class="line-clamp-2">{user.summary}</p>
+        </li>
+    }
+}
+
+#[server(GetSearchResults, "/server")]
+pub async fn get_search_results(
+    cx: Scope,
+    q: String,
+    genre: String,
+    language: String,
+    tag: String,
+    sensitive: String,
+) -> Result<Result<SearchResults, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use actix_web::web;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        account,
+        api::search::{self, SearchError, SearchQuery},
+        enums::Genres,
+        util::{local_handle, RedisHandle},
+        DbHandle,
+    };
+
+    let (pool, redis, session) = extract(
+        cx,
+        |pool: Data<DbHandle>, redis: web::Data<RedisHandle>, session: Session| async move {
+            (pool, redis, session)
+        },
+    )
+    .await?;
+    let user = account::current_user(pool.app_data(), &session).await.ok();
+
+    match search::resolve(&q, &pool, &redis, user.as_deref()).await {
+        Ok(Some(resolved)) => {
+            return Ok(Ok(SearchResults {
+                redirect: Some(resolved.path),
+                novels: vec![],
+                chapters: vec![],
+                users: vec![],
+            }))
+        }
+        Ok(None) => (),
+        Err(SearchError::InternalServerError(e)) => return Err(ServerFnError::ServerError(e)),
+        Err(e) => return Ok(Err(e.to_string())),
+    }
+
+    let genre = match genre.as_str() {
+        "" => None,
+        g => match Genres::from_str(g) {
+            Ok(g) => Some(g),
+            Err(e) => return Ok(Err(e.to_string())),
+        },
+    };
+    let query = SearchQuery {
+        q,
+        genre,
+        language: Some(language),
+        tag: Some(tag),
+        sensitive: sensitive.parse().ok(),
+        ..Default::default()
+    };
+
+    match search::search(pool.app_data(), query).await {
+        Ok(results) => Ok(Ok(SearchResults {
+            redirect: None,
+            novels: results
+                .novels
+                .into_iter()
+                .map(|n| NovelSummary::from(n.novel))
+                .collect(),
+            chapters: results
+                .chapters
+                .into_iter()
+                .map(|c| ChapterResult {
+                    novel_path: format!(
+                        "/novel/{}",
+                        local_handle(
+                            &c.novel_username.to_string().to_lowercase(),
+                            &c.novel,
+                            c.novel_local
+                        )
+                        .unwrap_or_default()
+                    ),
+                    novel_title: c.novel_title,
+                    sequence: c.sequence,
+                    title: c.title,
+                    headline: c
+                        .headline
+                        .split("<b>")
+                        .flat_map(|part| part.splitn(2, "</b>"))
+                        .map(str::to_string)
+                        .collect(),
+                    sensitive: c.sensitive,
+                })
+                .collect(),
+            users: results
+                .users
+                .into_iter()
+                .map(|u| {
+                    let handle = local_handle(&u.preferred_username, &u.apub_id, u.local)
+                        .unwrap_or(u.preferred_username);
+                    UserResult {
+                        path: format!("/user/{handle}"),
+                        handle,
+                        name: u.name,
+                        summary: u.summary,
+                    }
+                })
+                .collect(),
+        })),
+        Err(SearchError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
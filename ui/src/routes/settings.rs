@@ -0,0 +1,1191 @@
+use crate::components::{basicinput::*, errorview::*, listbox::*, toggle::*};
+use leptos::{html::*, *};
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[component]
+fn SettingsNav(cx: Scope) -> impl IntoView {
+    view! { cx,
+        <nav class="flex flex-row gap-2 overflow-x-auto whitespace-nowrap p-2 text-xl md:text-base">
+            <A
+                href="/settings/password"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Password"
+            </A>
+            <A
+                href="/settings/invites"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Invites"
+            </A>
+            <A
+                href="/settings/tokens"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "API tokens"
+            </A>
+            <A
+                href="/settings/sessions"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Sessions"
+            </A>
+            <A
+                href="/settings/filters"
+                class="rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+            >
+                "Blocks and mutes"
+            </A>
+        </nav>
+    }
+}
+
+#[component]
+pub fn PasswordSettings(cx: Scope) -> impl IntoView {
+    let change = create_server_action::<ChangePassword>(cx);
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let (changed, set_changed) = create_signal(cx, false);
+    let response = change.value();
+    let result = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(_)) => {
+                set_errormsg(String::new());
+                set_changed(true);
+            }
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Title text="Password"/>
+        <div class="mx-auto max-w-2xl px-4">
+            <SettingsNav/>
+            <h1 class="p-2 text-3xl">"Change password"</h1>
+            <ActionForm action=change class="space-y-4 p-2 w-full">
+                <div class="relative">
+                    <input
+                        type="password"
+                        class="basic-input peer"
+                        placeholder=" "
+                        name="current_password"
+                        required
+                    />
+                    <FloatingLabel target="current_password">"Current password"</FloatingLabel>
+                </div>
+                <div class="relative">
+                    <input
+                        type="password"
+                        class="basic-input peer"
+                        placeholder=" "
+                        name="new_password"
+                        minlength=8
+                        required
+                    />
+                    <FloatingLabel target="new_password">"New password"</FloatingLabel>
+                </div>
+                <div class="relative">
+                    <input
+                        type="password"
+                        class="basic-input peer"
+                        placeholder=" "
+                        name="confirm_password"
+                        minlength=8
+                        required
+                    />
+                    <FloatingLabel target="confirm_password">"Confirm new password"</FloatingLabel>
+                </div>
+                <input type="submit" class="button-1" value="Change password"/>
+            </ActionForm>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {result}
+            </div>
+            {move || {
+                changed()
+                    .then(|| {
+                        view! { cx,
+                            <p class="text-center dark:text-gray-400">
+                                "Your password was changed and all other sessions were signed out."
+                            </p>
+                        }
+                    })
+            }}
+        </div>
+    }
+}
+
+#[server(ChangePassword, "/server")]
+pub async fn change_password(
+    cx: Scope,
+    current_password: String,
+    new_password: String,
+    confirm_password: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use actix_web::web;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, PasswordChangeError},
+        util::AppState,
+        DbHandle,
+    };
+
+    if new_password != confirm_password {
+        return Ok(Err("Passwords do not match".to_string()));
+    }
+
+    let (pool, state, session) = extract(
+        cx,
+        |pool: Data<DbHandle>, state: web::Data<AppState>, session: Session| async move {
+            (pool, state, session)
+        },
+    )
+    .await?;
+
+    match account::change_password(
+        pool.app_data(),
+        &state,
+        &session,
+        current_password,
+        new_password,
+    )
+    .await
+    {
+        Ok(_) => Ok(Ok(())),
+        Err(PasswordChangeError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(PasswordChangeError::Unauthorized(e)) | Err(PasswordChangeError::BadRequest(e)) => {
+            Ok(Err(e))
+        }
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenItem {
+    id: String,
+    name: String,
+    app: Option<String>,
+    scopes: Vec<String>,
+    published: String,
+    last_used: Option<String>,
+}
+
+#[component]
+pub fn TokenSettings(cx: Scope) -> impl IntoView {
+    let create = create_server_action::<CreateToken>(cx);
+    let revoke = create_server_action::<RevokeToken>(cx);
+    let tokens = create_resource(
+        cx,
+        move || (create.version().get(), revoke.version().get()),
+        move |_| get_tokens(cx),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let (new_token, set_new_token) = create_signal::<Option<String>>(cx, None);
+    let response = create.value();
+    let created = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(token)) => {
+                set_errormsg(String::new());
+                set_new_token(Some(token));
+            }
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    let read = create_rw_signal(cx, true);
+    let write = create_rw_signal(cx, false);
+    let write_novels = create_rw_signal(cx, false);
+    let write_chapters = create_rw_signal(cx, false);
+    let follow = create_rw_signal(cx, false);
+    let scope_refs = [(); 5].map(|_| create_node_ref::<Input>(cx));
+
+    view! { cx,
+        <Title text="API tokens"/>
+        <div class="mx-auto max-w-2xl px-4">
+            <SettingsNav/>
+            <h1 class="p-2 text-3xl">"API tokens"</h1>
+            <ActionForm action=create class="space-y-4 p-2 w-full">
+                <div class="relative">
+                    <input type="text" class="basic-input peer" placeholder=" " name="name" required/>
+                    <FloatingLabel target="name">"Token name"</FloatingLabel>
+                </div>
+                <input type="hidden" name="read" value=move || read().to_string()/>
+                <input type="hidden" name="write" value=move || write().to_string()/>
+                <input type="hidden" name="write_novels" value=move || write_novels().to_string()/>
+                <input
+                    type="hidden"
+                    name="write_chapters"
+                    value=move || write_chapters().to_string()
+                />
+                <input type="hidden" name="follow" value=move || follow().to_string()/>
+                <div class="flex flex-col gap-2 items-start">
+                    <Toggle value=read node_ref=scope_refs[0]>
+                        "read: Read your account data"
+                    </Toggle>
+                    <Toggle value=write node_ref=scope_refs[1]>
+                        "write: Create and modify all content"
+                    </Toggle>
+                    <Toggle value=write_novels node_ref=scope_refs[2]>
+                        "write:novels: Create novels"
+                    </Toggle>
+                    <Toggle value=write_chapters node_ref=scope_refs[3]>
+                        "write:chapters: Add chapters"
+                    </Toggle>
+                    <Toggle value=follow node_ref=scope_refs[4]>
+                        "follow: Follow and unfollow"
+                    </Toggle>
+                </div>
+                <input type="submit" class="button-1" value="Generate token"/>
+            </ActionForm>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {created}
+            </div>
+            {move || {
+                new_token()
+                    .map(|token| {
+                        view! { cx,
+                            <div class="dark:bg-gray-800 rounded-xl px-4 py-2 my-2">
+                                <span class="text-gray-500 dark:text-gray-400">
+                                    "Copy your new token now. It will not be shown again."
+                                </span>
+                                <code class="block break-all p-2 dark:bg-gray-950 rounded-md">
+                                    {token}
+                                </code>
+                            </div>
+                        }
+                    })
+            }}
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        tokens
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("tokens: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(tokens)) => {
+                                    tokens
+                                        .into_iter()
+                                        .map(|t| {
+                                            view! { cx,
+                                                <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col">
+                                                        <span class="font-bold">{t.name}</span>
+                                                        {t
+                                                            .app
+                                                            .map(|a| {
+                                                                view! { cx,
+                                                                    <span class="text-sm dark:text-gray-400">
+                                                                        {format!("Authorized application: {a}")}
+                                                                    </span>
+                                                                }
+                                                            })}
+                                                        <span class="text-sm dark:text-gray-400">
+                                                            {t.scopes.join(" ")}
+                                                        </span>
+                                                        <span class="text-sm dark:text-gray-500">
+                                                            {format!(
+                                                                "Created {}, last used {}", t.published, t.last_used
+                                                                .unwrap_or_else(|| "never".to_string())
+                                                            )}
+                                                        </span>
+                                                    </div>
+                                                    <ActionForm action=revoke class="my-auto">
+                                                        <input type="hidden" name="id" value=t.id/>
+                                                        <input
+                                                            type="submit"
+                                                            class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                            value="Revoke"
+                                                        />
+                                                    </ActionForm>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[server(GetTokens, "/server")]
+pub async fn get_tokens(cx: Scope) -> Result<Result<Vec<TokenItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        oauth, DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    let tokens = oauth::list_tokens(pool.app_data(), &apub_id)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+        .into_iter()
+        .map(|t| TokenItem {
+            id: t.id.to_string(),
+            name: t.name,
+            app: t.app,
+            scopes: t.scopes,
+            published: HumanTime::from(t.published).to_string(),
+            last_used: t.last_used.map(|l| HumanTime::from(l).to_string()),
+        })
+        .collect();
+    Ok(Ok(tokens))
+}
+
+#[server(CreateToken, "/server")]
+pub async fn create_token(
+    cx: Scope,
+    name: String,
+    read: bool,
+    write: bool,
+    write_novels: bool,
+    write_chapters: bool,
+    follow: bool,
+) -> Result<Result<String, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        enums::Scopes,
+        oauth::{self, OAuthError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    let scopes = [
+        (read, Scopes::Read),
+        (write, Scopes::Write),
+        (write_novels, Scopes::WriteNovels),
+        (write_chapters, Scopes::WriteChapters),
+        (follow, Scopes::Follow),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, scope)| enabled.then_some(scope))
+    .collect();
+
+    match oauth::create_personal_token(pool.app_data(), &apub_id, name, scopes).await {
+        Ok(token) => Ok(Ok(token)),
+        Err(OAuthError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(RevokeToken, "/server")]
+pub async fn revoke_token(cx: Scope, id: String) -> Result<(), ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{account, oauth, DbHandle};
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = account::current_user(pool.app_data(), &session)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    let id = id
+        .parse()
+        .map_err(|_| ServerFnError::ServerError("Invalid token".to_string()))?;
+
+    oauth::revoke_token(pool.app_data(), &apub_id, id)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionItem {
+    id: String,
+    client_app: String,
+    client_website: Option<String>,
+    user_agent: Option<String>,
+    ip: Option<String>,
+    published: String,
+    last_seen: String,
+    current: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NoticeItem {
+    id: String,
+    message: String,
+    published: String,
+}
+
+#[component]
+pub fn SessionSettings(cx: Scope) -> impl IntoView {
+    let revoke = create_server_action::<RevokeSession>(cx);
+    let revoke_others = create_server_action::<RevokeOtherSessions>(cx);
+    let dismiss = create_server_action::<DismissNotice>(cx);
+    let sessions = create_resource(
+        cx,
+        move || (revoke.version().get(), revoke_others.version().get()),
+        move |_| get_sessions(cx),
+    );
+    let notices = create_resource(
+        cx,
+        move || dismiss.version().get(),
+        move |_| get_notices(cx),
+    );
+
+    view! { cx,
+        <Title text="Sessions"/>
+        <div class="mx-auto max-w-2xl px-4">
+            <SettingsNav/>
+            <div class="flex flex-row justify-between p-2">
+                <h1 class="text-3xl">"Sessions"</h1>
+                <ActionForm action=revoke_others class="my-auto">
+                    <input
+                        type="submit"
+                        class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                        value="Sign out all other sessions"
+                    />
+                </ActionForm>
+            </div>
+            <Transition fallback=|| ()>
+                {move || {
+                    notices
+                        .read(cx)
+                        .map(|v| match v {
+                            Ok(Ok(notices)) => {
+                                notices
+                                    .into_iter()
+                                    .map(|n| {
+                                        view! { cx,
+                                            <div class="flex flex-row justify-between gap-2 m-2 px-4 py-2 rounded-xl dark:bg-yellow-900">
+                                                <div class="flex flex-col">
+                                                    <span>{n.message}</span>
+                                                    <span class="text-sm dark:text-gray-400">{n.published}</span>
+                                                </div>
+                                                <ActionForm action=dismiss class="my-auto">
+                                                    <input type="hidden" name="id" value=n.id/>
+                                                    <input
+                                                        type="submit"
+                                                        class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                                        value="Dismiss"
+                                                    />
+                                                </ActionForm>
+                                            </div>
+                                        }
+                                    })
+                                    .collect_view(cx)
+                            }
+                            Ok(Err(_)) => ().into_view(cx),
+                            Err(e) => {
+                                error!("notices: {}", e.to_string());
+                                ().into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        sessions
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("sessions: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(sessions)) => {
+                                    sessions
+                                        .into_iter()
+                                        .map(|s| {
+                                            view! { cx,
+                                                <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col">
+                                                        <span class="font-bold">
+                                                            {s
+                                                                .user_agent
+                                                                .unwrap_or_else(|| "Unknown device".to_string())}
+                                                        </span>
+                                                        <span class="text-sm dark:text-gray-400">
+                                                            {match s.client_website {
+                                                                Some(w) => format!("{} ({w})", s.client_app),
+                                                                None => s.client_app,
+                                                            }}
+                                                        </span>
+                                                        {s
+                                                            .ip
+                                                            .map(|ip| {
+                                                                view! { cx,
+                                                                    <span class="text-sm dark:text-gray-400">{ip}</span>
+                                                                }
+                                                            })}
+                                                        <span class="text-sm dark:text-gray-500">
+                                                            {format!(
+                                                                "Signed in {}, last seen {}", s.published, s.last_seen
+                                                            )}
+                                                        </span>
+                                                    </div>
+                                                    {if s.current {
+                                                        view! { cx,
+                                                            <span class="my-auto text-sm dark:text-gray-400">
+                                                                "This session"
+                                                            </span>
+                                                        }
+                                                            .into_view(cx)
+                                                    } else {
+                                                        view! { cx,
+                                                            <ActionForm action=revoke class="my-auto">
+                                                                <input type="hidden" name="id" value=s.id/>
+                                                                <input
+                                                                    type="submit"
+                                                                    class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                                    value="Revoke"
+                                                                />
+                                                            </ActionForm>
+                                                        }
+                                                            .into_view(cx)
+                                                    }}
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[server(GetSessions, "/server")]
+pub async fn get_sessions(cx: Scope) -> Result<Result<Vec<SessionItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let sessions = match account::list_sessions(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(s) => s,
+    };
+
+    Ok(Ok(sessions
+        .into_iter()
+        .map(|s| SessionItem {
+            id: s.id.to_string(),
+            client_app: s.client_app,
+            client_website: s.client_website,
+            user_agent: s.user_agent,
+            ip: s.ip,
+            published: HumanTime::from(s.published).to_string(),
+            last_seen: HumanTime::from(s.last_seen).to_string(),
+            current: s.current,
+        })
+        .collect()))
+}
+
+#[server(RevokeSession, "/server")]
+pub async fn revoke_session(cx: Scope, id: String) -> Result<(), ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{account, DbHandle};
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let id = id
+        .parse()
+        .map_err(|_| ServerFnError::ServerError("Invalid session".to_string()))?;
+
+    account::revoke_session(pool.app_data(), &session, id)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[server(RevokeOtherSessions, "/server")]
+pub async fn revoke_other_sessions(cx: Scope) -> Result<(), ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{account, DbHandle};
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    account::revoke_other_sessions(pool.app_data(), &session)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[server(GetNotices, "/server")]
+pub async fn get_notices(cx: Scope) -> Result<Result<Vec<NoticeItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let notices = match account::list_notices(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(n) => n,
+    };
+
+    Ok(Ok(notices
+        .into_iter()
+        .map(|n| NoticeItem {
+            id: n.id.to_string(),
+            message: n.message,
+            published: HumanTime::from(n.published).to_string(),
+        })
+        .collect()))
+}
+
+#[server(DismissNotice, "/server")]
+pub async fn dismiss_notice(cx: Scope, id: String) -> Result<(), ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{account, DbHandle};
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let id = id
+        .parse()
+        .map_err(|_| ServerFnError::ServerError("Invalid notice".to_string()))?;
+
+    account::dismiss_notice(pool.app_data(), &session, id)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InviteItem {
+    code: String,
+    link: String,
+    uses: String,
+    expires: String,
+}
+
+#[component]
+pub fn InviteSettings(cx: Scope) -> impl IntoView {
+    let create = create_server_action::<CreateInvite>(cx);
+    let delete = create_server_action::<DeleteInvite>(cx);
+    let invites = create_resource(
+        cx,
+        move || (create.version().get(), delete.version().get()),
+        move |_| get_invites(cx),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let response = create.value();
+    let created = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(_)) => set_errormsg(String::new()),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Title text="Invites"/>
+        <div class="mx-auto max-w-2xl px-4">
+            <SettingsNav/>
+            <h1 class="p-2 text-3xl">"Invites"</h1>
+            <ActionForm action=create class="space-y-4 p-2 w-full">
+                <div class="relative">
+                    <input type="number" class="basic-input peer" placeholder=" " name="max_uses" min=1/>
+                    <FloatingLabel target="max_uses">"Use limit (optional)"</FloatingLabel>
+                </div>
+                <div class="relative">
+                    <input type="number" class="basic-input peer" placeholder=" " name="expires_in" min=1/>
+                    <FloatingLabel target="expires_in">"Expires after hours (optional)"</FloatingLabel>
+                </div>
+                <input type="submit" class="button-1" value="Generate invite link"/>
+            </ActionForm>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {created}
+            </div>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        invites
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("invites: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(invites)) => {
+                                    invites
+                                        .into_iter()
+                                        .map(|i| {
+                                            view! { cx,
+                                                <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col min-w-0">
+                                                        <code class="break-all p-1 dark:bg-gray-950 rounded-md">
+                                                            {i.link}
+                                                        </code>
+                                                        <span class="text-sm dark:text-gray-400">{i.uses}</span>
+                                                        <span class="text-sm dark:text-gray-500">{i.expires}</span>
+                                                    </div>
+                                                    <ActionForm action=delete class="my-auto">
+                                                        <input type="hidden" name="code" value=i.code/>
+                                                        <input
+                                                            type="submit"
+                                                            class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                            value="Delete"
+                                                        />
+                                                    </ActionForm>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[server(GetInvites, "/server")]
+pub async fn get_invites(cx: Scope) -> Result<Result<Vec<InviteItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use actix_web::web;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use std::time::SystemTime;
+    use wordforge_api::{
+        invite::{self, InviteError},
+        util::AppState,
+        DbHandle,
+    };
+
+    let (pool, state, session) = extract(
+        cx,
+        |pool: Data<DbHandle>, state: web::Data<AppState>, session: Session| async move {
+            (pool, state, session)
+        },
+    )
+    .await?;
+
+    let invites = match invite::list_invites(pool.app_data(), &session).await {
+        Err(InviteError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(i) => i,
+    };
+
+    Ok(Ok(invites
+        .into_iter()
+        .map(|i| InviteItem {
+            link: format!(
+                "{}://{}/auth?redirect_to=%2F&invite={}",
+                state.scheme,
+                pool.domain(),
+                i.code
+            ),
+            code: i.code,
+            uses: match i.max_uses {
+                Some(max) => format!("Used {} of {max} times", i.uses),
+                None => format!("Used {} times", i.uses),
+            },
+            expires: match i.expires {
+                Some(e) if SystemTime::from(e) < SystemTime::now() => "Expired".to_string(),
+                Some(e) => format!("Expires {}", HumanTime::from(e)),
+                None => "Never expires".to_string(),
+            },
+        })
+        .collect()))
+}
+
+#[server(CreateInvite, "/server")]
+pub async fn create_invite(
+    cx: Scope,
+    max_uses: String,
+    expires_in: String,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        invite::{self, InviteError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let max_uses = match max_uses.as_str() {
+        "" => None,
+        m => match m.parse() {
+            Ok(m) => Some(m),
+            Err(_) => return Ok(Err("Invalid use limit".to_string())),
+        },
+    };
+    let expires_in = match expires_in.as_str() {
+        "" => None,
+        e => match e.parse() {
+            Ok(e) => Some(e),
+            Err(_) => return Ok(Err("Invalid expiry".to_string())),
+        },
+    };
+
+    match invite::create_invite(pool.app_data(), &session, max_uses, expires_in).await {
+        Ok(_) => Ok(Ok(())),
+        Err(InviteError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(InviteError::Unauthorized(e)) | Err(InviteError::BadRequest(e)) => Ok(Err(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(DeleteInvite, "/server")]
+pub async fn delete_invite(cx: Scope, code: String) -> Result<(), ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{invite, DbHandle};
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    invite::delete_invite(pool.app_data(), &session, &code)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FilterItem {
+    target: String,
+    kind: String,
+    domain: bool,
+    published: String,
+}
+
+#[component]
+pub fn FilterSettings(cx: Scope) -> impl IntoView {
+    let add = create_server_action::<AddFilter>(cx);
+    let remove = create_server_action::<RemoveFilter>(cx);
+    let filters = create_resource(
+        cx,
+        move || (add.version().get(), remove.version().get()),
+        move |_| get_filters(cx),
+    );
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let response = add.value();
+    let added = move || {
+        response.get().map(|v| match v {
+            Ok(Ok(_)) => set_errormsg(String::new()),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    let kind = create_rw_signal(cx, "block".to_string());
+    let domain = create_rw_signal(cx, false);
+    let domain_ref = create_node_ref::<Input>(cx);
+
+    view! { cx,
+        <Title text="Blocks and mutes"/>
+        <div class="mx-auto max-w-2xl px-4">
+            <SettingsNav/>
+            <h1 class="p-2 text-3xl">"Blocks and mutes"</h1>
+            <p class="p-2 dark:text-gray-400">
+                "Blocked accounts can't follow you and are told about the block. Muted accounts are only hidden from your feeds and notifications."
+            </p>
+            <ActionForm action=add class="space-y-4 p-2 w-full">
+                <div class="relative">
+                    <input type="text" class="basic-input peer" placeholder=" " name="target" required/>
+                    <FloatingLabel target="target">
+                        {move || if domain() { "Domain" } else { "Username, handle or URL" }}
+                    </FloatingLabel>
+                </div>
+                <input type="hidden" name="kind" value=move || kind.get()/>
+                <input type="hidden" name="domain" value=move || domain().to_string()/>
+                <FilterListbox
+                    option=kind
+                    name="kind"
+                    label="Action"
+                    initial="block"
+                    items=vec!["block".to_string(), "mute".to_string()]
+                />
+                <Toggle value=domain node_ref=domain_ref>
+                    "Apply to the whole domain"
+                </Toggle>
+                <input type="submit" class="button-1" value="Save"/>
+            </ActionForm>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {added}
+            </div>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        filters
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("filters: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(filters)) => {
+                                    filters
+                                        .into_iter()
+                                        .map(|f| {
+                                            view! { cx,
+                                                <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col min-w-0">
+                                                        <span class="font-bold break-all">{f.target.clone()}</span>
+                                                        <span class="text-sm dark:text-gray-400">
+                                                            {match (f.kind.as_str(), f.domain) {
+                                                                ("block", true) => "Blocked domain",
+                                                                ("block", false) => "Blocked",
+                                                                (_, true) => "Muted domain",
+                                                                (_, false) => "Muted",
+                                                            }}
+                                                        </span>
+                                                        <span class="text-sm dark:text-gray-500">{f.published}</span>
+                                                    </div>
+                                                    <ActionForm action=remove class="my-auto">
+                                                        <input type="hidden" name="kind" value=f.kind.clone()/>
+                                                        <input type="hidden" name="target" value=f.target/>
+                                                        <input
+                                                            type="submit"
+                                                            class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                            value=if f.kind == "block" { "Unblock" } else { "Unmute" }
+                                                        />
+                                                    </ActionForm>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+#[server(GetFilters, "/server")]
+pub async fn get_filters(cx: Scope) -> Result<Result<Vec<FilterItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        enums::FilterKind,
+        user_filter, DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    let mut filters = Vec::new();
+    for kind in [FilterKind::Block, FilterKind::Mute] {
+        filters.extend(
+            user_filter::list_filters(pool.app_data(), &apub_id, kind)
+                .await
+                .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+                .into_iter()
+                .map(|f| FilterItem {
+                    target: f.target,
+                    kind: f.kind.to_string(),
+                    domain: f.domain,
+                    published: HumanTime::from(f.published).to_string(),
+                }),
+        );
+    }
+    Ok(Ok(filters))
+}
+
+#[server(AddFilter, "/server")]
+pub async fn add_filter(
+    cx: Scope,
+    target: String,
+    kind: String,
+    domain: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        enums::FilterKind,
+        user_filter::{self, FilterError},
+        DbHandle,
+    };
+
+    let (data, session) = extract(cx, |data: Data<DbHandle>, session: Session| async move {
+        (data, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(data.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let kind = match FilterKind::from_str(&kind) {
+        Ok(k) => k,
+        Err(e) => return Ok(Err(e.to_string())),
+    };
+
+    match user_filter::add_filter(&data, &apub_id, kind, &target, domain).await {
+        Ok(_) => Ok(Ok(())),
+        Err(FilterError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(FilterError::BadRequest(e)) => Ok(Err(e)),
+        Err(FilterError::NotFound) => Ok(Err("User not found".to_string())),
+    }
+}
+
+#[server(RemoveFilter, "/server")]
+pub async fn remove_filter(cx: Scope, target: String, kind: String) -> Result<(), ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use std::str::FromStr;
+    use wordforge_api::{account, enums::FilterKind, user_filter, DbHandle};
+
+    let (data, session) = extract(cx, |data: Data<DbHandle>, session: Session| async move {
+        (data, session)
+    })
+    .await?;
+
+    let apub_id = account::current_user(data.app_data(), &session)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    let kind =
+        FilterKind::from_str(&kind).map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+
+    user_filter::remove_filter(&data, &apub_id, kind, &target)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
@@ -0,0 +1,400 @@
+use crate::{
+    components::{basicinput::*, errorview::*, novelcard::*, shelf::ShelfItem, toggle::*},
+    path::ShelfViewParams,
+};
+use leptos::{html::*, *};
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShelfDetails {
+    name: String,
+    public: bool,
+    owner_name: String,
+    owner_path: String,
+    novels: Vec<NovelSummary>,
+}
+
+fn kind_label(kind: &str) -> &'static str {
+    if kind == "custom" {
+        "Custom list"
+    } else {
+        "Built-in list"
+    }
+}
+
+/// The signed in user's reading lists.
+#[component]
+pub fn ReadingLists(cx: Scope) -> impl IntoView {
+    let create = create_server_action::<CreateShelf>(cx);
+    let set_public = create_server_action::<SetShelfPublic>(cx);
+    let delete = create_server_action::<DeleteShelf>(cx);
+    let shelves = create_resource(
+        cx,
+        move || {
+            (
+                create.version().get(),
+                set_public.version().get(),
+                delete.version().get(),
+            )
+        },
+        move |_| get_shelves(cx),
+    );
+    let public = create_rw_signal(cx, false);
+    let public_ref = create_node_ref::<Input>(cx);
+
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let results = move || {
+        [
+            create.value().get(),
+            set_public.value().get(),
+            delete.value().get(),
+        ]
+        .into_iter()
+        .flatten()
+        .for_each(|v| match v {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => set_errormsg(e),
+            Err(e) => set_errormsg(e.to_string()),
+        })
+    };
+
+    view! { cx,
+        <Title text="Reading lists"/>
+        <div class="mx-auto max-w-2xl px-4">
+            <h1 class="p-2 text-3xl">"Reading lists"</h1>
+            <ActionForm action=create class="space-y-4 p-2 w-full">
+                <div class="relative">
+                    <input type="text" class="basic-input peer" placeholder=" " name="name" required/>
+                    <FloatingLabel target="name">"New list"</FloatingLabel>
+                </div>
+                <input type="hidden" name="public" value=move || public().to_string()/>
+                <div class="flex justify-start">
+                    <Toggle value=public node_ref=public_ref>
+                        "Public"
+                    </Toggle>
+                </div>
+                <input type="submit" class="button-1" value="Create list"/>
+            </ActionForm>
+            <div class="flex mx-auto text-2xl m-4 justify-center text-center">
+                <ErrorView message=errormsg/>
+                {results}
+            </div>
+            <ul class="flex flex-col gap-2 my-2">
+                <Transition fallback=move || {
+                    view! { cx,
+                        <Icon
+                            icon=CgIcon::CgSpinner
+                            class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                        />
+                    }
+                }>
+                    {move || {
+                        shelves
+                            .read(cx)
+                            .map(|v| match v {
+                                Err(e) => {
+                                    error!("shelves: {}", e.to_string());
+                                    view! { cx, <li>"Something went wrong"</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Err(e)) => {
+                                    view! { cx, <li>{e}</li> }
+                                        .into_view(cx)
+                                }
+                                Ok(Ok(shelves)) => {
+                                    shelves
+                                        .into_iter()
+                                        .map(|s| {
+                                            let custom = s.kind == "custom";
+                                            view! { cx,
+                                                <li class="flex flex-row justify-between gap-2 dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                    <div class="flex flex-col">
+                                                        <A href=format!("/shelves/{}", s.id) class="text-xl hover:underline">
+                                                            {s.name}
+                                                        </A>
+                                                        <span class="text-sm dark:text-gray-400">
+                                                            {kind_label(&s.kind)} " · "
+                                                            {if s.public { "Public" } else { "Private" }} " · "
+                                                            {format!("{} novel(s)", s.novels)}
+                                                        </span>
+                                                    </div>
+                                                    <div class="flex flex-row gap-2 my-auto">
+                                                        <ActionForm action=set_public>
+                                                            <input type="hidden" name="id" value=s.id.clone()/>
+                                                            <input type="hidden" name="public" value=(!s.public).to_string()/>
+                                                            <input
+                                                                type="submit"
+                                                                class="dark:bg-gray-700 hover:dark:bg-gray-600 rounded-md px-3 py-1 cursor-pointer"
+                                                                value=if s.public { "Make private" } else { "Make public" }
+                                                            />
+                                                        </ActionForm>
+                                                        {custom
+                                                            .then(|| {
+                                                                view! { cx,
+                                                                    <ActionForm action=delete>
+                                                                        <input type="hidden" name="id" value=s.id.clone()/>
+                                                                        <input
+                                                                            type="submit"
+                                                                            class="dark:bg-red-700 hover:dark:bg-red-800 rounded-md px-3 py-1 cursor-pointer"
+                                                                            value="Delete"
+                                                                        />
+                                                                    </ActionForm>
+                                                                }
+                                                            })}
+                                                    </div>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view(cx)
+                                }
+                            })
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
+/// A public reading list, or one of the signed in user's private ones.
+#[component]
+pub fn ShelfView(cx: Scope) -> impl IntoView {
+    let params = use_params::<ShelfViewParams>(cx);
+    let id = move || {
+        params
+            .with(|params| params.clone().map(|p| p.id))
+            .unwrap_or_default()
+    };
+    let shelf = create_resource(cx, id, move |id| get_shelf(cx, id));
+
+    view! { cx,
+        <div class="mx-auto max-w-2xl px-4">
+            <Transition fallback=move || {
+                view! { cx,
+                    <Icon
+                        icon=CgIcon::CgSpinner
+                        class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                    />
+                }
+            }>
+                {move || {
+                    shelf
+                        .read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("shelf: {}", e.to_string());
+                                view! { cx, <p class="p-2">"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => {
+                                view! { cx,
+                                    <Title text="Not found"/>
+                                    <p class="p-2">{e}</p>
+                                }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(s)) => {
+                                view! { cx,
+                                    <Title text=s.name.clone()/>
+                                    <h1 class="p-2 text-3xl">{s.name}</h1>
+                                    <span class="px-2 dark:text-gray-400">
+                                        <A href=s.owner_path class="hover:underline">
+                                            {s.owner_name}
+                                        </A>
+                                        {(!s.public).then_some(" · Private")}
+                                    </span>
+                                    <ul class="flex flex-col gap-2 my-2">
+                                        {if s.novels.is_empty() {
+                                            view! { cx, <li class="p-2">"No novels on this list yet"</li> }
+                                                .into_view(cx)
+                                        } else {
+                                            s.novels
+                                                .into_iter()
+                                                .map(|novel| view! { cx, <NovelCard novel=novel/> })
+                                                .collect_view(cx)
+                                        }}
+                                    </ul>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+#[server(GetShelves, "/server")]
+pub async fn get_shelves(cx: Scope) -> Result<Result<Vec<ShelfItem>, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::shelf::{self, ShelfError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match shelf::list_shelves(pool.app_data(), &apub_id, true).await {
+        Ok(shelves) => Ok(Ok(shelves.into_iter().map(ShelfItem::from).collect())),
+        Err(ShelfError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(CreateShelf, "/server")]
+pub async fn create_shelf(
+    cx: Scope,
+    name: String,
+    public: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::shelf::{self, ShelfError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+
+    match shelf::create_shelf(pool.app_data(), &apub_id, &name, public).await {
+        Ok(_) => Ok(Ok(())),
+        Err(ShelfError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(SetShelfPublic, "/server")]
+pub async fn set_shelf_public(
+    cx: Scope,
+    id: String,
+    public: bool,
+) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::shelf::{self, ShelfError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let id = match id.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("Not found".to_string())),
+    };
+
+    match shelf::set_public(pool.app_data(), &apub_id, id, public).await {
+        Ok(_) => Ok(Ok(())),
+        Err(ShelfError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ShelfError::NotFound) => Ok(Err("Not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(DeleteShelf, "/server")]
+pub async fn delete_shelf(cx: Scope, id: String) -> Result<Result<(), String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::shelf::{self, ShelfError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let apub_id = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(e)) => return Ok(Err(e)),
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => u,
+    };
+    let id = match id.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("Not found".to_string())),
+    };
+
+    match shelf::delete_shelf(pool.app_data(), &apub_id, id).await {
+        Ok(_) => Ok(Ok(())),
+        Err(ShelfError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ShelfError::NotFound) => Ok(Err("Not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
+
+#[server(GetShelf, "/server")]
+pub async fn get_shelf(
+    cx: Scope,
+    id: String,
+) -> Result<Result<ShelfDetails, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        account,
+        api::shelf::{self, ShelfError},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let viewer = account::current_user(pool.app_data(), &session).await.ok();
+    let id = match id.parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(Err("List not found".to_string())),
+    };
+
+    match shelf::get_shelf(pool.app_data(), viewer.as_deref(), id).await {
+        Ok(page) => Ok(Ok(ShelfDetails {
+            name: page.shelf.name,
+            public: page.shelf.public,
+            owner_name: page.owner_name,
+            owner_path: page.owner_path,
+            novels: page.novels.into_iter().map(NovelSummary::from).collect(),
+        })),
+        Err(ShelfError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ShelfError::NotFound) => Ok(Err("List not found".to_string())),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
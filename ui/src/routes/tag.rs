@@ -0,0 +1,133 @@
+use crate::{components::novelcard::*, path::TagViewParams};
+use leptos::*;
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TagPage {
+    total: i64,
+    page_size: i64,
+    novels: Vec<NovelSummary>,
+}
+
+#[component]
+pub fn TagView(cx: Scope) -> impl IntoView {
+    let params = use_params::<TagViewParams>(cx);
+    let query = use_query_map(cx);
+    let tag = move || {
+        params
+            .with(|params| params.clone().map(|p| p.tag))
+            .unwrap_or_default()
+    };
+    let page = move || {
+        query.with(|q| {
+            q.get("page")
+                .and_then(|p| p.parse::<i64>().ok())
+                .unwrap_or(0)
+                .max(0)
+        })
+    };
+    let novels = create_resource(
+        cx,
+        move || (tag(), page()),
+        move |(tag, page)| get_tag_page(cx, tag, page),
+    );
+
+    view! { cx,
+        <Title text=move || format!("#{}", tag())/>
+        <div class="mx-auto max-w-4xl px-4">
+            <h1 class="p-2 text-3xl">{move || format!("#{}", tag())}</h1>
+            <Transition fallback=move || {
+                view! { cx,
+                    <Icon
+                        icon=CgIcon::CgSpinner
+                        class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                    />
+                }
+            }>
+                {move || {
+                    novels
+                        .read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("tag: {}", e.to_string());
+                                view! { cx, <p class="p-2">"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => view! { cx, <p class="p-2">{e}</p> }.into_view(cx),
+                            Ok(Ok(t)) if t.novels.is_empty() => {
+                                view! { cx, <p class="p-2">"No novels with this tag yet"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(t)) => {
+                                let page = page();
+                                let more = (page + 1) * t.page_size < t.total;
+                                view! { cx,
+                                    <p class="px-2 dark:text-gray-400">
+                                        {format!(
+                                            "{} novel{}", t.total, if t.total == 1 { "" } else { "s" }
+                                        )}
+                                    </p>
+                                    <ul class="flex flex-col gap-2 my-2">
+                                        {t
+                                            .novels
+                                            .into_iter()
+                                            .map(|n| view! { cx, <NovelCard novel=n/> })
+                                            .collect_view(cx)}
+                                    </ul>
+                                    <div class="flex flex-row justify-between p-2">
+                                        {(page > 0)
+                                            .then(|| {
+                                                view! { cx,
+                                                    <A href=format!("?page={}", page - 1) class="hover:underline">
+                                                        "Newer"
+                                                    </A>
+                                                }
+                                            })}
+                                        <span></span>
+                                        {more
+                                            .then(|| {
+                                                view! { cx,
+                                                    <A href=format!("?page={}", page + 1) class="hover:underline">
+                                                        "Older"
+                                                    </A>
+                                                }
+                                            })}
+                                    </div>
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+#[server(GetTagPage, "/server")]
+pub async fn get_tag_page(
+    cx: Scope,
+    tag: String,
+    page: i64,
+) -> Result<Result<TagPage, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        api::tag::{self, TagError, PAGE_SIZE},
+        DbHandle,
+    };
+
+    let pool = extract(cx, |pool: Data<DbHandle>| async move { pool }).await?;
+
+    match tag::novels_with_tag(pool.app_data(), &tag, page).await {
+        Ok(t) => Ok(Ok(TagPage {
+            total: t.total,
+            page_size: PAGE_SIZE,
+            novels: t.novels.into_iter().map(NovelSummary::from).collect(),
+        })),
+        Err(TagError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(e) => Ok(Err(e.to_string())),
+    }
+}
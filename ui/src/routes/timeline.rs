@@ -0,0 +1,329 @@
+use crate::components::{errorview::ErrorView, novelcard::*, trending::*};
+use leptos::*;
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+use std::{cell::Cell, rc::Rc};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimelinePage {
+    entries: Vec<TimelineItem>,
+    /// The time and ID of the entry the next page starts after. Unset on the
+    /// last one.
+    next: Option<(String, String)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimelineItem {
+    key: String,
+    kind: String,
+    published: String,
+    novel: NovelSummary,
+    chapter: Option<TimelineChapter>,
+    /// The name and path of who boosted it, for boosts.
+    booster: Option<(String, String)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimelineChapter {
+    sequence: i32,
+    title: String,
+    summary: String,
+    sensitive: bool,
+}
+
+/// Whether the bottom of the page is close enough to load more.
+fn near_end() -> bool {
+    let near = || {
+        let height = window().inner_height().ok()?.as_f64()?;
+        let scrolled = window().scroll_y().ok()?;
+        let total = document().document_element()?.scroll_height() as f64;
+        Some(scrolled + height >= total - 400.0)
+    };
+    near().unwrap_or(false)
+}
+
+/// Entries of the `timeline` ("home", "local" or "public"), with more loaded
+/// when scrolling to the end. `sensitive` includes sensitive novels and
+/// chapters in the public ones.
+#[component]
+pub fn Timeline(
+    cx: Scope,
+    timeline: &'static str,
+    #[prop(optional)] sensitive: bool,
+    /// Shown when there is nothing in the timeline.
+    empty: &'static str,
+) -> impl IntoView {
+    let (entries, set_entries) = create_signal(cx, Vec::<TimelineItem>::new());
+    let (next, set_next) = create_signal::<Option<(String, String)>>(cx, None);
+    let (done, set_done) = create_signal(cx, false);
+    let (errormsg, set_errormsg) = create_signal(cx, String::new());
+    let load = create_action(cx, move |(before, before_id): &(String, String)| {
+        get_timeline(
+            cx,
+            timeline.to_string(),
+            before.clone(),
+            before_id.clone(),
+            sensitive,
+        )
+    });
+    let pending = load.pending();
+    let response = load.value();
+
+    create_effect(cx, move |_| match response.get() {
+        None => (),
+        Some(Ok(Ok(page))) => {
+            set_entries.update(|e| e.extend(page.entries));
+            set_done(page.next.is_none());
+            set_next(page.next);
+        }
+        Some(Ok(Err(e))) => {
+            set_errormsg(e);
+            set_done(true);
+        }
+        Some(Err(e)) => set_errormsg(e.to_string()),
+    });
+
+    let load_more = move || {
+        if !pending.get_untracked() && !done.get_untracked() {
+            load.dispatch(next.get_untracked().unwrap_or_default());
+        }
+    };
+
+    // Effects only run in the browser, so the first page is loaded there.
+    create_effect(cx, move |_| load_more());
+
+    // The listener outlives the component, so it is turned off on cleanup.
+    let active = Rc::new(Cell::new(true));
+    on_cleanup(cx, {
+        let active = active.clone();
+        move || active.set(false)
+    });
+    window_event_listener(ev::scroll, move |_| {
+        if active.get() && near_end() {
+            load_more();
+        }
+    });
+
+    view! { cx,
+        <ul class="flex flex-col gap-4 p-2">
+            <For
+                each=entries
+                key=|e| e.key.clone()
+                view=move |cx, entry| {
+                    view! { cx, <TimelineCard entry=entry/> }
+                }
+            />
+        </ul>
+        <Show
+            when=pending
+            fallback=move |cx| {
+                view! { cx,
+                    <Show when=move || !done() fallback=|_| ()>
+                        <button
+                            class="block mx-auto p-2 hover:underline"
+                            on:click=move |_| load_more()
+                        >
+                            "Load more"
+                        </button>
+                    </Show>
+                }
+            }
+        >
+            <Icon
+                icon=CgIcon::CgSpinner
+                class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+            />
+        </Show>
+        <Show
+            when=move || done() && entries.with(Vec::is_empty) && errormsg.with(String::is_empty)
+            fallback=|_| ()
+        >
+            <p class="p-2">{empty}</p>
+        </Show>
+        <div class="p-2">
+            <ErrorView message=errormsg/>
+        </div>
+    }
+}
+
+fn tab_class(selected: bool) -> &'static str {
+    if selected {
+        "rounded-full px-3 py-1 dark:bg-purple-600"
+    } else {
+        "rounded-full px-3 py-1 dark:bg-gray-800 hover:dark:bg-gray-700"
+    }
+}
+
+/// The local timeline of this instance, or the federated one with everything
+/// it knows about when `local` isn't set.
+#[component]
+pub fn Explore(cx: Scope, local: bool) -> impl IntoView {
+    let query = use_query_map(cx);
+    let sensitive = move || query.with(|q| q.get("sensitive").map(|s| s == "true")) == Some(true);
+    let path = if local { "/explore" } else { "/explore/public" };
+
+    view! { cx,
+        <Title text=if local { "Local" } else { "Public" }/>
+        <div class="mx-auto max-w-2xl px-4">
+            <h1 class="p-2 text-3xl">"Explore"</h1>
+            <div class="flex flex-row flex-wrap gap-2 px-2">
+                <A href="/explore" class=tab_class(local)>
+                    "Local"
+                </A>
+                <A href="/explore/public" class=tab_class(!local)>
+                    "Public"
+                </A>
+                <A
+                    href=move || {
+                        if sensitive() { path.to_string() } else { format!("{path}?sensitive=true") }
+                    }
+                    class=move || tab_class(sensitive())
+                >
+                    "Show sensitive"
+                </A>
+            </div>
+            <Trending/>
+            {move || {
+                let sensitive = sensitive();
+                view! { cx,
+                    <Timeline
+                        timeline=if local { "local" } else { "public" }
+                        sensitive=sensitive
+                        empty="Nothing has been published yet."
+                    />
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn TimelineCard(cx: Scope, entry: TimelineItem) -> impl IntoView {
+    let label = match (entry.kind.as_str(), entry.booster) {
+        (_, Some((name, path))) => view! { cx,
+            <A href=path class="hover:underline">{name}</A>
+            " boosted"
+        }
+        .into_view(cx),
+        ("chapter", None) => "New chapter".into_view(cx),
+        _ => "New novel".into_view(cx),
+    };
+
+    view! { cx,
+        <li class="flex flex-col gap-1">
+            <span class="px-2 text-sm dark:text-gray-400">{label} " · " {entry.published}</span>
+            <ul>
+                {match entry.chapter {
+                    Some(c) => {
+                        view! { cx,
+                            <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+                                <A href=entry.novel.path class="text-xl hover:underline">
+                                    {format!("{}. {}", c.sequence, c.title)}
+                                </A>
+                                <span class="text-sm dark:text-gray-400">
+                                    {entry.novel.title}
+                                    {c.sensitive.then_some(" · Sensitive")}
+                                </span>
+                                <p class="line-clamp-3">{c.summary}</p>
+                            </li>
+                        }
+                            .into_view(cx)
+                    }
+                    None => view! { cx, <NovelCard novel=entry.novel/> }.into_view(cx),
+                }}
+            </ul>
+        </li>
+    }
+}
+
+#[server(GetTimeline, "/server")]
+pub async fn get_timeline(
+    cx: Scope,
+    timeline: String,
+    before: String,
+    before_id: String,
+    sensitive: bool,
+) -> Result<Result<TimelinePage, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use actix_session::Session;
+    use chrono_humanize::HumanTime;
+    use leptos_actix::extract;
+    use sqlx::types::chrono::{DateTime, Utc};
+    use wordforge_api::{
+        account::{self, UserValidateError},
+        api::timeline::{self, PublicTimelineQuery, TimelineError, TimelineQuery},
+        DbHandle,
+    };
+
+    let (pool, session) = extract(cx, |pool: Data<DbHandle>, session: Session| async move {
+        (pool, session)
+    })
+    .await?;
+
+    let viewer = match account::current_user(pool.app_data(), &session).await {
+        Err(UserValidateError::Unauthorized(_)) => None,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+        Ok(u) => Some(u),
+    };
+
+    let before = match before.as_str() {
+        "" => None,
+        b => match b.parse::<DateTime<Utc>>() {
+            Ok(b) => Some(b),
+            Err(e) => return Ok(Err(e.to_string())),
+        },
+    };
+    let before_id = Some(before_id).filter(|b| !b.is_empty());
+
+    let page = match (timeline.as_str(), viewer) {
+        ("home", Some(owner)) => {
+            let query = TimelineQuery { before, before_id };
+            timeline::home(pool.app_data(), &owner, query).await
+        }
+        ("home", None) => return Ok(Err("Sign in to see your home timeline".to_string())),
+        ("local" | "public", viewer) => {
+            let query = PublicTimelineQuery {
+                before,
+                before_id,
+                local: timeline == "local",
+                sensitive,
+            };
+            timeline::public(pool.app_data(), viewer.as_deref(), query).await
+        }
+        _ => return Ok(Err("Unknown timeline".to_string())),
+    };
+
+    match page {
+        Ok(page) => Ok(Ok(TimelinePage {
+            entries: page
+                .entries
+                .into_iter()
+                .map(|e| TimelineItem {
+                    key: format!(
+                        "{}:{}:{}",
+                        e.kind,
+                        e.chapter
+                            .as_ref()
+                            .map(|c| c.apub_id.as_str())
+                            .unwrap_or(&e.novel.apub_id),
+                        e.booster.as_ref().map(|b| b.apub_id.as_str()).unwrap_or("")
+                    ),
+                    kind: e.kind.to_string(),
+                    published: HumanTime::from(e.published).to_string(),
+                    chapter: e.chapter.map(|c| TimelineChapter {
+                        sequence: c.sequence,
+                        title: c.title,
+                        summary: c.summary,
+                        sensitive: c.sensitive,
+                    }),
+                    booster: e.booster.map(|b| (b.name, b.path)),
+                    novel: NovelSummary::from(e.novel),
+                })
+                .collect(),
+            next: page.next.map(|n| (n.before.to_rfc3339(), n.before_id)),
+        })),
+        Err(TimelineError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    components::{follow::*, shelf::ShelfItem},
+    path::UserViewParams,
+};
+use leptos::*;
+use leptos_icons::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Profile {
+    apub_id: String,
+    handle: String,
+    name: String,
+    summary: String,
+    local: bool,
+    moved_to: Option<String>,
+    novels: Vec<ProfileNovel>,
+    shelves: Vec<ShelfItem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProfileNovel {
+    path: String,
+    title: String,
+    summary: String,
+    role: String,
+    sensitive: bool,
+}
+
+#[component]
+pub fn UserView(cx: Scope) -> impl IntoView {
+    let params = use_params::<UserViewParams>(cx);
+    let name = move || {
+        params
+            .with(|params| params.clone().map(|p| p.name))
+            .unwrap_or_default()
+    };
+    let profile = create_resource(cx, name, move |name| get_profile(cx, name));
+
+    view! { cx,
+        <div class="mx-auto max-w-4xl px-4">
+            <Transition fallback=move || {
+                view! { cx,
+                    <Icon
+                        icon=CgIcon::CgSpinner
+                        class="block dark:stroke-white py-1 w-10 h-10 mx-auto animate-spin pointer-events-none"
+                    />
+                }
+            }>
+                {move || {
+                    profile
+                        .read(cx)
+                        .map(|v| match v {
+                            Err(e) => {
+                                error!("profile: {}", e.to_string());
+                                view! { cx, <p class="p-2">"Something went wrong"</p> }
+                                    .into_view(cx)
+                            }
+                            Ok(Err(e)) => {
+                                view! { cx,
+                                    <Title text="Not found"/>
+                                    <p class="p-2">{e}</p>
+                                }
+                                    .into_view(cx)
+                            }
+                            Ok(Ok(p)) => {
+                                let (apub_id, _) = create_signal(cx, p.apub_id.clone());
+                                view! { cx,
+                                    <Title text=p.name.clone()/>
+                                    <div class="flex flex-col gap-1 p-2">
+                                        <div class="flex flex-row justify-between gap-2">
+                                            <h1 class="text-3xl">{p.name}</h1>
+                                            <FollowButton target=apub_id/>
+                                        </div>
+                                        <a
+                                            href=p.apub_id
+                                            rel="external"
+                                            class="dark:text-gray-400 hover:underline break-all"
+                                        >
+                                            {format!("@{}", p.handle)}
+                                        </a>
+                                        {(!p.local)
+                                            .then(|| {
+                                                view! { cx,
+                                                    <span class="text-sm dark:text-gray-500">
+                                                        "Remote account. Some of its content may be missing here."
+                                                    </span>
+                                                }
+                                            })}
+                                        {p
+                                            .moved_to
+                                            .map(|m| {
+                                                view! { cx,
+                                                    <span class="dark:text-gray-400">
+                                                        "This account has moved to "
+                                                        <a href=m.clone() rel="external" class="hover:underline break-all">
+                                                            {m.clone()}
+                                                        </a>
+                                                    </span>
+                                                }
+                                            })}
+                                        <p class="whitespace-pre-wrap">{p.summary}</p>
+                                    </div>
+                                    <h2 class="p-2 text-2xl">"Novels"</h2>
+                                    <ul class="flex flex-col gap-2 my-2">
+                                        {if p.novels.is_empty() {
+                                            view! { cx, <li class="p-2">"No novels yet"</li> }
+                                                .into_view(cx)
+                                        } else {
+                                            p.novels
+                                                .into_iter()
+                                                .map(|n| {
+                                                    view! { cx,
+                                                        <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                            <A href=n.path class="text-xl hover:underline">
+                                                                {n.title}
+                                                            </A>
+                                                            <span class="text-sm dark:text-gray-400">
+                                                                {n.role}
+                                                                {n.sensitive.then_some(" · Sensitive")}
+                                                            </span>
+                                                            <p class="line-clamp-3">{n.summary}</p>
+                                                        </li>
+                                                    }
+                                                })
+                                                .collect_view(cx)
+                                        }}
+                                    </ul>
+                                    {(!p.shelves.is_empty())
+                                        .then(|| {
+                                            view! { cx,
+                                                <h2 class="p-2 text-2xl">"Reading lists"</h2>
+                                                <ul class="flex flex-col gap-2 my-2">
+                                                    {p
+                                                        .shelves
+                                                        .into_iter()
+                                                        .map(|s| {
+                                                            view! { cx,
+                                                                <li class="flex flex-col dark:bg-gray-800 rounded-xl px-4 py-2">
+                                                                    <A href=format!("/shelves/{}", s.id) class="text-xl hover:underline">
+                                                                        {s.name}
+                                                                    </A>
+                                                                    <span class="text-sm dark:text-gray-400">
+                                                                        {format!("{} novel(s)", s.novels)}
+                                                                    </span>
+                                                                </li>
+                                                            }
+                                                        })
+                                                        .collect_view(cx)}
+                                                </ul>
+                                            }
+                                        })}
+                                }
+                                    .into_view(cx)
+                            }
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+#[server(GetProfile, "/server")]
+pub async fn get_profile(
+    cx: Scope,
+    name: String,
+) -> Result<Result<Profile, String>, ServerFnError> {
+    use activitypub_federation::config::Data;
+    use leptos_actix::extract;
+    use wordforge_api::{
+        api::user::{self, ProfileError},
+        DbHandle,
+    };
+
+    let pool = extract(cx, |pool: Data<DbHandle>| async move { pool }).await?;
+
+    match user::get_profile(&name, &pool).await {
+        Ok(p) => Ok(Ok(Profile {
+            apub_id: p.apub_id,
+            handle: p.handle,
+            name: p.name,
+            summary: p.summary,
+            local: p.local,
+            moved_to: p.moved_to,
+            novels: p
+                .novels
+                .into_iter()
+                .map(|n| ProfileNovel {
+                    path: n.path,
+                    title: n.title,
+                    summary: n.summary,
+                    role: n.role,
+                    sensitive: n.sensitive,
+                })
+                .collect(),
+            shelves: p.shelves.into_iter().map(ShelfItem::from).collect(),
+        })),
+        Err(ProfileError::InternalServerError(e)) => Err(ServerFnError::ServerError(e)),
+        Err(ProfileError::NotFound) => Ok(Err("User not found".to_string())),
+    }
+}